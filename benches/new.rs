@@ -0,0 +1,24 @@
+//! Measures how long it takes to create a large channel, which is mostly
+//! spent initializing its nodes.
+//!
+//! Run with `cargo bench --bench new`.
+
+use std::time::{Duration, Instant};
+
+use atomic_mpmc::channel;
+
+const CAPACITY: usize = 1 << 20;
+const RUNS: u32 = 20;
+
+fn main() {
+    let mut total = Duration::ZERO;
+    for _ in 0..RUNS {
+        let start = Instant::now();
+        let (sender, receiver) = channel::<u64>(CAPACITY);
+        total += start.elapsed();
+
+        drop((sender, receiver));
+    }
+
+    println!("capacity: {CAPACITY}  new: {:>8.2?}", total / RUNS);
+}
@@ -0,0 +1,67 @@
+//! Measures throughput of small values between several producers and
+//! consumers through a small channel, where they keep working on
+//! neighboring slots. Compare runs with and without the `padded` feature to
+//! see what false sharing between the slots costs.
+//!
+//! Run with `cargo bench --bench padded` and
+//! `cargo bench --bench padded --features padded`.
+
+use std::{thread, time::Instant};
+
+use atomic_mpmc::channel;
+
+const VALUES: u64 = 1_000_000;
+
+fn main() {
+    println!(
+        "padded: {}",
+        if cfg!(feature = "padded") {
+            "yes"
+        } else {
+            "no"
+        }
+    );
+
+    for threads in [1, 2, 4] {
+        for capacity in [16, 256] {
+            let (sender, receiver) = channel::<u8>(capacity);
+            let per_thread = VALUES / threads;
+
+            let start = Instant::now();
+            let producers = (0..threads)
+                .map(|_| {
+                    let sender = sender.clone();
+                    thread::spawn(move || {
+                        for i in 0..per_thread {
+                            sender.send(i as u8).unwrap();
+                        }
+                    })
+                })
+                .collect::<Vec<_>>();
+            drop(sender);
+
+            let consumers = (0..threads)
+                .map(|_| {
+                    let receiver = receiver.clone();
+                    thread::spawn(move || receiver.iter().count() as u64)
+                })
+                .collect::<Vec<_>>();
+            drop(receiver);
+
+            for producer in producers {
+                producer.join().unwrap();
+            }
+            let received = consumers
+                .into_iter()
+                .map(|consumer| consumer.join().unwrap())
+                .sum::<u64>();
+            assert_eq!(received, per_thread * threads);
+            let elapsed = start.elapsed();
+
+            println!(
+                "threads: {threads}x{threads}  capacity: {capacity:>4}  per value: {:>8.2?}",
+                elapsed / received as u32
+            );
+        }
+    }
+}
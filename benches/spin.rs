@@ -0,0 +1,65 @@
+//! Compares spin counts for `send_spin`/`recv_spin` in a ping-pong between
+//! two threads, where every message waits for the previous one's reply.
+//! Spinning only pays off with a core for each thread, otherwise it just
+//! holds up the other side. Yielding instead lets the other side run.
+//!
+//! Run with `cargo bench --bench spin`.
+
+use std::{
+    thread,
+    time::{Duration, Instant},
+};
+
+use atomic_mpmc::{channel, Receiver, RecvError};
+
+const ROUND_TRIPS: u32 = 100_000;
+
+/// Waits with `recv_yield` rather than `recv_spin` if `yielding` is set.
+fn recv(receiver: &Receiver<u32>, spins: u32, yielding: bool) -> Result<u32, RecvError> {
+    if yielding {
+        receiver.recv_yield(spins)
+    } else {
+        receiver.recv_spin(spins)
+    }
+}
+
+fn ping_pong(spins: u32, yielding: bool) -> Duration {
+    let (ping, pinged) = channel::<u32>(1);
+    let (pong, ponged) = channel::<u32>(1);
+
+    let echo = thread::spawn(move || {
+        while let Ok(value) = recv(&pinged, spins, yielding) {
+            pong.send_spin(value, spins).unwrap();
+        }
+    });
+
+    let start = Instant::now();
+    for i in 0..ROUND_TRIPS {
+        ping.send_spin(i, spins).unwrap();
+        assert_eq!(recv(&ponged, spins, yielding).unwrap(), i);
+    }
+    let elapsed = start.elapsed();
+
+    drop(ping);
+    echo.join().unwrap();
+
+    elapsed
+}
+
+fn main() {
+    for spins in [0, 10, 100, 1_000, 10_000] {
+        let elapsed = ping_pong(spins, false);
+        println!(
+            "spins: {spins:>6}  round trip: {:>8.0?}",
+            elapsed / ROUND_TRIPS
+        );
+    }
+
+    for yields in [10, 100, 1_000] {
+        let elapsed = ping_pong(yields, true);
+        println!(
+            "yields: {yields:>5}  round trip: {:>8.0?}",
+            elapsed / ROUND_TRIPS
+        );
+    }
+}
@@ -0,0 +1,31 @@
+//! Measures throughput between one producer and one consumer through a
+//! channel which is large enough to rarely fill up, so sends mostly take
+//! the path which doesn't block.
+//!
+//! Run with `cargo bench --bench throughput`.
+
+use std::{thread, time::Instant};
+
+use atomic_mpmc::channel;
+
+const VALUES: u64 = 1_000_000;
+
+fn main() {
+    for capacity in [64, 1024, 16384] {
+        let (sender, receiver) = channel::<u64>(capacity);
+
+        let start = Instant::now();
+        let consumer = thread::spawn(move || receiver.iter().sum::<u64>());
+        for i in 0..VALUES {
+            sender.send(i).unwrap();
+        }
+        drop(sender);
+        assert_eq!(consumer.join().unwrap(), VALUES * (VALUES - 1) / 2);
+        let elapsed = start.elapsed();
+
+        println!(
+            "capacity: {capacity:>6}  per value: {:>8.2?}",
+            elapsed / VALUES as u32
+        );
+    }
+}
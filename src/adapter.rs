@@ -0,0 +1,444 @@
+use core::fmt;
+use sealed::sealed;
+#[cfg(feature = "std")]
+use std::time::Instant;
+
+use crate::{
+    iterator::{self, Recv},
+    sync::Mutex,
+    Iter, RecvError, TryIter,
+};
+
+/// A receiver which transforms every value it receives, created by
+/// [`Receiver::map`](crate::Receiver::map).
+///
+/// Errors, including hang-ups, are passed through as they are.
+#[derive(Debug)]
+pub struct Map<R, F> {
+    receiver: R,
+    f: F,
+}
+
+impl<R, F> Map<R, F> {
+    pub(crate) fn new(receiver: R, f: F) -> Self {
+        Self { receiver, f }
+    }
+}
+
+impl<R: Recv, U, F: Fn(R::Item) -> U> Map<R, F> {
+    /// Receive a value from the channel and transform it. This function will
+    /// block the current thread if the channel is empty.
+    pub fn recv(&self) -> Result<U, RecvError> {
+        self.receiver.recv().map(&self.f)
+    }
+
+    /// Attempt to receive a value from the channel and transform it. This
+    /// function will return `Err(RecvError(ErrorCause::WouldBlock))` if the
+    /// channel is empty.
+    pub fn try_recv(&self) -> Result<U, RecvError> {
+        self.receiver.try_recv().map(&self.f)
+    }
+
+    /// Creates a new iterator over the transformed values of this channel.
+    /// The iterator will block when the channel is empty.
+    pub fn iter(&self) -> Iter<&Self> {
+        Iter::new(self)
+    }
+
+    /// Creates a new iterator over the transformed pending values of this
+    /// channel. The iterator will not block when the channel is empty.
+    pub fn try_iter(&self) -> TryIter<&Self> {
+        TryIter::new(self)
+    }
+
+    /// Transform the values further.
+    pub fn map<V, G: Fn(U) -> V>(self, g: G) -> Map<Self, G> {
+        Map::new(self, g)
+    }
+
+    /// Skip the transformed values which don't match a predicate.
+    pub fn filter<P: Fn(&U) -> bool>(self, predicate: P) -> Filter<Self, P> {
+        Filter::new(self, predicate)
+    }
+
+    /// Transform the values further, skipping the ones which transform into
+    /// `None`.
+    pub fn filter_map<V, G: Fn(U) -> Option<V>>(self, g: G) -> FilterMap<Self, G> {
+        FilterMap::new(self, g)
+    }
+
+    /// Skip the transformed values equal to the one received before.
+    pub fn dedup(self) -> Dedup<Self>
+    where
+        U: PartialEq + Clone,
+    {
+        Dedup::new(self)
+    }
+}
+
+#[sealed]
+impl<R: Recv, U, F: Fn(R::Item) -> U> iterator::Recv for Map<R, F> {
+    type Item = U;
+    fn recv(&self) -> Result<Self::Item, RecvError> {
+        self.recv()
+    }
+
+    fn try_recv(&self) -> Result<Self::Item, RecvError> {
+        self.try_recv()
+    }
+
+    #[cfg(feature = "std")]
+    fn recv_deadline(&self, deadline: Instant) -> Result<Self::Item, RecvError> {
+        self.receiver.recv_deadline(deadline).map(&self.f)
+    }
+}
+
+impl<R: Recv, U, F: Fn(R::Item) -> U> IntoIterator for Map<R, F> {
+    type Item = U;
+    type IntoIter = Iter<Self>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        Iter::new(self)
+    }
+}
+
+/// A receiver which skips the values not matching a predicate, created by
+/// [`Receiver::filter`](crate::Receiver::filter).
+///
+/// Skipped values are received and dropped. Errors, including hang-ups, are
+/// passed through as they are.
+#[derive(Debug)]
+pub struct Filter<R, P> {
+    receiver: R,
+    predicate: P,
+}
+
+impl<R, P> Filter<R, P> {
+    pub(crate) fn new(receiver: R, predicate: P) -> Self {
+        Self {
+            receiver,
+            predicate,
+        }
+    }
+}
+
+impl<R: Recv, P: Fn(&R::Item) -> bool> Filter<R, P> {
+    /// Receive the next matching value from the channel. This function will
+    /// block the current thread until one arrives.
+    pub fn recv(&self) -> Result<R::Item, RecvError> {
+        loop {
+            match self.receiver.recv() {
+                Ok(value) if !(self.predicate)(&value) => {}
+                result => return result,
+            }
+        }
+    }
+
+    /// Attempt to receive the next matching value from the channel. This
+    /// function will return `Err(RecvError(ErrorCause::WouldBlock))` if the
+    /// channel runs empty before one is found.
+    pub fn try_recv(&self) -> Result<R::Item, RecvError> {
+        loop {
+            match self.receiver.try_recv() {
+                Ok(value) if !(self.predicate)(&value) => {}
+                result => return result,
+            }
+        }
+    }
+
+    /// Creates a new iterator over the matching values of this channel.
+    /// The iterator will block when the channel is empty.
+    pub fn iter(&self) -> Iter<&Self> {
+        Iter::new(self)
+    }
+
+    /// Creates a new iterator over the matching pending values of this
+    /// channel. The iterator will not block when the channel is empty.
+    pub fn try_iter(&self) -> TryIter<&Self> {
+        TryIter::new(self)
+    }
+
+    /// Transform the matching values.
+    pub fn map<U, F: Fn(R::Item) -> U>(self, f: F) -> Map<Self, F> {
+        Map::new(self, f)
+    }
+
+    /// Skip the values which don't match another predicate as well.
+    pub fn filter<Q: Fn(&R::Item) -> bool>(self, predicate: Q) -> Filter<Self, Q> {
+        Filter::new(self, predicate)
+    }
+
+    /// Transform the matching values, skipping the ones which transform into
+    /// `None`.
+    pub fn filter_map<U, F: Fn(R::Item) -> Option<U>>(self, f: F) -> FilterMap<Self, F> {
+        FilterMap::new(self, f)
+    }
+
+    /// Skip the matching values equal to the one received before.
+    pub fn dedup(self) -> Dedup<Self>
+    where
+        R::Item: PartialEq + Clone,
+    {
+        Dedup::new(self)
+    }
+}
+
+#[sealed]
+impl<R: Recv, P: Fn(&R::Item) -> bool> iterator::Recv for Filter<R, P> {
+    type Item = R::Item;
+    fn recv(&self) -> Result<Self::Item, RecvError> {
+        self.recv()
+    }
+
+    fn try_recv(&self) -> Result<Self::Item, RecvError> {
+        self.try_recv()
+    }
+
+    #[cfg(feature = "std")]
+    fn recv_deadline(&self, deadline: Instant) -> Result<Self::Item, RecvError> {
+        loop {
+            match self.receiver.recv_deadline(deadline) {
+                Ok(value) if !(self.predicate)(&value) => {}
+                result => return result,
+            }
+        }
+    }
+}
+
+impl<R: Recv, P: Fn(&R::Item) -> bool> IntoIterator for Filter<R, P> {
+    type Item = R::Item;
+    type IntoIter = Iter<Self>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        Iter::new(self)
+    }
+}
+
+/// A receiver which transforms every value it receives, and skips the ones
+/// which transform into `None`, created by
+/// [`Receiver::filter_map`](crate::Receiver::filter_map).
+///
+/// Skipped values are received and dropped. Errors, including hang-ups, are
+/// passed through as they are.
+#[derive(Debug)]
+pub struct FilterMap<R, F> {
+    receiver: R,
+    f: F,
+}
+
+impl<R, F> FilterMap<R, F> {
+    pub(crate) fn new(receiver: R, f: F) -> Self {
+        Self { receiver, f }
+    }
+}
+
+impl<R: Recv, U, F: Fn(R::Item) -> Option<U>> FilterMap<R, F> {
+    /// Receives values with `recv` until one transforms into `Some`.
+    fn next_some(&self, recv: impl Fn(&R) -> Result<R::Item, RecvError>) -> Result<U, RecvError> {
+        loop {
+            if let Some(value) = (self.f)(recv(&self.receiver)?) {
+                return Ok(value);
+            }
+        }
+    }
+
+    /// Receive the next value from the channel which transforms into `Some`.
+    /// This function will block the current thread until one arrives.
+    pub fn recv(&self) -> Result<U, RecvError> {
+        self.next_some(R::recv)
+    }
+
+    /// Attempt to receive the next value from the channel which transforms
+    /// into `Some`. This function will return
+    /// `Err(RecvError(ErrorCause::WouldBlock))` if the channel runs empty
+    /// before one is found.
+    pub fn try_recv(&self) -> Result<U, RecvError> {
+        self.next_some(R::try_recv)
+    }
+
+    /// Creates a new iterator over the transformed values of this channel.
+    /// The iterator will block when the channel is empty.
+    pub fn iter(&self) -> Iter<&Self> {
+        Iter::new(self)
+    }
+
+    /// Creates a new iterator over the transformed pending values of this
+    /// channel. The iterator will not block when the channel is empty.
+    pub fn try_iter(&self) -> TryIter<&Self> {
+        TryIter::new(self)
+    }
+
+    /// Transform the values further.
+    pub fn map<V, G: Fn(U) -> V>(self, g: G) -> Map<Self, G> {
+        Map::new(self, g)
+    }
+
+    /// Skip the transformed values which don't match a predicate.
+    pub fn filter<P: Fn(&U) -> bool>(self, predicate: P) -> Filter<Self, P> {
+        Filter::new(self, predicate)
+    }
+
+    /// Transform the values further, skipping the ones which transform into
+    /// `None`.
+    pub fn filter_map<V, G: Fn(U) -> Option<V>>(self, g: G) -> FilterMap<Self, G> {
+        FilterMap::new(self, g)
+    }
+
+    /// Skip the transformed values equal to the one received before.
+    pub fn dedup(self) -> Dedup<Self>
+    where
+        U: PartialEq + Clone,
+    {
+        Dedup::new(self)
+    }
+}
+
+#[sealed]
+impl<R: Recv, U, F: Fn(R::Item) -> Option<U>> iterator::Recv for FilterMap<R, F> {
+    type Item = U;
+    fn recv(&self) -> Result<Self::Item, RecvError> {
+        self.recv()
+    }
+
+    fn try_recv(&self) -> Result<Self::Item, RecvError> {
+        self.try_recv()
+    }
+
+    #[cfg(feature = "std")]
+    fn recv_deadline(&self, deadline: Instant) -> Result<Self::Item, RecvError> {
+        self.next_some(|receiver| receiver.recv_deadline(deadline))
+    }
+}
+
+impl<R: Recv, U, F: Fn(R::Item) -> Option<U>> IntoIterator for FilterMap<R, F> {
+    type Item = U;
+    type IntoIter = Iter<Self>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        Iter::new(self)
+    }
+}
+
+/// A receiver which skips the values equal to the one received before it,
+/// created by [`Receiver::dedup`](crate::Receiver::dedup).
+///
+/// It keeps a clone of the last value it returned to compare with. Skipped
+/// values are received and dropped. Errors, including hang-ups, are passed
+/// through as they are. Receiving goes through a lock, so that values
+/// received on different threads are still compared in order.
+pub struct Dedup<R: Recv> {
+    receiver: R,
+    last: Mutex<Option<R::Item>>,
+}
+
+impl<R: Recv> Dedup<R> {
+    pub(crate) fn new(receiver: R) -> Self {
+        Self {
+            receiver,
+            last: Mutex::new(None),
+        }
+    }
+}
+
+impl<R: Recv> Dedup<R>
+where
+    R::Item: PartialEq + Clone,
+{
+    /// Receives values with `recv` until one differs from the last one
+    /// returned.
+    fn next_distinct(
+        &self,
+        recv: impl Fn(&R) -> Result<R::Item, RecvError>,
+    ) -> Result<R::Item, RecvError> {
+        let mut last = self.last.lock();
+        loop {
+            let value = recv(&self.receiver)?;
+            if last.as_ref() != Some(&value) {
+                *last = Some(value.clone());
+                return Ok(value);
+            }
+        }
+    }
+
+    /// Receive the next value from the channel which differs from the one
+    /// before. This function will block the current thread until one arrives.
+    pub fn recv(&self) -> Result<R::Item, RecvError> {
+        self.next_distinct(R::recv)
+    }
+
+    /// Attempt to receive the next value from the channel which differs from
+    /// the one before. This function will return
+    /// `Err(RecvError(ErrorCause::WouldBlock))` if the channel runs empty
+    /// before one is found.
+    pub fn try_recv(&self) -> Result<R::Item, RecvError> {
+        self.next_distinct(R::try_recv)
+    }
+
+    /// Creates a new iterator over the distinct values of this channel.
+    /// The iterator will block when the channel is empty.
+    pub fn iter(&self) -> Iter<&Self> {
+        Iter::new(self)
+    }
+
+    /// Creates a new iterator over the distinct pending values of this
+    /// channel. The iterator will not block when the channel is empty.
+    pub fn try_iter(&self) -> TryIter<&Self> {
+        TryIter::new(self)
+    }
+
+    /// Transform the distinct values.
+    pub fn map<U, F: Fn(R::Item) -> U>(self, f: F) -> Map<Self, F> {
+        Map::new(self, f)
+    }
+
+    /// Skip the distinct values which don't match a predicate.
+    pub fn filter<P: Fn(&R::Item) -> bool>(self, predicate: P) -> Filter<Self, P> {
+        Filter::new(self, predicate)
+    }
+
+    /// Transform the distinct values, skipping the ones which transform into
+    /// `None`.
+    pub fn filter_map<U, F: Fn(R::Item) -> Option<U>>(self, f: F) -> FilterMap<Self, F> {
+        FilterMap::new(self, f)
+    }
+}
+
+#[sealed]
+impl<R: Recv> iterator::Recv for Dedup<R>
+where
+    R::Item: PartialEq + Clone,
+{
+    type Item = R::Item;
+    fn recv(&self) -> Result<Self::Item, RecvError> {
+        self.recv()
+    }
+
+    fn try_recv(&self) -> Result<Self::Item, RecvError> {
+        self.try_recv()
+    }
+
+    #[cfg(feature = "std")]
+    fn recv_deadline(&self, deadline: Instant) -> Result<Self::Item, RecvError> {
+        self.next_distinct(|receiver| receiver.recv_deadline(deadline))
+    }
+}
+
+impl<R: Recv> IntoIterator for Dedup<R>
+where
+    R::Item: PartialEq + Clone,
+{
+    type Item = R::Item;
+    type IntoIter = Iter<Self>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        Iter::new(self)
+    }
+}
+
+impl<R: Recv + fmt::Debug> fmt::Debug for Dedup<R> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Dedup")
+            .field("receiver", &self.receiver)
+            .finish_non_exhaustive()
+    }
+}
@@ -0,0 +1,238 @@
+use alloc::{boxed::Box, sync::Arc};
+
+use crate::{sync, waiter, watermark::Watermarks, Channel, Receiver, Sender, WaitStrategy};
+
+/// A builder for configuring a channel before creating it.
+///
+/// This is the most flexible way to create a channel. The [`channel`](crate::channel)
+/// and [`bounded`](crate::bounded) functions are thin wrappers around it.
+///
+/// # Examples
+/// ```
+/// use atomic_mpmc::ChannelBuilder;
+///
+/// let (sender, receiver) = ChannelBuilder::new(4).name("jobs").build::<i32>();
+///
+/// sender.send(1).unwrap();
+/// assert_eq!(receiver.recv().unwrap(), 1);
+/// ```
+#[derive(Debug, Clone)]
+pub struct ChannelBuilder {
+    pub(crate) capacity: usize,
+    pub(crate) name: Option<Arc<str>>,
+    pub(crate) fair: bool,
+    pub(crate) growable: bool,
+    pub(crate) blocking: bool,
+    pub(crate) overflow: Overflow,
+    pub(crate) single: bool,
+    pub(crate) wait_strategy: fn(bool) -> Box<dyn WaitStrategy>,
+    pub(crate) watermarks: Watermarks,
+}
+
+impl ChannelBuilder {
+    /// Creates a new builder for a channel with a buffer of size `capacity`.
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            name: None,
+            fair: false,
+            growable: false,
+            blocking: true,
+            overflow: Overflow::Block,
+            single: false,
+            wait_strategy: waiter::default_strategy,
+            watermarks: Watermarks::default(),
+        }
+    }
+
+    /// Sets the size of the channel's buffer.
+    pub fn capacity(mut self, capacity: usize) -> Self {
+        self.capacity = capacity;
+        self
+    }
+
+    /// Sets a name for the channel. The name is only used for debugging, and
+    /// can be read back with [`Sender::name`] and [`Receiver::name`].
+    pub fn name(mut self, name: impl Into<Arc<str>>) -> Self {
+        self.name = Some(name.into());
+        self
+    }
+
+    /// Sets whether blocked senders are served in the order they arrived.
+    ///
+    /// By default, senders blocked on a full channel race for each freed slot,
+    /// so an unlucky sender can be passed over indefinitely under contention.
+    /// In fair mode, every blocking [`Sender::send`] first waits for its turn
+    /// in a FIFO queue, and [`Sender::try_send`] fails with
+    /// [`ErrorCause::WouldBlock`](crate::ErrorCause::WouldBlock) while anyone
+    /// is queued.
+    ///
+    /// This comes at a cost: blocking sends are serialized, and each one
+    /// takes a lock to hand the turn over, even when the channel has free
+    /// slots. Only enable it if starving a producer is a real problem.
+    pub fn fair(mut self, fair: bool) -> Self {
+        self.fair = fair;
+        self
+    }
+
+    /// Sets whether the channel's buffer can be grown with
+    /// [`Sender::try_grow`].
+    ///
+    /// Every send and receive on a growable channel takes a shared lock, so
+    /// that growing can wait for them to get out of the way. Channels which
+    /// are not growable skip it entirely.
+    pub fn growable(mut self, growable: bool) -> Self {
+        self.growable = growable;
+        self
+    }
+
+    /// Sets whether [`Sender::send`] and [`Receiver::recv`] block.
+    ///
+    /// By default, they block while the channel is full or empty. A channel
+    /// which is not blocking makes them fail right away instead, just like
+    /// [`Sender::try_send`] and [`Receiver::try_recv`], so the policy can be
+    /// set once for the channel rather than at every call site. Iterating
+    /// over such a channel's receiver stops once it runs empty.
+    ///
+    /// Operations which are blocking by definition, like
+    /// [`Sender::reserve`] or [`Receiver::recv_timeout`], are not affected.
+    ///
+    /// # Examples
+    /// ```
+    /// use atomic_mpmc::{ChannelBuilder, ErrorCause};
+    ///
+    /// let (sender, receiver) = ChannelBuilder::new(1).blocking(false).build::<i32>();
+    ///
+    /// sender.send(1).unwrap();
+    /// assert_eq!(sender.send(2).unwrap_err().1, ErrorCause::WouldBlock);
+    /// ```
+    pub fn blocking(mut self, blocking: bool) -> Self {
+        self.blocking = blocking;
+        self
+    }
+
+    /// Sets what [`Sender::send`] does when the channel is full, see
+    /// [`Overflow`]. By default, it blocks.
+    ///
+    /// # Examples
+    /// ```
+    /// use atomic_mpmc::{ChannelBuilder, Overflow};
+    ///
+    /// let (sender, receiver) = ChannelBuilder::new(2)
+    ///     .overflow(Overflow::DropOldest)
+    ///     .build::<i32>();
+    ///
+    /// sender.send_all([1, 2, 3]).unwrap();
+    /// assert_eq!(receiver.try_iter().collect::<Vec<_>>(), [2, 3]);
+    /// ```
+    pub fn overflow(mut self, overflow: Overflow) -> Self {
+        self.overflow = overflow;
+        self
+    }
+
+    /// Sets how blocked senders and receivers wait, see [`WaitStrategy`].
+    ///
+    /// By default, they park with [`BlockWait`](crate::BlockWait). Spinning
+    /// with [`SpinWait`](crate::SpinWait) or yielding with
+    /// [`YieldWait`](crate::YieldWait) wakes them up faster, at the cost of
+    /// keeping the CPU busy while they wait. Without the `std` feature, they
+    /// spin by default, as there is no way to park them.
+    ///
+    /// # Examples
+    /// ```
+    /// use atomic_mpmc::{ChannelBuilder, SpinWait};
+    ///
+    /// let (sender, receiver) = ChannelBuilder::new(1).wait_strategy::<SpinWait>().build::<i32>();
+    ///
+    /// sender.send(1).unwrap();
+    /// assert_eq!(receiver.recv().unwrap(), 1);
+    /// ```
+    pub fn wait_strategy<W: WaitStrategy + 'static>(mut self) -> Self {
+        self.wait_strategy = |signalled| Box::new(W::new(signalled));
+        self
+    }
+
+    /// Sets a callback for when the channel fills up to `level` values, for
+    /// producers to throttle themselves.
+    ///
+    /// The callback is called with the channel's length by the send which
+    /// reached the level. It is only called again once the channel has
+    /// drained down to the low watermark, see [`Self::on_low_watermark`], or
+    /// below `level` if there is none. It runs on the sending thread, so it
+    /// should be quick.
+    ///
+    /// # Examples
+    /// ```
+    /// use atomic_mpmc::ChannelBuilder;
+    /// use std::sync::atomic::{AtomicBool, Ordering};
+    /// use std::sync::Arc;
+    ///
+    /// let full = Arc::new(AtomicBool::new(false));
+    /// let (sender, receiver) = {
+    ///     let full = full.clone();
+    ///     ChannelBuilder::new(4)
+    ///         .on_high_watermark(3, move |_| full.store(true, Ordering::Relaxed))
+    ///         .build::<i32>()
+    /// };
+    ///
+    /// sender.send_all([1, 2, 3]).unwrap();
+    /// assert!(full.load(Ordering::Relaxed));
+    /// ```
+    pub fn on_high_watermark(
+        mut self,
+        level: usize,
+        callback: impl Fn(usize) + Send + Sync + 'static,
+    ) -> Self {
+        self.watermarks.high = Some((level, Arc::new(callback)));
+        self
+    }
+
+    /// Sets a callback for when the channel drains down to `level` values,
+    /// after having reached the high watermark, see
+    /// [`Self::on_high_watermark`]. Without a high watermark, it is called
+    /// once the channel drains down to `level` after having more.
+    ///
+    /// The callback is called with the channel's length by the receive which
+    /// reached the level. It runs on the receiving thread, so it should be
+    /// quick.
+    pub fn on_low_watermark(
+        mut self,
+        level: usize,
+        callback: impl Fn(usize) + Send + Sync + 'static,
+    ) -> Self {
+        self.watermarks.low = Some((level, Arc::new(callback)));
+        self
+    }
+
+    /// Creates the channel, returning its [`Sender`] and [`Receiver`].
+    ///
+    /// # Panics
+    /// Panics if the capacity is zero and the channel is not growable, see
+    /// [`channel`](crate::channel).
+    pub fn build<T>(self) -> (Sender<T>, Receiver<T>) {
+        let channel = sync::Arc::new(Channel::new(self));
+        (Sender::new(channel.clone()), Receiver::new(channel))
+    }
+}
+
+/// What [`Sender::send`] does when the channel is full, set with
+/// [`ChannelBuilder::overflow`]. This also goes for everything which sends
+/// like it, such as [`Sender::send_all`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Overflow {
+    /// Wait for room, unless the channel is not blocking, see
+    /// [`ChannelBuilder::blocking`]. This is the default.
+    Block,
+
+    /// Fail with [`ErrorCause::WouldBlock`](crate::ErrorCause::WouldBlock)
+    /// right away, like [`Sender::try_send`]. Unlike
+    /// [`ChannelBuilder::blocking`], this leaves receiving alone.
+    Reject,
+
+    /// Drop the oldest value in the channel to make room, so receivers only
+    /// get the latest ones. Values receivers already claimed, like with
+    /// [`Receiver::reserve`], are theirs however: if those are all there is,
+    /// the send waits for room after all, or is pending if it is polled,
+    /// like [`Sender::poll_send`].
+    DropOldest,
+}
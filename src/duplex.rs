@@ -0,0 +1,82 @@
+use crate::{Receiver, RecvError, SendError, Sender};
+
+/// Both halves of a channel in one handle, created by
+/// [`duplex`](crate::duplex).
+///
+/// An endpoint counts as both a sender and a receiver, so the channel only
+/// hangs up once every endpoint is dropped. In particular, receiving blocks
+/// for as long as any endpoint is around to send, including the one
+/// receiving.
+#[derive(Debug, Clone)]
+pub struct Endpoint<T> {
+    pub(crate) sender: Sender<T>,
+    pub(crate) receiver: Receiver<T>,
+}
+
+impl<T> Endpoint<T> {
+    pub(crate) fn new(sender: Sender<T>, receiver: Receiver<T>) -> Self {
+        Self { sender, receiver }
+    }
+
+    /// Send a value to the channel. This function will block the current thread
+    /// if the channel is full.
+    ///
+    /// # Examples
+    /// ```
+    /// use atomic_mpmc::duplex;
+    ///
+    /// let endpoint = duplex::<i32>(1);
+    ///
+    /// endpoint.send(1).unwrap();
+    /// ```
+    pub fn send(&self, value: T) -> Result<(), SendError<T>> {
+        self.sender.send(value)
+    }
+
+    /// Attempt to send a value to the channel. This function will return
+    /// `Err(SendError(value, ErrorCause::WouldBlock))` if the channel is full.
+    ///
+    /// # Examples
+    /// ```
+    /// use atomic_mpmc::duplex;
+    ///
+    /// let endpoint = duplex::<i32>(1);
+    ///
+    /// endpoint.send(1).unwrap();
+    /// endpoint.try_send(2).unwrap_err();
+    /// ```
+    pub fn try_send(&self, value: T) -> Result<(), SendError<T>> {
+        self.sender.try_send(value)
+    }
+
+    /// Receive a value from the channel. This function will block the current
+    /// thread if the channel is empty.
+    ///
+    /// # Examples
+    /// ```
+    /// use atomic_mpmc::duplex;
+    ///
+    /// let endpoint = duplex::<i32>(1);
+    ///
+    /// endpoint.send(1).unwrap();
+    /// assert_eq!(endpoint.recv().unwrap(), 1);
+    /// ```
+    pub fn recv(&self) -> Result<T, RecvError> {
+        self.receiver.recv()
+    }
+
+    /// Attempt to receive a value from the channel. This function will return
+    /// `Err(RecvError(ErrorCause::WouldBlock))` if the channel is empty.
+    ///
+    /// # Examples
+    /// ```
+    /// use atomic_mpmc::duplex;
+    ///
+    /// let endpoint = duplex::<i32>(1);
+    ///
+    /// endpoint.try_recv().unwrap_err();
+    /// ```
+    pub fn try_recv(&self) -> Result<T, RecvError> {
+        self.receiver.try_recv()
+    }
+}
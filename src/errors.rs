@@ -1,16 +1,29 @@
-use std::{error::Error, fmt};
+use alloc::{sync::Arc, vec::Vec};
+use core::{error::Error, fmt};
+#[cfg(feature = "std")]
+use std::sync::mpsc;
 
 // for documentation
 #[allow(unused_imports)]
 use super::{Receiver, Sender};
 
 /// Cause of a [`SendError`] or [`RecvError`].
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub enum ErrorCause {
     /// There are no more senders or receivers, and the operation would either discard data or block.
     HungUp,
     /// The channel is empty or full, and the operation would block.
     WouldBlock,
+    /// The channel stayed empty or full until the operation timed out.
+    Timeout,
+    /// The channel was closed with the given reason, and the operation would
+    /// either discard data or block. See [`Sender::close_with`].
+    Closed(Arc<str>),
+    /// The operation was cancelled before it could complete.
+    Cancelled,
+    /// The operation lost a race with another thread, and gave up instead of
+    /// trying again. See [`Sender::try_send_once`].
+    Contended,
 }
 
 impl fmt::Display for ErrorCause {
@@ -18,6 +31,10 @@ impl fmt::Display for ErrorCause {
         match self {
             ErrorCause::HungUp => write!(f, "channel hung up"),
             ErrorCause::WouldBlock => write!(f, "channel would block"),
+            ErrorCause::Timeout => write!(f, "channel timed out"),
+            ErrorCause::Closed(reason) => write!(f, "channel closed: {reason}"),
+            ErrorCause::Cancelled => write!(f, "channel operation cancelled"),
+            ErrorCause::Contended => write!(f, "channel operation contended"),
         }
     }
 }
@@ -25,6 +42,7 @@ impl fmt::Display for ErrorCause {
 /// Error returned by [`Sender::send`].
 ///
 /// It contains the cause of the error, as well as the data that was attempted to be sent.
+#[derive(PartialEq, Eq)]
 pub struct SendError<T>(
     /// The data that was attempted to be sent.
     pub T,
@@ -49,6 +67,30 @@ impl<T> SendError<T> {
     pub fn into_inner(self) -> T {
         self.0
     }
+
+    /// Splits the error into the data that was attempted to be sent and the
+    /// cause of the error.
+    ///
+    /// # Examples
+    /// ```
+    /// use atomic_mpmc::{channel, ErrorCause};
+    ///
+    /// let (sender, receiver) = channel::<i32>(1);
+    /// std::mem::drop(receiver);
+    ///
+    /// let (value, cause) = sender.send(1).unwrap_err().into_parts();
+    /// assert_eq!(value, 1);
+    /// assert_eq!(cause, ErrorCause::HungUp);
+    /// ```
+    pub fn into_parts(self) -> (T, ErrorCause) {
+        (self.0, self.1)
+    }
+
+    /// Creates an error from the data that was attempted to be sent and the
+    /// cause of the error. This is the inverse of [`Self::into_parts`].
+    pub fn from_parts(value: T, cause: ErrorCause) -> Self {
+        Self(value, cause)
+    }
 }
 
 impl<T> fmt::Debug for SendError<T> {
@@ -65,10 +107,27 @@ impl<T> fmt::Display for SendError<T> {
 
 impl<T> Error for SendError<T> {}
 
+#[cfg(feature = "std")]
+impl<T> From<SendError<T>> for mpsc::SendError<T> {
+    fn from(err: SendError<T>) -> Self {
+        mpsc::SendError(err.0)
+    }
+}
+
+#[cfg(feature = "std")]
+impl<T> From<SendError<T>> for mpsc::TrySendError<T> {
+    fn from(err: SendError<T>) -> Self {
+        match err.1 {
+            ErrorCause::WouldBlock | ErrorCause::Contended => mpsc::TrySendError::Full(err.0),
+            _ => mpsc::TrySendError::Disconnected(err.0),
+        }
+    }
+}
+
 /// Error returned by [`Receiver::recv`].
 ///
 /// It contains the cause of the error.
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct RecvError(
     /// The cause of the error.
     pub ErrorCause,
@@ -81,3 +140,154 @@ impl fmt::Display for RecvError {
 }
 
 impl Error for RecvError {}
+
+#[cfg(feature = "std")]
+impl From<RecvError> for mpsc::RecvError {
+    fn from(_: RecvError) -> Self {
+        mpsc::RecvError
+    }
+}
+
+#[cfg(feature = "std")]
+impl From<RecvError> for mpsc::TryRecvError {
+    fn from(err: RecvError) -> Self {
+        match err.0 {
+            ErrorCause::WouldBlock => mpsc::TryRecvError::Empty,
+            _ => mpsc::TryRecvError::Disconnected,
+        }
+    }
+}
+
+/// Error returned by [`Receiver::recv_n`].
+///
+/// It contains the cause of the error, as well as the values received before
+/// it happened.
+#[derive(PartialEq, Eq)]
+pub struct PartialRecvError<T>(
+    /// The values received before the error.
+    pub Vec<T>,
+    /// The cause of the error.
+    pub ErrorCause,
+);
+
+impl<T> PartialRecvError<T> {
+    /// Returns the values received before the error.
+    ///
+    /// # Examples
+    /// ```
+    /// use atomic_mpmc::channel;
+    ///
+    /// let (sender, receiver) = channel::<i32>(2);
+    /// sender.send(1).unwrap();
+    /// drop(sender);
+    ///
+    /// assert_eq!(receiver.recv_n(2).unwrap_err().into_inner(), [1]);
+    /// ```
+    pub fn into_inner(self) -> Vec<T> {
+        self.0
+    }
+
+    /// Splits the error into the values received before it and its cause.
+    ///
+    /// # Examples
+    /// ```
+    /// use atomic_mpmc::{channel, ErrorCause};
+    ///
+    /// let (sender, receiver) = channel::<i32>(2);
+    /// sender.send(1).unwrap();
+    /// drop(sender);
+    ///
+    /// let (values, cause) = receiver.recv_n(2).unwrap_err().into_parts();
+    /// assert_eq!(values, [1]);
+    /// assert_eq!(cause, ErrorCause::HungUp);
+    /// ```
+    pub fn into_parts(self) -> (Vec<T>, ErrorCause) {
+        (self.0, self.1)
+    }
+}
+
+impl<T> fmt::Debug for PartialRecvError<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("PartialRecvError")
+            .field(&self.0.len())
+            .field(&self.1)
+            .finish()
+    }
+}
+
+impl<T> fmt::Display for PartialRecvError<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "RecvError after {} values: {}", self.0.len(), self.1)
+    }
+}
+
+impl<T> Error for PartialRecvError<T> {}
+
+impl<T> From<PartialRecvError<T>> for RecvError {
+    fn from(err: PartialRecvError<T>) -> Self {
+        RecvError(err.1)
+    }
+}
+
+/// Error returned by [`Receiver::recv_timeout`].
+///
+/// Unlike [`RecvError`], it can only be caused by a timeout, or by the
+/// channel having no senders left.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RecvTimeoutError {
+    /// The channel stayed empty until the operation timed out.
+    Timeout,
+    /// The channel is empty, and all senders have hung up or the channel was
+    /// closed.
+    Disconnected,
+}
+
+impl fmt::Display for RecvTimeoutError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            RecvTimeoutError::Timeout => write!(f, "channel timed out"),
+            RecvTimeoutError::Disconnected => write!(f, "channel disconnected"),
+        }
+    }
+}
+
+impl Error for RecvTimeoutError {}
+
+#[cfg(feature = "std")]
+impl From<RecvTimeoutError> for mpsc::RecvTimeoutError {
+    fn from(err: RecvTimeoutError) -> Self {
+        match err {
+            RecvTimeoutError::Timeout => mpsc::RecvTimeoutError::Timeout,
+            RecvTimeoutError::Disconnected => mpsc::RecvTimeoutError::Disconnected,
+        }
+    }
+}
+
+impl From<RecvError> for RecvTimeoutError {
+    fn from(err: RecvError) -> Self {
+        match err.0 {
+            ErrorCause::Timeout => RecvTimeoutError::Timeout,
+            _ => RecvTimeoutError::Disconnected,
+        }
+    }
+}
+
+/// Error returned by [`Sender::try_grow`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GrowError {
+    /// The channel was not built to be growable.
+    NotGrowable,
+    /// The new capacity would overflow a `usize`.
+    CapacityOverflow,
+}
+
+impl fmt::Display for GrowError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            GrowError::NotGrowable => write!(f, "channel is not growable"),
+            GrowError::CapacityOverflow => write!(f, "channel capacity overflow"),
+        }
+    }
+}
+
+impl Error for GrowError {}
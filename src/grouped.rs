@@ -0,0 +1,352 @@
+//! A channel with consumer groups, for when several independent consumers
+//! each need to see every value, like subscribers of an event stream.
+//!
+//! Every group has a ring buffer of its own, and every value sent is cloned
+//! into each of them. Within a group, receivers share a cursor and take
+//! turns like the receivers of a regular channel, so every value is received
+//! once per group. The groups see the values in the same order.
+//!
+//! Sending waits for room in every group, so the slowest group holds back
+//! the senders. A group whose receivers all hung up is left out, until a
+//! receiver joins it again with [`Receiver::group`].
+//!
+//! # Examples
+//! ```
+//! use atomic_mpmc::grouped;
+//!
+//! let (sender, receiver) = grouped::channel::<i32>(4);
+//! let audit = receiver.group("audit");
+//!
+//! sender.send(1).unwrap();
+//! sender.send(2).unwrap();
+//!
+//! assert_eq!(receiver.try_iter().collect::<Vec<_>>(), [1, 2]);
+//! assert_eq!(audit.try_iter().collect::<Vec<_>>(), [1, 2]);
+//! ```
+
+use alloc::{sync::Arc, vec::Vec};
+use core::fmt;
+
+use crate::{
+    sync::{self, atomic::AtomicUsize, Mutex, Ordering, RwLock},
+    Channel, ChannelBuilder, ErrorCause, RecvError, SendError, TryIter,
+};
+
+/// The name of the group the receiver returned by [`channel`] is in.
+pub const DEFAULT_GROUP: &str = "default";
+
+struct Group<T> {
+    name: Arc<str>,
+    channel: sync::Arc<Channel<T>>,
+
+    /// Stands in for all senders of the grouped channel, dropped once the
+    /// last of them is.
+    sender: Option<crate::Sender<T>>,
+}
+
+struct Shared<T> {
+    capacity: usize,
+    groups: RwLock<Vec<Group<T>>>,
+    senders: AtomicUsize,
+
+    /// Held while sending, so every group gets the values in the same order.
+    sending: Mutex<()>,
+}
+
+impl<T> Shared<T> {
+    /// Returns a receiver in the group, creating the group if there is none
+    /// of this name yet.
+    fn join(self: &Arc<Self>, name: &str) -> Receiver<T> {
+        let mut groups = self.groups.write();
+        let group = match groups.iter().position(|group| &*group.name == name) {
+            Some(index) => &groups[index],
+            None => {
+                let channel = sync::Arc::new(Channel::new(ChannelBuilder::new(self.capacity)));
+
+                // A group created after all senders hung up starts out hung up.
+                let sender = (self.senders.load(Ordering::Acquire) > 0)
+                    .then(|| crate::Sender::new(channel.clone()));
+                groups.push(Group {
+                    name: name.into(),
+                    channel,
+                    sender,
+                });
+                groups.last().unwrap()
+            }
+        };
+
+        Receiver {
+            shared: self.clone(),
+            name: group.name.clone(),
+            receiver: crate::Receiver::new(group.channel.clone()),
+        }
+    }
+
+    /// Returns a sender for every group with receivers left.
+    fn live_senders(&self) -> Vec<crate::Sender<T>> {
+        self.groups
+            .read()
+            .iter()
+            .filter_map(|group| group.sender.clone())
+            .filter(|sender| !sender.is_disconnected())
+            .collect()
+    }
+}
+
+/// The sending half of a grouped channel, created by [`channel`].
+pub struct Sender<T> {
+    shared: Arc<Shared<T>>,
+}
+
+impl<T: Clone> Sender<T> {
+    /// Send a value to every group. This function will block the current
+    /// thread until there is room for it in all of them, and return
+    /// `Err(SendError(value, ErrorCause::HungUp))` if no group has receivers
+    /// left.
+    ///
+    /// The value is only delivered once there is room in every group, so
+    /// the groups never get out of step.
+    ///
+    /// # Examples
+    /// ```
+    /// use atomic_mpmc::grouped;
+    ///
+    /// let (sender, receiver) = grouped::channel::<i32>(1);
+    ///
+    /// sender.send(1).unwrap();
+    /// ```
+    pub fn send(&self, value: T) -> Result<(), SendError<T>> {
+        let _sending = self.shared.sending.lock();
+        let senders = self.shared.live_senders();
+
+        // Groups which hang up in the meantime are left out.
+        let permits = senders
+            .iter()
+            .filter_map(|sender| sender.reserve().ok())
+            .collect::<Vec<_>>();
+        Self::deliver(permits, value)
+    }
+
+    /// Attempt to send a value to every group. This function will return
+    /// `Err(SendError(value, ErrorCause::WouldBlock))` if any of them is
+    /// full, in which case the value is sent to none of them. It fails the
+    /// same way while another thread is in [`Sender::send`], which may be
+    /// waiting for room.
+    ///
+    /// # Examples
+    /// ```
+    /// use atomic_mpmc::grouped;
+    ///
+    /// let (sender, receiver) = grouped::channel::<i32>(1);
+    /// let other = receiver.group("other");
+    ///
+    /// sender.try_send(1).unwrap();
+    /// assert_eq!(receiver.recv().unwrap(), 1);
+    ///
+    /// // The other group is still full.
+    /// sender.try_send(2).unwrap_err();
+    /// ```
+    pub fn try_send(&self, value: T) -> Result<(), SendError<T>> {
+        // Sending holds the lock for as long as it blocks, so don't wait on it.
+        let Some(_sending) = self.shared.sending.try_lock() else {
+            return Err(SendError(value, ErrorCause::WouldBlock));
+        };
+        let senders = self.shared.live_senders();
+
+        let mut permits = Vec::with_capacity(senders.len());
+        for sender in &senders {
+            match sender.try_reserve() {
+                Ok(permit) => permits.push(permit),
+                Err(SendError((), ErrorCause::WouldBlock)) => {
+                    return Err(SendError(value, ErrorCause::WouldBlock));
+                }
+                // Hung up in the meantime, so leave it out.
+                Err(_) => {}
+            }
+        }
+        Self::deliver(permits, value)
+    }
+
+    fn deliver(mut permits: Vec<crate::Permit<'_, T>>, value: T) -> Result<(), SendError<T>> {
+        let Some(last) = permits.pop() else {
+            return Err(SendError(value, ErrorCause::HungUp));
+        };
+
+        for permit in permits {
+            permit.send(value.clone());
+        }
+        last.send(value);
+        Ok(())
+    }
+}
+
+impl<T> Drop for Sender<T> {
+    fn drop(&mut self) {
+        let senders = self.shared.senders.fetch_sub(1, Ordering::AcqRel);
+        debug_assert_ne!(senders, 0, "dropped more senders than were created");
+        if senders == 1 {
+            // Hang up on every group, which wakes up their blocked receivers.
+            for group in self.shared.groups.write().iter_mut() {
+                group.sender = None;
+            }
+        }
+    }
+}
+
+impl<T> Clone for Sender<T> {
+    fn clone(&self) -> Self {
+        self.shared.senders.fetch_add(1, Ordering::Relaxed);
+        Self {
+            shared: self.shared.clone(),
+        }
+    }
+}
+
+impl<T> fmt::Debug for Sender<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let groups = self.shared.groups.read();
+        f.debug_tuple("Sender")
+            .field(&groups.iter().map(|group| &group.name).collect::<Vec<_>>())
+            .finish()
+    }
+}
+
+/// The receiving half of a grouped channel, created by [`channel`], in one
+/// of its groups.
+pub struct Receiver<T> {
+    shared: Arc<Shared<T>>,
+    name: Arc<str>,
+    receiver: crate::Receiver<T>,
+}
+
+impl<T> Receiver<T> {
+    /// Returns a receiver in the group called `name`, which receives every
+    /// value independently of the other groups. Receivers of the same group
+    /// share its values, like clones of a regular receiver.
+    ///
+    /// A group which doesn't exist yet is created, and only gets the values
+    /// sent from then on.
+    ///
+    /// # Examples
+    /// ```
+    /// use atomic_mpmc::grouped;
+    ///
+    /// let (sender, receiver) = grouped::channel::<i32>(2);
+    /// let first = receiver.group("metrics");
+    /// let second = receiver.group("metrics");
+    ///
+    /// sender.send(1).unwrap();
+    /// sender.send(2).unwrap();
+    ///
+    /// assert_eq!(first.recv().unwrap(), 1);
+    /// assert_eq!(second.recv().unwrap(), 2);
+    /// assert_eq!(receiver.try_iter().collect::<Vec<_>>(), [1, 2]);
+    /// ```
+    pub fn group(&self, name: &str) -> Receiver<T> {
+        self.shared.join(name)
+    }
+
+    /// Returns the name of the group this receiver is in.
+    ///
+    /// # Examples
+    /// ```
+    /// use atomic_mpmc::grouped;
+    ///
+    /// let (sender, receiver) = grouped::channel::<i32>(1);
+    ///
+    /// assert_eq!(receiver.name(), grouped::DEFAULT_GROUP);
+    /// assert_eq!(receiver.group("other").name(), "other");
+    /// ```
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Receive the next value of this receiver's group. This function will
+    /// block the current thread if the group has no values left.
+    ///
+    /// # Examples
+    /// ```
+    /// use atomic_mpmc::grouped;
+    ///
+    /// let (sender, receiver) = grouped::channel::<i32>(1);
+    ///
+    /// sender.send(1).unwrap();
+    /// assert_eq!(receiver.recv().unwrap(), 1);
+    /// ```
+    pub fn recv(&self) -> Result<T, RecvError> {
+        self.receiver.recv()
+    }
+
+    /// Attempt to receive the next value of this receiver's group. This
+    /// function will return `Err(RecvError(ErrorCause::WouldBlock))` if the
+    /// group has no values left.
+    ///
+    /// # Examples
+    /// ```
+    /// use atomic_mpmc::grouped;
+    ///
+    /// let (sender, receiver) = grouped::channel::<i32>(1);
+    ///
+    /// receiver.try_recv().unwrap_err();
+    /// ```
+    pub fn try_recv(&self) -> Result<T, RecvError> {
+        self.receiver.try_recv()
+    }
+
+    /// Creates an iterator over the values of this receiver's group. For more
+    /// information, see [`crate::Receiver::iter`].
+    pub fn iter(&self) -> crate::Iter<&crate::Receiver<T>> {
+        self.receiver.iter()
+    }
+
+    /// Creates an iterator over the pending values of this receiver's group.
+    /// For more information, see [`crate::Receiver::try_iter`].
+    pub fn try_iter(&self) -> TryIter<&crate::Receiver<T>> {
+        self.receiver.try_iter()
+    }
+}
+
+impl<T> Clone for Receiver<T> {
+    fn clone(&self) -> Self {
+        Self {
+            shared: self.shared.clone(),
+            name: self.name.clone(),
+            receiver: self.receiver.clone(),
+        }
+    }
+}
+
+impl<T> fmt::Debug for Receiver<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("Receiver")
+            .field(&self.name)
+            .field(&self.receiver)
+            .finish()
+    }
+}
+
+/// Creates a multi-producer, multi-consumer channel with consumer groups,
+/// each with a buffer of size `capacity`. The receiver is in a group called
+/// [`DEFAULT_GROUP`], and more join with [`Receiver::group`]. For more
+/// information, see the [module documentation](self).
+///
+/// # Examples
+/// ```
+/// use atomic_mpmc::grouped;
+///
+/// let (sender, receiver) = grouped::channel::<i32>(10);
+///
+/// sender.send(1).unwrap();
+/// assert_eq!(receiver.recv().unwrap(), 1);
+/// ```
+pub fn channel<T: Clone>(capacity: usize) -> (Sender<T>, Receiver<T>) {
+    let shared = Arc::new(Shared {
+        capacity,
+        groups: RwLock::new(Vec::new()),
+        senders: AtomicUsize::new(1),
+        sending: Mutex::new(()),
+    });
+
+    let receiver = shared.join(DEFAULT_GROUP);
+    (Sender { shared }, receiver)
+}
@@ -1,15 +1,23 @@
+#[cfg(feature = "std")]
+use alloc::{vec, vec::Vec};
+use core::iter::FusedIterator;
 use sealed::sealed;
-use std::iter::FusedIterator;
+#[cfg(feature = "std")]
+use std::time::{Duration, Instant};
 
-use crate::{Receiver, RecvError};
+#[cfg(feature = "std")]
+use crate::ErrorCause;
+use crate::{Permit, Receiver, RecvError, Sender};
 
 /// A helper trait for implementing [`Iter`].
-#[sealed]
+#[sealed(pub(crate))]
 #[doc(hidden)]
 pub trait Recv {
     type Item;
     fn recv(&self) -> Result<Self::Item, RecvError>;
     fn try_recv(&self) -> Result<Self::Item, RecvError>;
+    #[cfg(feature = "std")]
+    fn recv_deadline(&self, deadline: Instant) -> Result<Self::Item, RecvError>;
 }
 
 #[sealed]
@@ -22,11 +30,16 @@ impl<T> Recv for Receiver<T> {
     fn try_recv(&self) -> Result<Self::Item, RecvError> {
         self.try_recv()
     }
+
+    #[cfg(feature = "std")]
+    fn recv_deadline(&self, deadline: Instant) -> Result<Self::Item, RecvError> {
+        self.0.read_until(Some(deadline))
+    }
 }
 
 #[sealed]
-impl<T> Recv for &Receiver<T> {
-    type Item = T;
+impl<R: Recv> Recv for &R {
+    type Item = R::Item;
     fn recv(&self) -> Result<Self::Item, RecvError> {
         (**self).recv()
     }
@@ -34,6 +47,11 @@ impl<T> Recv for &Receiver<T> {
     fn try_recv(&self) -> Result<Self::Item, RecvError> {
         (**self).try_recv()
     }
+
+    #[cfg(feature = "std")]
+    fn recv_deadline(&self, deadline: Instant) -> Result<Self::Item, RecvError> {
+        (**self).recv_deadline(deadline)
+    }
 }
 
 /// Iterator over the values of a receiver.
@@ -89,3 +107,203 @@ impl<R: Recv> Iterator for TryIter<R> {
         }
     }
 }
+
+/// An iterator over the values which were pending in a channel when it was
+/// created by [`Receiver::try_iter_snapshot`]. The iterator will return `None`
+/// once it reaches those values' end, or when the channel is hung up or empty.
+#[derive(Debug)]
+pub struct SnapshotIter<'a, T> {
+    receiver: Option<&'a Receiver<T>>,
+    end: usize,
+}
+
+impl<'a, T> SnapshotIter<'a, T> {
+    pub(super) fn new(receiver: &'a Receiver<T>, end: usize) -> Self {
+        Self {
+            receiver: Some(receiver),
+            end,
+        }
+    }
+}
+
+impl<T> Iterator for SnapshotIter<'_, T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self.receiver.map(|r| r.0.try_read_before(Some(self.end))) {
+            Some(Ok(v)) => Some(v),
+            Some(Err(_)) => {
+                self.receiver = None;
+                None
+            }
+            None => None,
+        }
+    }
+}
+
+impl<T> FusedIterator for SnapshotIter<'_, T> {}
+
+/// An owning iterator over the values which were pending in a channel when
+/// it was created by [`Receiver::drain_into_iter`]. The iterator will return
+/// `None`, and drop the receiver, once it reaches those values' end, or when
+/// the channel is hung up or empty.
+#[derive(Debug)]
+pub struct DrainIter<T> {
+    receiver: Option<Receiver<T>>,
+    end: usize,
+}
+
+impl<T> DrainIter<T> {
+    pub(super) fn new(receiver: Receiver<T>, end: usize) -> Self {
+        Self {
+            receiver: Some(receiver),
+            end,
+        }
+    }
+}
+
+impl<T> Iterator for DrainIter<T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self
+            .receiver
+            .as_ref()
+            .map(|r| r.0.try_read_before(Some(self.end)))
+        {
+            Some(Ok(v)) => Some(v),
+            Some(Err(_)) => {
+                self.receiver = None;
+                None
+            }
+            None => None,
+        }
+    }
+}
+
+impl<T> FusedIterator for DrainIter<T> {}
+
+/// An iterator over batches of values of a channel, created by
+/// [`Receiver::batches`]. The iterator will return `None` when the channel is
+/// hung up and empty.
+#[cfg(feature = "std")]
+#[derive(Debug)]
+pub struct Batches<R> {
+    receiver: Option<R>,
+    max: usize,
+    window: Duration,
+}
+
+#[cfg(feature = "std")]
+impl<R> Batches<R> {
+    pub(super) fn new(receiver: R, max: usize, window: Duration) -> Self {
+        Self {
+            receiver: Some(receiver),
+            max,
+            window,
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl<R: Recv> Iterator for Batches<R> {
+    type Item = Vec<R::Item>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let receiver = self.receiver.as_ref()?;
+
+        let first = match receiver.recv() {
+            Ok(v) => v,
+            Err(_) => {
+                self.receiver = None;
+                return None;
+            }
+        };
+
+        let deadline = Instant::now() + self.window;
+        let mut batch = vec![first];
+        while batch.len() < self.max {
+            match receiver.recv_deadline(deadline) {
+                Ok(v) => batch.push(v),
+                Err(RecvError(ErrorCause::Timeout)) => break,
+                Err(_) => {
+                    self.receiver = None;
+                    break;
+                }
+            }
+        }
+
+        Some(batch)
+    }
+}
+
+#[cfg(feature = "std")]
+impl<R: Recv> FusedIterator for Batches<R> {}
+
+/// An iterator over the values of a channel, created by
+/// [`Receiver::iter_idle_timeout`]. The iterator will return `None` once no
+/// value arrived for its timeout, or when the channel is hung up.
+#[cfg(feature = "std")]
+#[derive(Debug)]
+pub struct IdleIter<R> {
+    receiver: Option<R>,
+    timeout: Duration,
+}
+
+#[cfg(feature = "std")]
+impl<R> IdleIter<R> {
+    pub(super) fn new(receiver: R, timeout: Duration) -> Self {
+        Self {
+            receiver: Some(receiver),
+            timeout,
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl<R: Recv> Iterator for IdleIter<R> {
+    type Item = R::Item;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let receiver = self.receiver.as_ref()?;
+        match receiver.recv_deadline(Instant::now() + self.timeout) {
+            Ok(v) => Some(v),
+            Err(_) => {
+                self.receiver = None;
+                None
+            }
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl<R: Recv> FusedIterator for IdleIter<R> {}
+
+/// An iterator over slots reserved in a channel, created by
+/// [`Sender::writable_iter`]. The iterator will block when the channel is
+/// full, and return `None` when the channel is hung up.
+#[derive(Debug)]
+pub struct Permits<'a, T>(Option<&'a Sender<T>>);
+
+impl<'a, T> Permits<'a, T> {
+    pub(super) fn new(sender: &'a Sender<T>) -> Self {
+        Self(Some(sender))
+    }
+}
+
+impl<'a, T> Iterator for Permits<'a, T> {
+    type Item = Permit<'a, T>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self.0.map(Sender::reserve) {
+            Some(Ok(permit)) => Some(permit),
+            Some(Err(_)) => {
+                self.0 = None;
+                None
+            }
+            None => None,
+        }
+    }
+}
+
+impl<T> FusedIterator for Permits<'_, T> {}
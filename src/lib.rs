@@ -5,56 +5,166 @@
 //! buffer. The [`Sender`] and [`Receiver`] types are used to send and
 //! receive values, and they implement [`Send`], [`Sync`], and [`Clone`].
 //!
-//! The [`channel`] function is used to create a channel.
+//! The [`channel`] function is used to create a channel. For more control
+//! over the channel's configuration, use a [`ChannelBuilder`].
+//! When there is only ever one producer and one consumer, [`channel_single`]
+//! creates a channel whose handles can't be cloned. The [`local`] module has
+//! a channel for when everything lives on a single thread.
+//! The [`priority`] module has a channel whose values are received by
+//! priority level, rather than just in order. The [`grouped`] module has a
+//! channel whose consumer groups each receive every value.
+//! The [`mpsc`] module mirrors the API of [`std::sync::mpsc`], to make
+//! switching over easier.
+//!
+//! # Features
+//!
+//! - `std` (default): everything which needs an operating system, like
+//!   parking blocked threads, timeouts, and the [`mpsc`] module. Without it,
+//!   the crate is `no_std` and only needs `alloc`: blocked threads spin, see
+//!   [`SpinWait`], and nothing times out.
+//! - `async`: implements `futures::Sink` for [`Sender`], and adds
+//!   `Sender::poll_send` and `Receiver::poll_recv` for futures of its own,
+//!   and `Receiver::into_stream` for a `futures::Stream` of the values.
+//!   Implies `std`.
+//! - `metrics`: keeps track of the channel's peak occupancy and of how often
+//!   it blocks, see `Sender::high_water_mark` and `Sender::block_count`.
+//! - `timing`: records when each value was sent, so `Receiver::recv_timed`
+//!   can tell how long it waited in the channel. Implies `std`.
+//! - `receipts`: records which values were taken out of the channel, so
+//!   `Sender::send_tracked` can hand out a [`Receipt`] for when a value is
+//!   received. Adds a counter to every slot. Implies `std`.
+//! - `padded`: aligns every slot of the buffer to a cache line of 64 bytes,
+//!   so threads working on neighboring slots don't keep taking the line
+//!   away from each other. This helps small values under heavy contention,
+//!   but every slot takes up at least 64 bytes, so a channel of `u8` with a
+//!   capacity of 1024 needs 64 KiB rather than 2 KiB.
+//! - `seqcst`: makes every atomic operation `SeqCst`, for audits and model
+//!   checking, where one total order of operations is easier to reason
+//!   about. This only slows the channel down, so it is not meant for
+//!   production.
 
 #![warn(missing_docs)]
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
+
+extern crate alloc;
+
+use alloc::{sync::Arc, vec::Vec};
+#[cfg(feature = "async")]
+use core::task::{Context, Poll};
+use core::{cell::UnsafeCell, fmt, hint, mem::MaybeUninit, num::NonZeroUsize, ptr, time::Duration};
+#[cfg(feature = "std")]
+use std::time::Instant;
 
-use std::{
-    cell::UnsafeCell,
-    mem::MaybeUninit,
-    ptr,
-    sync::{
-        atomic::{AtomicBool, AtomicUsize, Ordering},
-        Arc,
-    },
+mod sync;
+use sync::{
+    atomic::{AtomicBool, AtomicUsize},
+    cell, Mutex, OnceLock, Ordering, RwLock, RwLockReadGuard,
 };
 
 mod waiter;
-use waiter::Waiter;
+#[cfg(feature = "std")]
+pub use waiter::{BlockWait, YieldWait};
+pub use waiter::{Deadline, SpinWait, WaitStrategy};
+use waiter::{Turnstile, Waiter};
+
+mod watermark;
+use watermark::WatermarkState;
 
 mod errors;
-pub use errors::{ErrorCause, RecvError, SendError};
+pub use errors::{ErrorCause, GrowError, PartialRecvError, RecvError, RecvTimeoutError, SendError};
 
 mod iterator;
-pub use iterator::{Iter, TryIter};
+#[cfg(feature = "std")]
+pub use iterator::{Batches, IdleIter};
+pub use iterator::{DrainIter, Iter, Permits, SnapshotIter, TryIter};
+
+mod builder;
+pub use builder::{ChannelBuilder, Overflow};
+
+mod adapter;
+pub use adapter::{Dedup, Filter, FilterMap, Map};
+
+mod permit;
+pub use permit::{PeekGuard, Permit, PermitBatch, RecvPermit, RequeueGuard};
 
-#[cfg(test)]
+mod unique;
+pub use unique::{UniqueReceiver, UniqueSender};
+
+mod duplex;
+pub use duplex::Endpoint;
+
+mod scoped;
+pub use scoped::{ScopedChannel, ScopedReceiver, ScopedSender};
+
+pub mod grouped;
+pub mod local;
+#[cfg(feature = "std")]
+pub mod mpsc;
+pub mod priority;
+
+#[cfg(feature = "async")]
+mod sink;
+#[cfg(feature = "async")]
+mod stream;
+#[cfg(feature = "async")]
+pub use stream::RecvStream;
+
+#[cfg(feature = "receipts")]
+mod receipt;
+#[cfg(feature = "receipts")]
+pub use receipt::Receipt;
+
+#[cfg(feature = "std")]
+mod select;
+#[cfg(feature = "std")]
+pub use select::Select;
+
+#[cfg(all(test, feature = "std", not(loom)))]
 mod tests;
 
+#[cfg(all(test, loom))]
+mod tests_loom;
+
+#[cfg(all(test, not(feature = "std")))]
+mod tests_no_std;
+
+/// A slot in the channel's buffer.
+///
+/// For zero-sized `T`, `data` takes up no space, so the node is only as large
+/// as its `hot` flag, unless the `timing` or `receipts` features add to it. The
+/// `padded` feature rounds it up to a cache line, so that neighboring nodes
+/// are never on the same one.
+#[cfg_attr(feature = "padded", repr(align(64)))]
 struct Node<T> {
-    data: MaybeUninit<UnsafeCell<T>>,
+    data: cell::UnsafeCell<MaybeUninit<T>>,
 
     /// Whether data is initialized.
     /// Stupid name, but I'm not changing it.
     hot: AtomicBool,
+
+    /// When data was written, guarded by `hot` like data.
+    #[cfg(feature = "timing")]
+    sent: cell::UnsafeCell<Option<Instant>>,
+
+    /// Position of the last value taken out of the node, see [`Receipt`].
+    #[cfg(feature = "receipts")]
+    done: AtomicUsize,
 }
 
 impl<T> Default for Node<T> {
     fn default() -> Self {
         Node {
-            data: MaybeUninit::uninit(),
+            data: cell::UnsafeCell::new(MaybeUninit::uninit()),
             hot: Default::default(),
+            #[cfg(feature = "timing")]
+            sent: cell::UnsafeCell::new(None),
+            // Nothing was taken out yet, not even the value at position 0.
+            #[cfg(feature = "receipts")]
+            done: AtomicUsize::new(usize::MAX),
         }
     }
 }
 
-impl<T> Node<T> {
-    #[inline(always)]
-    fn data(&self) -> *mut T {
-        UnsafeCell::raw_get(self.data.as_ptr())
-    }
-}
-
 impl<T> core::fmt::Debug for Node<T> {
     fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         f.debug_struct("Node")
@@ -69,15 +179,18 @@ impl<T> Drop for Node<T> {
             // SAFETY: This is safe because hot is only ever set to true
             // after the data is initialized.
             if self.hot.load(Ordering::Relaxed) {
-                ptr::drop_in_place(self.data());
+                self.data.with_mut(|data| (*data).assume_init_drop());
             }
         }
     }
 }
 
-#[derive(Debug)]
 struct Channel<T> {
-    data: Vec<Node<T>>,
+    /// Only ever replaced by [`Channel::grow`], see [`Channel::enter`].
+    data: UnsafeCell<Vec<Node<T>>>,
+
+    /// Shown in the debug output, see [`Sender::name`].
+    name: Option<Arc<str>>,
 
     write: AtomicUsize,
     read: AtomicUsize,
@@ -85,22 +198,74 @@ struct Channel<T> {
     receivers: AtomicUsize,
     senders: AtomicUsize,
 
+    /// Reason the channel was closed with, if it was.
+    closed: OnceLock<Arc<str>>,
+
+    /// Whether a sender declared that nothing more will be sent, see
+    /// [`Sender::finish`].
+    finished: AtomicBool,
+
+    /// Callbacks for when the length crosses a watermark, if any.
+    watermarks: Option<WatermarkState>,
+
     writable: Waiter,
     readable: Waiter,
+
+    /// Queue of blocked writers, only present in fair mode.
+    turnstile: Option<Turnstile>,
+
+    /// Held shared while accessing nodes and exclusively while replacing
+    /// them, only present in growable channels.
+    gate: Option<RwLock<()>>,
+
+    /// Whether blocking writes and reads block, or fail when they would.
+    blocking: bool,
+
+    /// What blocking writes do when the channel is full.
+    overflow: Overflow,
+
+    /// Whether there is only ever one writer and one reader, who can move
+    /// the counters without racing anyone.
+    single: bool,
+
+    /// Positions which were claimed by a writer, but never written to.
+    skipped: Mutex<Vec<usize>>,
+
+    /// Positions behind the read position which were claimed by a reader,
+    /// but never read from, to be claimed again before any other.
+    returned: Mutex<Vec<usize>>,
+    returned_len: AtomicUsize,
+
+    /// Largest number of values ever buffered at once.
+    #[cfg(feature = "metrics")]
+    high_water_mark: AtomicUsize,
+
+    /// Number of times a write or read had to block.
+    #[cfg(feature = "metrics")]
+    write_blocks: sync::atomic::AtomicU64,
+    #[cfg(feature = "metrics")]
+    read_blocks: sync::atomic::AtomicU64,
 }
 
 impl<T> Channel<T> {
     // The members of this struct should all get inlined into the public API.
 
     #[inline(always)]
-    fn new(capacity: usize) -> Self {
-        let mut data = Vec::with_capacity(capacity);
-        for _ in 0..capacity {
-            data.push(Default::default());
-        }
+    fn new(builder: ChannelBuilder) -> Self {
+        // Sends on it would block forever, so fail loudly instead.
+        assert!(
+            builder.capacity > 0 || builder.growable,
+            "a channel with a capacity of zero never has room for a value, \
+             use `channel_nonzero`, or make it growable"
+        );
+
+        let mut data = Vec::with_capacity(builder.capacity);
+        data.resize_with(builder.capacity, Node::default);
 
         Self {
-            data,
+            data: UnsafeCell::new(data),
+
+            name: builder.name,
 
             write: Default::default(),
             read: Default::default(),
@@ -108,14 +273,221 @@ impl<T> Channel<T> {
             receivers: Default::default(),
             senders: Default::default(),
 
-            writable: Waiter::new(true),
-            readable: Waiter::new(false),
+            closed: OnceLock::new(),
+            finished: AtomicBool::new(false),
+
+            watermarks: WatermarkState::new(builder.watermarks),
+
+            writable: Waiter::new((builder.wait_strategy)(true)),
+            readable: Waiter::new((builder.wait_strategy)(false)),
+
+            turnstile: builder.fair.then(Turnstile::default),
+
+            gate: builder.growable.then(RwLock::default),
+
+            blocking: builder.blocking,
+            overflow: builder.overflow,
+            single: builder.single,
+
+            skipped: Mutex::new(Vec::new()),
+
+            returned: Mutex::new(Vec::new()),
+            returned_len: Default::default(),
+
+            #[cfg(feature = "metrics")]
+            high_water_mark: Default::default(),
+
+            #[cfg(feature = "metrics")]
+            write_blocks: Default::default(),
+            #[cfg(feature = "metrics")]
+            read_blocks: Default::default(),
+        }
+    }
+
+    /// Writes values into the nodes up front, before anyone else can use the
+    /// channel. Stops once all nodes are hot.
+    fn prefill(&mut self, iter: impl IntoIterator<Item = T>) {
+        let nodes = self.data.get_mut();
+        let mut write = 0;
+        for (node, value) in nodes.iter_mut().zip(iter) {
+            node.data = cell::UnsafeCell::new(MaybeUninit::new(value));
+            node.hot = AtomicBool::new(true);
+            #[cfg(feature = "timing")]
+            {
+                node.sent = cell::UnsafeCell::new(Some(Instant::now()));
+            }
+            write += 1;
+        }
+
+        self.write = AtomicUsize::new(write);
+    }
+
+    /// Must be called before accessing the nodes, and held until done with
+    /// them. It can be entered recursively, as a [`Permit`] keeps it entered
+    /// while its owner goes on using the channel.
+    #[inline(always)]
+    fn enter(&self) -> Option<RwLockReadGuard<'_, ()>> {
+        self.gate.as_ref().map(RwLock::read_recursive)
+    }
+
+    #[inline(always)]
+    fn nodes(&self) -> &[Node<T>] {
+        unsafe {
+            // SAFETY: The nodes are only replaced while the gate is held
+            // exclusively, and they are only accessed from within the gate.
+            &*self.data.get()
+        }
+    }
+
+    #[inline(always)]
+    fn capacity(&self) -> usize {
+        self.nodes().len()
+    }
+
+    #[inline(always)]
+    fn len(&self) -> usize {
+        let read = self.read.load(Ordering::Relaxed);
+        let write = self.write.load(Ordering::Relaxed);
+        let returned = self.returned_len.load(Ordering::Relaxed);
+
+        // The loads race with other threads, and a claim given back in
+        // between can leave the write position behind the read one.
+        let len = write.wrapping_sub(read).wrapping_add(returned) as isize;
+        (len.max(0) as usize).min(self.capacity())
+    }
+
+    #[inline(always)]
+    fn is_full(&self) -> bool {
+        let _gate = self.enter();
+        self.writable_index().is_none()
+    }
+
+    #[inline(always)]
+    fn is_empty(&self) -> bool {
+        let _gate = self.enter();
+        if self.returned_len.load(Ordering::Acquire) > 0 {
+            return false;
+        }
+
+        loop {
+            let read = self.read.load(Ordering::Acquire);
+            let write = self.write.load(Ordering::Acquire);
+
+            if write.wrapping_sub(read) as isize <= 0 {
+                return true;
+            }
+            if self.node(read).hot.load(Ordering::Acquire) {
+                return false;
+            }
+            if !self.take_skipped(read) {
+                return true;
+            }
+        }
+    }
+
+    /// Blocks until the channel is not full, or no one can read anymore.
+    fn wait_writable(&self) {
+        self.writable
+            .wait_for(|| !self.is_full() || self.check_receivers(()).is_err());
+    }
+
+    /// Blocks until the channel is not empty, or no one can write anymore.
+    fn wait_readable(&self) {
+        self.readable
+            .wait_for(|| !self.is_empty() || self.check_senders().is_err());
+    }
+
+    /// Blocks until every value has been taken out, or no one can read
+    /// anymore.
+    fn flush(&self) -> Result<(), SendError<()>> {
+        self.flush_until(|| self.drained_before(self.write.load(Ordering::Acquire)))
+    }
+
+    /// Blocks until every value written before the `end` position has been
+    /// taken out, or no one can read anymore. Values written since don't
+    /// count.
+    fn flush_before(&self, end: usize) -> Result<(), SendError<()>> {
+        self.flush_until(|| self.drained_before(end))
+    }
+
+    /// Whether every value written before the `end` position was taken out.
+    /// Moving the read position past a value only claims it, so this looks
+    /// at the nodes the last lap before `end` went through instead.
+    fn drained_before(&self, end: usize) -> bool {
+        let _gate = self.enter();
+        if end.wrapping_sub(self.read.load(Ordering::Acquire)) as isize > 0 {
+            return false;
+        }
+
+        // A node which is not hot was taken out of, or skipped. A hot one may
+        // hold a later value instead, once a writer claimed it a lap later:
+        // writers only claim nodes which were taken out of.
+        let write = self.write.load(Ordering::Acquire);
+        let capacity = self.capacity();
+        (1..=capacity.min(end)).all(|back| {
+            let position = end.wrapping_sub(back);
+            !self.node(position).hot.load(Ordering::Acquire)
+                || write.wrapping_sub(position) > capacity
+        })
+    }
+
+    fn flush_until(&self, drained: impl Fn() -> bool) -> Result<(), SendError<()>> {
+        self.writable
+            .wait_for(|| drained() || self.check_receivers(()).is_err());
+
+        if drained() {
+            Ok(())
+        } else {
+            self.check_receivers(())
+        }
+    }
+
+    /// Counts a new sender handle. Called once nothing can panic anymore
+    /// while creating it, so every count is matched by a handle to drop.
+    #[inline(always)]
+    fn add_sender(&self) {
+        self.senders.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Uncounts a dropped sender handle, and wakes up all blocked receivers
+    /// if it was the last, so they can see the hang up.
+    #[inline(always)]
+    fn remove_sender(&self) {
+        let senders = self.senders.fetch_sub(1, Ordering::Release);
+        debug_assert_ne!(senders, 0, "dropped more senders than were created");
+        if senders == 1 {
+            self.readable.set();
+        }
+    }
+
+    /// Counts a new receiver handle, see [`Channel::add_sender`].
+    #[inline(always)]
+    fn add_receiver(&self) {
+        self.receivers.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Uncounts a dropped receiver handle, and wakes up all blocked senders
+    /// if it was the last, so they can see the hang up.
+    #[inline(always)]
+    fn remove_receiver(&self) {
+        let receivers = self.receivers.fetch_sub(1, Ordering::Release);
+        debug_assert_ne!(receivers, 0, "dropped more receivers than were created");
+        if receivers == 1 {
+            self.writable.set();
         }
     }
 
+    /// Whether all senders hung up, or declared that they are done.
+    #[inline(always)]
+    fn senders_gone(&self) -> bool {
+        self.senders.load(Ordering::Acquire) == 0 || self.finished.load(Ordering::Acquire)
+    }
+
     #[inline(always)]
     fn check_senders(&self) -> Result<(), RecvError> {
-        if self.senders.load(Ordering::Relaxed) == 0 {
+        if let Some(reason) = self.closed.get() {
+            Err(RecvError(ErrorCause::Closed(reason.clone())))
+        } else if self.senders_gone() {
             Err(RecvError(ErrorCause::HungUp))
         } else {
             Ok(())
@@ -123,8 +495,12 @@ impl<T> Channel<T> {
     }
 
     #[inline(always)]
-    fn check_receivers(&self, value: T) -> Result<T, SendError<T>> {
-        if self.receivers.load(Ordering::Relaxed) == 0 {
+    fn check_receivers<V>(&self, value: V) -> Result<V, SendError<V>> {
+        if let Some(reason) = self.closed.get() {
+            Err(SendError(value, ErrorCause::Closed(reason.clone())))
+        } else if self.receivers.load(Ordering::Acquire) == 0
+            || self.finished.load(Ordering::Acquire)
+        {
             Err(SendError(value, ErrorCause::HungUp))
         } else {
             Ok(value)
@@ -132,245 +508,2834 @@ impl<T> Channel<T> {
     }
 
     #[inline(always)]
-    fn get_node<'a>(&'a self, from: &AtomicUsize) -> &'a Node<T> {
+    fn node(&self, index: usize) -> &Node<T> {
         unsafe {
             // SAFETY: The index is always in bounds, because of the modulo.
-            self.data
-                .get_unchecked(from.fetch_add(1, Ordering::AcqRel) % self.data.len())
+            let nodes = self.nodes();
+            nodes.get_unchecked(index % nodes.len())
         }
     }
 
+    /// Returns the write position, if its node is free to be claimed.
     #[inline(always)]
-    fn try_node<'a>(&'a self, from: &AtomicUsize) -> (&'a Node<T>, usize) {
-        let index = from.load(Ordering::Acquire);
+    fn writable_index(&self) -> Option<usize> {
+        loop {
+            let write = self.write.load(Ordering::Acquire);
+            let read = self.read.load(Ordering::Acquire);
 
-        (
-            unsafe {
-                // SAFETY: The index is always in bounds, because of the modulo.
-                self.data.get_unchecked(index % self.data.len())
-            },
-            index,
-        )
+            // A node a whole lap ahead of the readers is not free, even if it
+            // is not hot: its writer may not have gotten around to writing to it.
+            if write.wrapping_sub(read) < self.capacity()
+                && !self.node(write).hot.load(Ordering::Acquire)
+            {
+                return Some(write);
+            }
+
+            // The node may be hot, or the read position past it, because
+            // other threads wrote to it and read from it since we loaded the
+            // write position. Then it moved on, try again...
+            if self.write.load(Ordering::Acquire) != write {
+                continue;
+            }
+
+            // A skipped position at the front may be all that's in the way.
+            if self.capacity() == 0
+                || self.node(read).hot.load(Ordering::Acquire)
+                || !self.take_skipped(read)
+            {
+                return None;
+            }
+        }
     }
 
+    /// Claims the node at the write position, which must then be either
+    /// filled or released. Returns `None` if the channel is full.
     #[inline(always)]
-    fn write(&self, value: T) -> Result<(), SendError<T>> {
-        let value = self.check_receivers(value)?;
-        self.writable.wait();
+    fn claim_write(&self) -> Option<(&Node<T>, usize)> {
+        loop {
+            let index = self.writable_index()?;
+
+            if self.single {
+                self.write.store(index.wrapping_add(1), Ordering::Release);
+                return Some((self.node(index), index));
+            }
 
-        let node = self.get_node(&self.write);
+            if self
+                .write
+                .compare_exchange(
+                    index,
+                    index.wrapping_add(1),
+                    Ordering::AcqRel,
+                    Ordering::Relaxed,
+                )
+                .is_ok()
+            {
+                return Some((self.node(index), index));
+            }
 
-        if node.hot.load(Ordering::Acquire) {
-            self.writable.reset();
-            self.writable.wait();
+            // A thread stole the node, try again...
         }
+    }
 
+    /// Fills a node claimed by [`Channel::claim_write`].
+    ///
+    /// # Safety
+    /// The node must have been claimed, and not filled or released since.
+    #[inline(always)]
+    unsafe fn fill(&self, node: &Node<T>, value: T) {
         unsafe {
-            // SAFETY: The node is not hot, so it is safe to write to it.
-            ptr::write(node.data(), value);
+            // SAFETY: The node is claimed, so no one else is writing to it,
+            // and it is not hot, so no one is reading from it.
+            node.data.with_mut(|data| (*data).write(value));
+            #[cfg(feature = "timing")]
+            node.sent.with_mut(|sent| *sent = Some(Instant::now()));
         }
 
         node.hot.store(true, Ordering::Release);
-        self.readable.set();
 
-        Ok(())
+        if let Some(watermarks) = &self.watermarks {
+            watermarks.written(self.len());
+        }
+
+        #[cfg(feature = "metrics")]
+        self.high_water_mark
+            .fetch_max(self.len(), Ordering::Relaxed);
+
+        self.readable.set();
+        self.set_displacing();
     }
 
+    /// Wakes up writers waiting for a value to drop, see
+    /// [`Channel::write_displacing`]. Called wherever a value becomes ready
+    /// to be claimed.
     #[inline(always)]
-    fn try_write(&self, value: T) -> Result<(), SendError<T>> {
-        let value = self.check_receivers(value)?;
+    fn set_displacing(&self) {
+        if self.overflow == Overflow::DropOldest {
+            self.writable.set();
+        }
+    }
+
+    /// Non-blocking write of as many values as there is room for, claiming
+    /// runs of free nodes at once. Returns the values which didn't fit.
+    fn try_write_n(&self, values: Vec<T>) -> Vec<T> {
+        // Don't cut in front of queued writers.
+        if self.check_receivers(()).is_err()
+            || self.turnstile.as_ref().is_some_and(Turnstile::is_busy)
+        {
+            return values;
+        }
+
+        let _gate = self.enter();
+        let mut values = values.into_iter();
+        while values.len() > 0 {
+            let Some(write) = self.writable_index() else {
+                break;
+            };
+            let read = self.read.load(Ordering::Acquire);
+
+            // Count the free nodes from the write position on, see
+            // `writable_index`.
+            let free = self.capacity().saturating_sub(write.wrapping_sub(read));
+            let mut count = 0;
+            while count < values.len()
+                && count < free
+                && !self
+                    .node(write.wrapping_add(count))
+                    .hot
+                    .load(Ordering::Acquire)
+            {
+                count += 1;
+            }
+
+            let end = write.wrapping_add(count);
+            if count == 0 {
+                // The write position moved on since we looked, try again...
+                continue;
+            } else if self.single {
+                self.write.store(end, Ordering::Release);
+            } else if self
+                .write
+                .compare_exchange(write, end, Ordering::AcqRel, Ordering::Relaxed)
+                .is_err()
+            {
+                // A thread stole some of the nodes, try again...
+                continue;
+            }
+
+            for (index, value) in (0..count).zip(&mut values) {
+                unsafe {
+                    // SAFETY: The nodes were claimed above.
+                    self.fill(self.node(write.wrapping_add(index)), value);
+                }
+            }
+        }
+
+        values.collect()
+    }
+
+    /// Non-blocking write which merges the value into the last one written
+    /// if the channel is full, see [`Sender::send_coalesce`].
+    fn try_write_coalesce(&self, value: T, merge: impl Fn(&mut T, T)) -> Result<(), SendError<T>> {
+        let gate = self.enter();
+        let mut value = value;
         loop {
-            let node = self.try_node(&self.write);
+            match self.try_write(value) {
+                Err(SendError(v, ErrorCause::WouldBlock)) => value = v,
+                result => return result,
+            }
+
+            let read = self.read.load(Ordering::Acquire);
+            let write = self.write.load(Ordering::Acquire);
 
-            if node.0.hot.load(Ordering::Acquire) {
-                self.writable.reset();
-                // Return error when the channel is full
-                return Err(SendError(value, ErrorCause::WouldBlock));
+            // Nothing to merge into, so wait for room instead. Leave the gate
+            // first, or the channel could never grow to make room.
+            if write == read || self.capacity() == 0 {
+                drop(gate);
+                return self.write(value);
             }
 
-            if let Err(_) = self.write.compare_exchange(
-                node.1,
-                node.1 + 1,
-                Ordering::Release,
-                Ordering::Relaxed,
-            ) {
-                // A thread stole the node, try again...
+            // Claim every buffered node at once, so no reader gets to the last
+            // one while it is being merged into. A node which is not hot yet
+            // belongs to a writer which is still filling it.
+            let count = write.wrapping_sub(read);
+            if count > self.capacity()
+                || !(0..count).all(|i| self.node(read.wrapping_add(i)).hot.load(Ordering::Acquire))
+                || self
+                    .read
+                    .compare_exchange(read, write, Ordering::AcqRel, Ordering::Relaxed)
+                    .is_err()
+            {
+                // A thread took or wrote something since we looked, try again...
                 continue;
             }
 
+            /// Gives back the claimed nodes, even if merging panics.
+            struct Unclaim<'a, T>(&'a Channel<T>, usize, usize);
+            impl<T> Drop for Unclaim<'_, T> {
+                fn drop(&mut self) {
+                    let Self(channel, read, write) = *self;
+
+                    // Readers only move the read position past the write one
+                    // if another writer sent something in the meantime, which
+                    // misuse can do. Then the nodes are claimed again before
+                    // any other, like in `unclaim_read`. They stay hot either
+                    // way, so no writer can claim them in the meantime.
+                    if channel
+                        .read
+                        .compare_exchange(write, read, Ordering::AcqRel, Ordering::Relaxed)
+                        .is_err()
+                    {
+                        let count = write.wrapping_sub(read);
+                        channel
+                            .returned
+                            .lock()
+                            .extend((0..count).map(|i| read.wrapping_add(i)));
+                        channel.returned_len.fetch_add(count, Ordering::Release);
+                    }
+                }
+            }
+
+            let unclaim = Unclaim(self, read, write);
             unsafe {
-                // SAFETY: The node is not hot, so it is safe to write to it.
-                ptr::write(node.0.data(), value);
+                // SAFETY: The node is hot and claimed, so it holds a value
+                // nobody else is reading.
+                self.node(write.wrapping_sub(1))
+                    .data
+                    .with_mut(|data| merge((*data).assume_init_mut(), value));
             }
+            drop(unclaim);
 
-            node.0.hot.store(true, Ordering::Release);
             self.readable.set();
-
             return Ok(());
         }
     }
 
-    #[inline(always)]
-    fn read(&self) -> Result<T, RecvError> {
-        self.readable.wait();
-
-        let node = self.get_node(&self.read);
-
-        if !node.hot.load(Ordering::Acquire) {
-            self.check_senders()?;
-            self.readable.reset();
-            self.readable.wait();
+    /// Gives up a node claimed by [`Channel::claim_write`] without filling
+    /// it. If no node was claimed after it, it is simply unclaimed, otherwise
+    /// it is skipped over once it gets to the front.
+    fn release(&self, index: usize) {
+        if self
+            .write
+            .compare_exchange(
+                index.wrapping_add(1),
+                index,
+                Ordering::AcqRel,
+                Ordering::Relaxed,
+            )
+            .is_err()
+        {
+            self.skipped.lock().push(index);
+            self.readable.set();
         }
 
-        let value = unsafe {
-            // SAFETY: The node is hot, so it is safe to read from it.
-            ptr::read(node.data())
+        self.writable.set();
+    }
+
+    /// Moves the read position past the given one, if it was skipped.
+    fn take_skipped(&self, index: usize) -> bool {
+        let mut skipped = self.skipped.lock();
+        let Some(i) = skipped.iter().position(|&s| s == index) else {
+            return false;
         };
-        node.hot.store(false, Ordering::Release);
+
+        // Only ever moved past skipped positions under the lock, so this
+        // can't fail.
+        let _ = self.read.compare_exchange(
+            index,
+            index.wrapping_add(1),
+            Ordering::AcqRel,
+            Ordering::Relaxed,
+        );
+        skipped.swap_remove(i);
+        drop(skipped);
+
         self.writable.set();
+        true
+    }
 
-        Ok(value)
+    #[inline(always)]
+    fn reserve(&self) -> Result<Permit<'_, T>, SendError<()>> {
+        let _turn = self.turnstile.as_ref().map(Turnstile::enter);
+
+        #[cfg(feature = "metrics")]
+        let mut blocked = false;
+        loop {
+            match self.reserve_once() {
+                Err(SendError((), ErrorCause::WouldBlock)) => {}
+                result => return result,
+            }
+
+            // Prepare before checking again, so a slot freed in between
+            // is either seen by the check or sets the waiter.
+            let sleep = self.writable.prepare();
+            match self.reserve_once() {
+                Err(SendError((), ErrorCause::WouldBlock)) => {}
+                result => return result,
+            }
+
+            // Only count the first wait, not every spurious wakeup.
+            #[cfg(feature = "metrics")]
+            if !core::mem::replace(&mut blocked, true) {
+                self.write_blocks.fetch_add(1, Ordering::Relaxed);
+            }
+
+            sleep.wait();
+        }
     }
 
     #[inline(always)]
-    fn try_read(&self) -> Result<T, RecvError> {
+    fn try_reserve(&self) -> Result<Permit<'_, T>, SendError<()>> {
+        self.check_receivers(())?;
+
+        // Don't cut in front of queued writers.
+        if self.turnstile.as_ref().is_some_and(Turnstile::is_busy) {
+            return Err(SendError((), ErrorCause::WouldBlock));
+        }
+
+        self.reserve_once()
+    }
+
+    /// Non-blocking claim of `count` consecutive nodes from the write
+    /// position, see [`Sender::try_reserve_many`].
+    fn try_reserve_many(&self, count: usize) -> Result<PermitBatch<'_, T>, SendError<()>> {
+        self.check_receivers(())?;
+
+        // Don't cut in front of queued writers.
+        if self.turnstile.as_ref().is_some_and(Turnstile::is_busy) {
+            return Err(SendError((), ErrorCause::WouldBlock));
+        }
+
+        let gate = self.enter();
+        if count == 0 {
+            let write = self.write.load(Ordering::Acquire);
+            return Ok(PermitBatch::new(self, write, write, gate));
+        }
+
         loop {
-            let node = self.try_node(&self.read);
+            let Some(write) = self.writable_index() else {
+                return Err(SendError((), ErrorCause::WouldBlock));
+            };
+            let read = self.read.load(Ordering::Acquire);
 
-            if !node.0.hot.load(Ordering::Acquire) {
-                self.check_senders()?;
-                self.readable.reset();
-                // Return error when the channel is empty
-                return Err(RecvError(ErrorCause::WouldBlock));
+            // All of the nodes must be free, see `writable_index`.
+            let free = self.capacity().saturating_sub(write.wrapping_sub(read));
+            if free < count
+                || (0..count).any(|i| self.node(write.wrapping_add(i)).hot.load(Ordering::Acquire))
+            {
+                if self.write.load(Ordering::Acquire) != write {
+                    // The write position moved on since we looked, try again...
+                    continue;
+                }
+                return Err(SendError((), ErrorCause::WouldBlock));
             }
 
-            if let Err(_) =
-                self.read
-                    .compare_exchange(node.1, node.1 + 1, Ordering::Release, Ordering::Relaxed)
+            let end = write.wrapping_add(count);
+            if self.single {
+                self.write.store(end, Ordering::Release);
+            } else if self
+                .write
+                .compare_exchange(write, end, Ordering::AcqRel, Ordering::Relaxed)
+                .is_err()
             {
-                // A thread stole the node, try again...
+                // A thread stole some of the nodes, try again...
                 continue;
             }
 
-            let value = unsafe {
-                // SAFETY: The node is hot, so it is safe to read from it.
-                ptr::read(node.0.data())
-            };
-            node.0.hot.store(false, Ordering::Release);
-            self.writable.set();
+            return Ok(PermitBatch::new(self, write, end, gate));
+        }
+    }
 
-            return Ok(value);
+    #[inline(always)]
+    fn reserve_once(&self) -> Result<Permit<'_, T>, SendError<()>> {
+        self.check_receivers(())?;
+
+        let gate = self.enter();
+        match self.claim_write() {
+            Some((node, index)) => Ok(Permit::new(self, node, index, gate)),
+            // Return error when the channel is full
+            None => Err(SendError((), ErrorCause::WouldBlock)),
         }
     }
-}
 
-unsafe impl<T: Send> Send for Channel<T> {}
-unsafe impl<T: Send> Sync for Channel<T> {}
+    #[inline(always)]
+    fn write(&self, value: T) -> Result<(), SendError<T>> {
+        self.write_positioned(value).map(drop)
+    }
 
-/// A sender for a MPMC channel.
-///
-/// This struct is created by the [`channel`] function. It provides methods for
-/// sending data to the channel.
-#[derive(Debug)]
-pub struct Sender<T>(Arc<Channel<T>>);
+    /// Write which follows the overflow policy, like [`Channel::write`], and
+    /// returns the position and the ring slot the value was written to.
+    #[inline(always)]
+    fn write_positioned(&self, value: T) -> Result<(usize, usize), SendError<T>> {
+        match self.overflow {
+            Overflow::Block if self.blocking => {}
+            Overflow::Block | Overflow::Reject => return self.try_write_positioned(value),
+            Overflow::DropOldest => return self.write_displacing(value),
+        }
 
-impl<T> Sender<T> {
-    fn new(channel: Arc<Channel<T>>) -> Self {
-        channel.senders.fetch_add(1, Ordering::Relaxed);
-        Self(channel)
+        match self.reserve() {
+            Ok(permit) => Ok(permit.send_positioned(value)),
+            Err(SendError((), cause)) => Err(SendError(value, cause)),
+        }
     }
 
-    /// Send a value to the channel. This function will block the current thread
-    /// if the channel is full.
-    ///
-    /// # Examples
-    /// ```
-    /// use atomic_mpmc::channel;
-    ///
-    /// let (sender, receiver) = channel::<i32>(1);
-    ///
-    /// sender.send(1).unwrap();
-    /// ```
-    pub fn send(&self, value: T) -> Result<(), SendError<T>> {
-        self.0.write(value)
+    /// Write which drops the oldest value to make room if the channel is
+    /// full, see [`Overflow::DropOldest`].
+    fn write_displacing(&self, value: T) -> Result<(usize, usize), SendError<T>> {
+        let mut value = value;
+        loop {
+            match self.try_write_displacing(value) {
+                Err(SendError(v, ErrorCause::WouldBlock)) => value = v,
+                result => return result,
+            }
+
+            // Every value is claimed, so wait until one is taken, or given
+            // back, or a writer is done with the node at the front.
+            self.writable.wait_for(|| {
+                !self.is_full() || !self.is_empty() || self.check_receivers(()).is_err()
+            });
+        }
     }
 
-    /// Attempt to send a value to the channel. This function will return
-    /// `Err(SendError(value, ErrorCause::WouldBlock))` if the channel is full.
+    /// Non-blocking write which drops the oldest value to make room if the
+    /// channel is full. Only fails with [`ErrorCause::WouldBlock`] if every
+    /// value in the channel is claimed.
+    fn try_write_displacing(&self, value: T) -> Result<(usize, usize), SendError<T>> {
+        let mut value = value;
+        loop {
+            match self.try_write_positioned(value) {
+                Err(SendError(v, ErrorCause::WouldBlock)) => value = v,
+                result => return result,
+            }
+
+            // If a receiver took the oldest value first, there is room now
+            // anyway, try again...
+            match self.claim_read(None) {
+                Ok(permit) => permit.discard(),
+                Err(_) => return Err(SendError(value, ErrorCause::WouldBlock)),
+            }
+        }
+    }
+
+    /// Non-blocking write which follows the overflow policy, for polling.
+    /// Unlike [`Channel::write`], it never blocks, not even to wait for a
+    /// value to drop.
+    #[cfg(feature = "async")]
+    fn try_write_overflowing(&self, value: T) -> Result<(), SendError<T>> {
+        match self.overflow {
+            Overflow::DropOldest => self.try_write_displacing(value).map(drop),
+            Overflow::Block | Overflow::Reject => self.try_write(value),
+        }
+    }
+
+    #[inline(always)]
+    fn try_write(&self, value: T) -> Result<(), SendError<T>> {
+        self.try_write_positioned(value).map(drop)
+    }
+
+    #[inline(always)]
+    fn try_write_positioned(&self, value: T) -> Result<(usize, usize), SendError<T>> {
+        match self.try_reserve() {
+            Ok(permit) => Ok(permit.send_positioned(value)),
+            Err(SendError((), cause)) => Err(SendError(value, cause)),
+        }
+    }
+
+    /// Non-blocking write which tries to claim the write position only once,
+    /// see [`Sender::try_send_once`].
+    fn try_write_once(&self, value: T) -> Result<(), SendError<T>> {
+        let value = self.check_receivers(value)?;
+
+        // Don't cut in front of queued writers.
+        if self.turnstile.as_ref().is_some_and(Turnstile::is_busy) {
+            return Err(SendError(value, ErrorCause::WouldBlock));
+        }
+
+        let _gate = self.enter();
+        let Some(index) = self.writable_index() else {
+            return Err(SendError(value, ErrorCause::WouldBlock));
+        };
+
+        if self.single {
+            self.write.store(index.wrapping_add(1), Ordering::Release);
+        } else if self
+            .write
+            .compare_exchange(
+                index,
+                index.wrapping_add(1),
+                Ordering::AcqRel,
+                Ordering::Relaxed,
+            )
+            .is_err()
+        {
+            return Err(SendError(value, ErrorCause::Contended));
+        }
+
+        unsafe {
+            // SAFETY: The node was claimed above.
+            self.fill(self.node(index), value);
+        }
+        Ok(())
+    }
+
+    #[inline(always)]
+    fn read(&self) -> Result<T, RecvError> {
+        if !self.blocking {
+            return self.try_read();
+        }
+
+        self.read_until(None)
+    }
+
+    /// Blocking read which gives up with [`ErrorCause::Timeout`] once the
+    /// deadline, if any, passes.
+    #[inline(always)]
+    fn read_until(&self, deadline: Option<Deadline>) -> Result<T, RecvError> {
+        self.read_cancellable(deadline, None)
+    }
+
+    /// Blocking read which gives up with [`ErrorCause::Timeout`] once the
+    /// deadline, if any, passes, and with [`ErrorCause::Cancelled`] once the
+    /// cancel flag, if any, is set.
+    #[inline(always)]
+    fn read_cancellable(
+        &self,
+        deadline: Option<Deadline>,
+        cancel: Option<(&AtomicBool, Duration)>,
+    ) -> Result<T, RecvError> {
+        self.reserve_read(deadline, cancel).map(RecvPermit::recv)
+    }
+
+    /// Blocking claim of the node at the read position, see
+    /// [`Channel::read_cancellable`]. Nothing wakes us up when the cancel flag
+    /// is set, so it is checked after parking for at most the given interval.
+    #[inline(always)]
+    fn reserve_read(
+        &self,
+        deadline: Option<Deadline>,
+        cancel: Option<(&AtomicBool, Duration)>,
+    ) -> Result<RecvPermit<'_, T>, RecvError> {
+        #[cfg(feature = "metrics")]
+        let mut blocked = false;
+        loop {
+            match self.claim_read(None) {
+                Err(RecvError(ErrorCause::WouldBlock)) => {}
+                result => return result,
+            }
+
+            // Prepare before checking again, so a value written in between
+            // is either seen by the check or sets the waiter.
+            let sleep = self.readable.prepare();
+            match self.claim_read(None) {
+                Err(RecvError(ErrorCause::WouldBlock)) => {}
+                result => return result,
+            }
+
+            if cancel.is_some_and(|(flag, _)| flag.load(Ordering::Acquire)) {
+                return Err(RecvError(ErrorCause::Cancelled));
+            }
+
+            // Only count the first wait, not every spurious wakeup.
+            #[cfg(feature = "metrics")]
+            if !core::mem::replace(&mut blocked, true) {
+                self.read_blocks.fetch_add(1, Ordering::Relaxed);
+            }
+
+            #[cfg(feature = "std")]
+            {
+                let poll = cancel.map(|(_, interval)| Instant::now() + interval);
+                match deadline.into_iter().chain(poll).min() {
+                    Some(until) => {
+                        if !sleep.wait_until(until) && waiter::passed(deadline) {
+                            return Err(RecvError(ErrorCause::Timeout));
+                        }
+                    }
+                    None => sleep.wait(),
+                }
+            }
+
+            // Without a clock, deadlines never pass, and there is nothing to
+            // poll the cancel flag with.
+            #[cfg(not(feature = "std"))]
+            {
+                if waiter::passed(deadline) {
+                    return Err(RecvError(ErrorCause::Timeout));
+                }
+                sleep.wait();
+            }
+        }
+    }
+
+    #[inline(always)]
+    fn try_read(&self) -> Result<T, RecvError> {
+        self.try_read_before(None)
+    }
+
+    /// Non-blocking read, which treats the channel as empty from the `end`
+    /// position on, if given.
+    #[inline(always)]
+    fn try_read_before(&self, end: Option<usize>) -> Result<T, RecvError> {
+        self.claim_read(end).map(RecvPermit::recv)
+    }
+
+    /// Claims the node at the read position, which must then be either taken
+    /// from or released. Treats the channel as empty from the `end` position
+    /// on, if given.
+    #[inline(always)]
+    fn claim_read(&self, end: Option<usize>) -> Result<RecvPermit<'_, T>, RecvError> {
+        self.claim_read_with(end, || {})
+    }
+
+    /// Non-blocking read which calls `yield_now` after losing a node to
+    /// another reader, rather than retrying right away.
+    #[cfg(feature = "std")]
+    fn try_read_yield(&self) -> Result<T, RecvError> {
+        self.claim_read_with(None, std::thread::yield_now)
+            .map(RecvPermit::recv)
+    }
+
+    /// Like [`Channel::claim_read`], but calls `contended` every time another
+    /// reader wins the node first.
+    #[inline(always)]
+    fn claim_read_with(
+        &self,
+        end: Option<usize>,
+        mut contended: impl FnMut(),
+    ) -> Result<RecvPermit<'_, T>, RecvError> {
+        let gate = self.enter();
+        let mut hung_up = None;
+        loop {
+            if self.returned_len.load(Ordering::Acquire) > 0 {
+                if let Some(index) = self.take_returned() {
+                    return Ok(RecvPermit::new(self, self.node(index), index, gate));
+                }
+            }
+
+            let read = self.read.load(Ordering::Acquire);
+            let write = self.write.load(Ordering::Acquire);
+
+            if end.is_some_and(|end| end.wrapping_sub(read) as isize <= 0) {
+                return Err(RecvError(ErrorCause::WouldBlock));
+            }
+
+            // A node not claimed by a writer yet is not ready, even if it is
+            // hot: its value from the last lap may still be getting read.
+            let claimed = write.wrapping_sub(read) as isize > 0;
+            if !claimed || !self.node(read).hot.load(Ordering::Acquire) {
+                if claimed && self.take_skipped(read) {
+                    continue;
+                }
+
+                if let Some(err) = hung_up {
+                    return Err(err);
+                }
+                if let Err(err) = self.check_senders() {
+                    // The last sender may have sent a value right before
+                    // hanging up, so look again now that all sends are done.
+                    hung_up = Some(err);
+                    continue;
+                }
+
+                // Return error when the channel is empty
+                return Err(RecvError(ErrorCause::WouldBlock));
+            }
+
+            if self.single {
+                self.read.store(read.wrapping_add(1), Ordering::Release);
+                return Ok(RecvPermit::new(self, self.node(read), read, gate));
+            }
+
+            // Moving the read position past the node is what claims it, so
+            // only one reader wins it. The node can't have cooled down since
+            // it was checked: that takes a claim, which would have moved the
+            // read position on and failed this one.
+            if self
+                .read
+                .compare_exchange(
+                    read,
+                    read.wrapping_add(1),
+                    Ordering::AcqRel,
+                    Ordering::Relaxed,
+                )
+                .is_ok()
+            {
+                debug_assert!(
+                    self.node(read).hot.load(Ordering::Acquire),
+                    "claimed a node which is not hot"
+                );
+                return Ok(RecvPermit::new(self, self.node(read), read, gate));
+            }
+
+            // A thread stole the node, try again...
+            contended();
+        }
+    }
+
+    /// Takes the value out of the node at a position claimed by
+    /// [`Channel::claim_read`].
+    ///
+    /// # Safety
+    /// The node must have been claimed, and not taken from or released since.
+    #[inline(always)]
+    unsafe fn take(&self, index: usize) -> T {
+        let node = self.node(index);
+        let value = unsafe {
+            // SAFETY: The node is hot and claimed, so it is safe to read from it.
+            node.data.with(|data| (*data).assume_init_read())
+        };
+
+        #[cfg(feature = "receipts")]
+        node.done.store(index, Ordering::Release);
+        node.hot.store(false, Ordering::Release);
+        self.writable.set();
+
+        if let Some(watermarks) = &self.watermarks {
+            watermarks.read(self.len());
+        }
+
+        value
+    }
+
+    /// Drops the value of the node at a position claimed by
+    /// [`Channel::claim_read`] in place.
+    ///
+    /// # Safety
+    /// The node must have been claimed, and not taken from or released since.
+    #[inline(always)]
+    unsafe fn discard(&self, index: usize) {
+        let node = self.node(index);
+        unsafe {
+            // SAFETY: The node is hot and claimed, so it holds a value nobody
+            // else will read.
+            node.data.with_mut(|data| (*data).assume_init_drop());
+        }
+
+        #[cfg(feature = "receipts")]
+        node.done.store(index, Ordering::Release);
+        node.hot.store(false, Ordering::Release);
+        self.writable.set();
+
+        if let Some(watermarks) = &self.watermarks {
+            watermarks.read(self.len());
+        }
+    }
+
+    /// Drops every value sent before the call, see [`Receiver::clear`].
+    fn clear(&self) -> usize {
+        // Values sent while clearing are left alone, or this might never end.
+        let end = self.write.load(Ordering::Acquire);
+        let mut count = 0;
+        while let Ok(permit) = self.claim_read(Some(end)) {
+            permit.discard();
+            count += 1;
+        }
+
+        count
+    }
+
+    /// Gives up a node claimed by [`Channel::claim_read`] without taking from
+    /// it. If no node was claimed after it, it is simply unclaimed, otherwise
+    /// it is claimed again before any other. It stays hot either way, so no
+    /// writer can claim it in the meantime.
+    fn unclaim_read(&self, index: usize) {
+        if self
+            .read
+            .compare_exchange(
+                index.wrapping_add(1),
+                index,
+                Ordering::AcqRel,
+                Ordering::Relaxed,
+            )
+            .is_err()
+        {
+            self.returned.lock().push(index);
+            self.returned_len.fetch_add(1, Ordering::Release);
+        }
+
+        self.readable.set();
+        self.set_displacing();
+    }
+
+    /// Blocking read of the first value matching the predicate, see
+    /// [`Receiver::recv_matching`].
+    fn read_matching(&self, predicate: impl Fn(&T) -> bool) -> Result<T, RecvError> {
+        loop {
+            let write = self.write.load(Ordering::Acquire);
+            match self.try_read_matching(&predicate) {
+                Err(RecvError(ErrorCause::WouldBlock)) => {}
+                result => return result,
+            }
+
+            // Putting back the values we looked at set the waiter, so only
+            // wake up for new values.
+            self.readable.wait_for(|| {
+                self.write.load(Ordering::Acquire) != write || self.check_senders().is_err()
+            });
+        }
+    }
+
+    /// Claims values until one matches the predicate, and then puts back the
+    /// others in the order they were claimed.
+    fn try_read_matching(&self, predicate: impl Fn(&T) -> bool) -> Result<T, RecvError> {
+        let mut claimed = Vec::new();
+        loop {
+            let permit = self.claim_read(None)?;
+            if predicate(permit.get()) {
+                return Ok(permit.recv());
+            }
+
+            claimed.push(permit);
+        }
+    }
+
+    /// Reads up to `n` values without blocking, claiming as many as are
+    /// ready at once, see [`Receiver::try_recv_n`].
+    fn try_read_n(&self, n: usize) -> Vec<T> {
+        let _gate = self.enter();
+        let mut values = Vec::with_capacity(n.min(self.capacity()));
+
+        // Returned values are claimed one at a time, before any others.
+        while values.len() < n && self.returned_len.load(Ordering::Acquire) > 0 {
+            let Some(index) = self.take_returned() else {
+                break;
+            };
+            values.push(unsafe {
+                // SAFETY: Returned positions stay hot, and are claimed by
+                // whoever takes them off the list.
+                self.take(index)
+            });
+        }
+
+        while values.len() < n {
+            let read = self.read.load(Ordering::Acquire);
+            let write = self.write.load(Ordering::Acquire);
+
+            // Count the ready nodes from the read position on, which are
+            // claimed by a writer and hot, see `claim_read`.
+            let claimed = write.wrapping_sub(read) as isize;
+            let mut count = 0;
+            while count < n - values.len()
+                && (count as isize) < claimed
+                && self
+                    .node(read.wrapping_add(count))
+                    .hot
+                    .load(Ordering::Acquire)
+            {
+                count += 1;
+            }
+
+            if count == 0 {
+                if claimed > 0 && self.take_skipped(read) {
+                    continue;
+                }
+                break;
+            }
+
+            let end = read.wrapping_add(count);
+            if self.single {
+                self.read.store(end, Ordering::Release);
+            } else if self
+                .read
+                .compare_exchange(read, end, Ordering::AcqRel, Ordering::Relaxed)
+                .is_err()
+            {
+                // A thread stole some of the nodes, try again...
+                continue;
+            }
+
+            for index in 0..count {
+                values.push(unsafe {
+                    // SAFETY: The nodes are hot, and were claimed above.
+                    self.take(read.wrapping_add(index))
+                });
+            }
+        }
+
+        values
+    }
+
+    /// Copies as many ready values into `dst` as fit without blocking,
+    /// claiming runs of them at once, see [`Receiver::recv_copy_into`].
+    fn try_read_copy(&self, dst: &mut [T]) -> usize
+    where
+        T: Copy,
+    {
+        let _gate = self.enter();
+        let mut copied = 0;
+
+        // Returned values are claimed one at a time, before any others.
+        while copied < dst.len() && self.returned_len.load(Ordering::Acquire) > 0 {
+            let Some(index) = self.take_returned() else {
+                break;
+            };
+            dst[copied] = unsafe {
+                // SAFETY: Returned positions stay hot, and are claimed by
+                // whoever takes them off the list.
+                self.take(index)
+            };
+            copied += 1;
+        }
+
+        let nodes = self.nodes();
+        while copied < dst.len() {
+            let read = self.read.load(Ordering::Acquire);
+            let write = self.write.load(Ordering::Acquire);
+
+            // Count the ready nodes from the read position on, like
+            // `try_read_n` does.
+            let claimed = write.wrapping_sub(read) as isize;
+            let mut count = 0;
+            while count < dst.len() - copied
+                && (count as isize) < claimed
+                && self
+                    .node(read.wrapping_add(count))
+                    .hot
+                    .load(Ordering::Acquire)
+            {
+                count += 1;
+            }
+
+            if count == 0 {
+                if claimed > 0 && self.take_skipped(read) {
+                    continue;
+                }
+                break;
+            }
+
+            let end = read.wrapping_add(count);
+            if self.single {
+                self.read.store(end, Ordering::Release);
+            } else if self
+                .read
+                .compare_exchange(read, end, Ordering::AcqRel, Ordering::Relaxed)
+                .is_err()
+            {
+                // A thread stole some of the nodes, try again...
+                continue;
+            }
+
+            // The run wraps around the end of the buffer at most once, so it
+            // is copied as two segments at most, without wrapping every index.
+            let start = read % nodes.len();
+            let first = count.min(nodes.len() - start);
+            let (head, tail) = dst[copied..copied + count].split_at_mut(first);
+            let mut position = read;
+            for (from, segment) in [
+                (&nodes[start..start + first], head),
+                (&nodes[..count - first], tail),
+            ] {
+                for (node, to) in from.iter().zip(segment) {
+                    unsafe {
+                        // SAFETY: The node is hot, and was claimed above. The
+                        // value is `Copy`, so copying it out moves it.
+                        node.data
+                            .with(|data| ptr::copy_nonoverlapping((*data).as_ptr(), to, 1));
+                    }
+
+                    #[cfg(feature = "receipts")]
+                    node.done.store(position, Ordering::Release);
+                    node.hot.store(false, Ordering::Release);
+                    position = position.wrapping_add(1);
+                }
+            }
+            copied += count;
+
+            self.writable.set();
+            if let Some(watermarks) = &self.watermarks {
+                watermarks.read(self.len());
+            }
+        }
+
+        copied
+    }
+
+    fn take_returned(&self) -> Option<usize> {
+        let mut returned = self.returned.lock();
+        if returned.is_empty() {
+            return None;
+        }
+
+        self.returned_len.fetch_sub(1, Ordering::Relaxed);
+        Some(returned.remove(0))
+    }
+
+    fn finish(&self) {
+        self.finished.store(true, Ordering::Release);
+
+        // Wake up everyone, so they can see the hang up.
+        self.readable.set();
+        self.writable.set();
+    }
+
+    fn close(&self, reason: Arc<str>) {
+        // Only the first reason sticks.
+        let _ = self.closed.set(reason);
+
+        // Wake up everyone, so they can see the channel is closed.
+        self.readable.set();
+        self.writable.set();
+    }
+
+    fn grow(&self, additional: usize) -> Result<(), GrowError> {
+        let gate = self.gate.as_ref().ok_or(GrowError::NotGrowable)?;
+        let gate = gate.write();
+
+        let data = unsafe {
+            // SAFETY: Nobody else is accessing the nodes, as we hold the gate
+            // exclusively.
+            &mut *self.data.get()
+        };
+        let capacity = data
+            .len()
+            .checked_add(additional)
+            .ok_or(GrowError::CapacityOverflow)?;
+
+        let mut grown = Vec::with_capacity(capacity);
+        grown.resize_with(capacity, Node::default);
+
+        // Every operation has finished within the gate, so the nodes between
+        // the read and write counters are either hot or skipped, and so are
+        // the returned ones. Keep them at the same positions, so the counters
+        // stay valid.
+        let read = self.read.load(Ordering::Relaxed);
+        let write = self.write.load(Ordering::Relaxed);
+
+        // Everything before the read position was taken out, except for the
+        // returned values, which are moved over below.
+        #[cfg(feature = "receipts")]
+        for node in &mut grown {
+            node.done = AtomicUsize::new(read.wrapping_sub(1));
+        }
+
+        for index in (0..write.wrapping_sub(read))
+            .map(|i| read.wrapping_add(i))
+            .chain(self.returned.lock().iter().copied())
+        {
+            let from = &data[index % data.len()];
+            let to = &grown[index % capacity];
+
+            // Skipped positions stay skipped.
+            if !from.hot.load(Ordering::Relaxed) {
+                continue;
+            }
+
+            unsafe {
+                // SAFETY: `from` is hot and `to` is not, and `from` is cooled
+                // down right after, so the value is only dropped once.
+                to.data
+                    .with_mut(|to| from.data.with(|from| ptr::copy_nonoverlapping(from, to, 1)));
+                #[cfg(feature = "timing")]
+                to.sent.with_mut(|to| from.sent.with(|from| *to = *from));
+            }
+            from.hot.store(false, Ordering::Relaxed);
+            to.hot.store(true, Ordering::Relaxed);
+            #[cfg(feature = "receipts")]
+            to.done.store(index.wrapping_sub(1), Ordering::Relaxed);
+        }
+
+        *data = grown;
+        drop(gate);
+
+        // Wake up all blocked writers, there is room for them now.
+        self.writable.set();
+
+        Ok(())
+    }
+}
+
+impl<T> fmt::Debug for Channel<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let _gate = self.enter();
+        let senders = self.senders.load(Ordering::Relaxed);
+        let receivers = self.receivers.load(Ordering::Relaxed);
+
+        f.debug_struct("Channel")
+            .field("name", &self.name)
+            .field("capacity", &self.capacity())
+            .field("len", &self.len())
+            .field("sender_count", &senders)
+            .field("receiver_count", &receivers)
+            .field(
+                "closed",
+                &(self.senders_gone() || receivers == 0 || self.closed.get().is_some()),
+            )
+            .finish()
+    }
+}
+
+unsafe impl<T: Send> Send for Channel<T> {}
+unsafe impl<T: Send> Sync for Channel<T> {}
+
+/// Identifies a channel, created by [`Sender::id`] or [`Receiver::id`].
+///
+/// All senders and receivers of a channel have the same id, and no other
+/// channel alive at the same time has it. Once a channel is dropped, its id
+/// may be reused by a new one.
+///
+/// # Examples
+/// ```
+/// use atomic_mpmc::channel;
+///
+/// let (sender, receiver) = channel::<i32>(1);
+/// let (other, _) = channel::<i32>(1);
+///
+/// assert_eq!(sender.id(), receiver.id());
+/// assert_ne!(sender.id(), other.id());
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ChannelId(usize);
+
+impl ChannelId {
+    fn new<T>(channel: &sync::Arc<Channel<T>>) -> Self {
+        Self(sync::Arc::as_ptr(channel) as usize)
+    }
+}
+
+/// A sender for a MPMC channel.
+///
+/// This struct is created by the [`channel`] function. It provides methods for
+/// sending data to the channel.
+///
+/// A failed send hands the value back in its error, so ignoring the result
+/// of one is warned about, as the value would be lost:
+/// ```compile_fail
+/// #![deny(unused_must_use)]
+/// use atomic_mpmc::channel;
+///
+/// let (sender, receiver) = channel::<i32>(1);
+/// sender.send(1);
+/// ```
+pub struct Sender<T>(sync::Arc<Channel<T>>);
+
+impl<T> Sender<T> {
+    fn new(channel: sync::Arc<Channel<T>>) -> Self {
+        channel.add_sender();
+        Self(channel)
+    }
+
+    /// Send a value to the channel. This function will block the current thread
+    /// if the channel is full.
+    ///
+    /// # Examples
+    /// ```
+    /// use atomic_mpmc::channel;
+    ///
+    /// let (sender, receiver) = channel::<i32>(1);
+    ///
+    /// sender.send(1).unwrap();
+    /// ```
+    #[must_use = "a failed send returns the value you tried to send"]
+    pub fn send(&self, value: T) -> Result<(), SendError<T>> {
+        self.0.write(value)
+    }
+
+    /// Send a value to the channel like [`Self::send`], returning the ring
+    /// slot it was written to, in `0..capacity`. The slot can be matched with
+    /// the one returned by [`Receiver::recv_traced`] to trace the value.
+    ///
+    /// # Examples
+    /// ```
+    /// use atomic_mpmc::channel;
+    ///
+    /// let (sender, receiver) = channel::<i32>(2);
+    ///
+    /// assert_eq!(sender.send_traced(1).unwrap(), 0);
+    /// assert_eq!(sender.send_traced(2).unwrap(), 1);
+    /// ```
+    pub fn send_traced(&self, value: T) -> Result<usize, SendError<T>> {
+        let (_, slot) = self.0.write_positioned(value)?;
+        Ok(slot)
+    }
+
+    /// Attempt to send a value to the channel. This function will return
+    /// `Err(SendError(value, ErrorCause::WouldBlock))` if the channel is full.
+    ///
+    /// # Examples
+    /// ```
+    /// use atomic_mpmc::channel;
+    ///
+    /// let (sender, receiver) = channel::<i32>(1);
+    ///
+    /// sender.send(1).unwrap();
+    /// sender.try_send(2).unwrap_err();
+    /// ```
+    #[must_use = "a failed send returns the value you tried to send"]
+    pub fn try_send(&self, value: T) -> Result<(), SendError<T>> {
+        self.0.try_write(value)
+    }
+
+    /// Attempt to send a value to the channel, like [`Self::try_send`], and
+    /// return the number of free slots left right after, like
+    /// [`Self::capacity_remaining`]. This lets producers adapt to how full
+    /// the channel is getting without asking separately.
+    ///
+    /// # Examples
+    /// ```
+    /// use atomic_mpmc::channel;
+    ///
+    /// let (sender, receiver) = channel::<i32>(2);
+    ///
+    /// assert_eq!(sender.try_send_counted(1).unwrap(), 1);
+    /// assert_eq!(sender.try_send_counted(2).unwrap(), 0);
+    /// sender.try_send_counted(3).unwrap_err();
+    /// ```
+    pub fn try_send_counted(&self, value: T) -> Result<usize, SendError<T>> {
+        let _gate = self.0.enter();
+        self.0.try_write(value)?;
+        Ok(self.0.capacity() - self.0.len())
+    }
+
+    /// Send a value to the channel, or if it is full, merge it into the last
+    /// value sent with `merge` instead of blocking. This fits a stream of
+    /// deltas, where a slow receiver gets them summed up instead of holding
+    /// back the sender.
+    ///
+    /// While merging, the buffered values are claimed, so receivers see the
+    /// channel as empty for a moment.
+    ///
+    /// The last value sent must be this sender's, so this function may only
+    /// be used while there is a single producer: other senders, clones of
+    /// this one included, must not send at the same time. Otherwise, it may
+    /// spin until they are done, or merge into a value it didn't send, and
+    /// the values it merged over may be received after ones sent later. It
+    /// falls back to blocking like [`Self::send`] if there is no value to
+    /// merge into.
+    ///
+    /// # Examples
+    /// ```
+    /// use atomic_mpmc::channel;
+    ///
+    /// let (sender, receiver) = channel::<i32>(1);
+    ///
+    /// sender.send_coalesce(1, |last, value| *last += value).unwrap();
+    /// sender.send_coalesce(2, |last, value| *last += value).unwrap();
+    ///
+    /// assert_eq!(receiver.try_recv().unwrap(), 3);
+    /// ```
+    pub fn send_coalesce<F: Fn(&mut T, T)>(&self, value: T, merge: F) -> Result<(), SendError<T>> {
+        self.0.try_write_coalesce(value, merge)
+    }
+
+    /// Attempt to send a value to the channel, giving up if another sender
+    /// claimed the free slot first. This function will return
+    /// `Err(SendError(value, ErrorCause::Contended))` in that case, and
+    /// `Err(SendError(value, ErrorCause::WouldBlock))` if the channel is full.
+    ///
+    /// Unlike [`Self::try_send`], which tries again after losing a race, this
+    /// lets the caller tell contention from a full channel, and back off in
+    /// its own way.
+    ///
+    /// # Examples
+    /// ```
+    /// use atomic_mpmc::{channel, ErrorCause};
+    ///
+    /// let (sender, receiver) = channel::<i32>(1);
+    ///
+    /// sender.try_send_once(1).unwrap();
+    /// assert_eq!(sender.try_send_once(2).unwrap_err().1, ErrorCause::WouldBlock);
+    /// ```
+    #[must_use = "a failed send returns the value you tried to send"]
+    pub fn try_send_once(&self, value: T) -> Result<(), SendError<T>> {
+        self.0.try_write_once(value)
+    }
+
+    /// Send a value to the channel like [`Self::send`], and return a
+    /// [`Receipt`] which tells when a receiver took it out. For more
+    /// information, see [`Receipt`].
+    ///
+    /// # Examples
+    /// ```
+    /// use atomic_mpmc::channel;
+    ///
+    /// let (sender, receiver) = channel::<i32>(1);
+    ///
+    /// let receipt = sender.send_tracked(1).unwrap();
+    /// receiver.recv().unwrap();
+    /// receipt.wait().unwrap();
+    /// ```
+    #[cfg(feature = "receipts")]
+    pub fn send_tracked(&self, value: T) -> Result<Receipt<T>, SendError<T>> {
+        let (position, _) = self.0.write_positioned(value)?;
+        Ok(Receipt::new(self.0.clone(), position))
+    }
+
+    /// Send a value to the channel, retrying up to `spins` times while the
+    /// channel is full before blocking the current thread like [`Self::send`].
+    ///
+    /// Spinning burns CPU time, but avoids the cost of parking and waking the
+    /// thread when a receiver is likely to make room very soon. The right
+    /// number of spins depends on the workload, so measure it.
+    ///
+    /// # Examples
+    /// ```
+    /// use atomic_mpmc::channel;
+    ///
+    /// let (sender, receiver) = channel::<i32>(1);
+    ///
+    /// sender.send_spin(1, 100).unwrap();
+    /// assert_eq!(receiver.recv().unwrap(), 1);
+    /// ```
+    pub fn send_spin(&self, mut value: T, spins: u32) -> Result<(), SendError<T>> {
+        for _ in 0..spins {
+            value = match self.0.try_write(value) {
+                Err(SendError(value, ErrorCause::WouldBlock)) => value,
+                result => return result,
+            };
+            hint::spin_loop();
+        }
+
+        self.0.write(value)
+    }
+
+    /// Attempt to send a clone of a value to the channel. This function will
+    /// return `Err(SendError((), ErrorCause::WouldBlock))` if the channel is
+    /// full.
+    ///
+    /// The value is only cloned once a slot has been reserved for it, so
+    /// failed attempts don't clone anything.
+    ///
+    /// # Examples
+    /// ```
+    /// use atomic_mpmc::{channel, ErrorCause};
+    ///
+    /// let (sender, receiver) = channel::<String>(1);
+    /// let value = "hello".to_string();
+    ///
+    /// sender.try_send_ref(&value).unwrap();
+    /// assert_eq!(sender.try_send_ref(&value).unwrap_err().1, ErrorCause::WouldBlock);
+    /// assert_eq!(receiver.recv().unwrap(), value);
+    /// ```
+    pub fn try_send_ref(&self, value: &T) -> Result<(), SendError<()>>
+    where
+        T: Clone,
+    {
+        let permit = self.0.try_reserve()?;
+        permit.send(value.clone());
+        Ok(())
+    }
+
+    /// Attempt to send a value to the channel, retrying for as long as the
+    /// channel is full and `should_retry` returns `true`.
+    ///
+    /// This lets the caller implement its own backoff or deadline between
+    /// attempts. If the channel is still full when `should_retry` returns
+    /// `false`, the value is given back in a
+    /// `SendError(value, ErrorCause::WouldBlock)`.
+    ///
+    /// # Examples
+    /// ```
+    /// use atomic_mpmc::channel;
+    ///
+    /// let (sender, receiver) = channel::<i32>(1);
+    ///
+    /// sender.send(1).unwrap();
+    ///
+    /// let mut retries = 3;
+    /// let err = sender
+    ///     .try_send_with(2, || {
+    ///         retries -= 1;
+    ///         retries > 0
+    ///     })
+    ///     .unwrap_err();
+    /// assert_eq!(err.into_inner(), 2);
+    /// ```
+    pub fn try_send_with<F: FnMut() -> bool>(
+        &self,
+        mut value: T,
+        mut should_retry: F,
+    ) -> Result<(), SendError<T>> {
+        loop {
+            value = match self.0.try_write(value) {
+                Err(SendError(value, ErrorCause::WouldBlock)) if should_retry() => value,
+                result => return result,
+            };
+        }
+    }
+
+    /// Send every value of an iterator to the channel, blocking as needed.
+    ///
+    /// On success, returns the number of values sent. If the channel hangs up
+    /// partway through, returns the number of values sent so far together with
+    /// the error containing the value that failed. The rest of the iterator is
+    /// not consumed.
+    ///
+    /// # Examples
+    /// ```
+    /// use atomic_mpmc::channel;
+    ///
+    /// let (sender, receiver) = channel::<i32>(3);
+    ///
+    /// assert_eq!(sender.send_all([1, 2, 3]).unwrap(), 3);
+    /// assert_eq!(receiver.try_iter().collect::<Vec<_>>(), [1, 2, 3]);
+    /// ```
+    pub fn send_all<I: IntoIterator<Item = T>>(
+        &self,
+        iter: I,
+    ) -> Result<usize, (usize, SendError<T>)> {
+        let mut sent = 0;
+        for value in iter {
+            self.send(value).map_err(|err| (sent, err))?;
+            sent += 1;
+        }
+
+        Ok(sent)
+    }
+
+    /// Send as many values to the channel as there is room for, without
+    /// blocking, and return the ones which didn't fit, in order. If the
+    /// channel is hung up, all values are returned.
+    ///
+    /// # Examples
+    /// ```
+    /// use atomic_mpmc::channel;
+    ///
+    /// let (sender, receiver) = channel::<i32>(2);
+    ///
+    /// assert_eq!(sender.try_send_all(vec![1, 2, 3]), [3]);
+    /// assert_eq!(receiver.try_iter().collect::<Vec<_>>(), [1, 2]);
+    /// ```
+    pub fn try_send_all(&self, values: Vec<T>) -> Vec<T> {
+        self.0.try_write_n(values)
+    }
+
+    /// Attempt to send the value to the channel, registering the task to be
+    /// woken up if the channel is full. This is the building block for
+    /// futures which send to the channel.
+    ///
+    /// The value is only taken out of `value` once it is sent, or returned
+    /// in the error if the channel is hung up. While this function returns
+    /// `Poll::Pending`, it stays there for the next attempt.
+    ///
+    /// # Panics
+    /// Panics if `value` is `None`.
+    ///
+    /// # Examples
+    /// ```
+    /// use atomic_mpmc::channel;
+    /// use futures::task::{noop_waker, Context, Poll};
+    ///
+    /// let (sender, receiver) = channel::<i32>(1);
+    /// let waker = noop_waker();
+    /// let mut cx = Context::from_waker(&waker);
+    ///
+    /// sender.send(1).unwrap();
+    ///
+    /// let mut value = Some(2);
+    /// assert!(sender.poll_send(&mut value, &mut cx).is_pending());
+    /// assert_eq!(value, Some(2));
+    ///
+    /// assert_eq!(receiver.recv().unwrap(), 1);
+    /// assert_eq!(sender.poll_send(&mut value, &mut cx), Poll::Ready(Ok(())));
+    /// assert_eq!(value, None);
+    /// ```
+    #[cfg(feature = "async")]
+    pub fn poll_send(
+        &self,
+        value: &mut Option<T>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Result<(), SendError<T>>> {
+        let pending = value.take().expect("no value to send");
+        let pending = match self.0.try_write_overflowing(pending) {
+            Err(SendError(pending, ErrorCause::WouldBlock)) => pending,
+            result => return Poll::Ready(result),
+        };
+
+        // Check again after registering, so a slot freed in between can't
+        // be missed.
+        self.0.writable.register(cx.waker());
+        match self.0.try_write_overflowing(pending) {
+            Err(SendError(pending, ErrorCause::WouldBlock)) => {
+                *value = Some(pending);
+                Poll::Pending
+            }
+            result => Poll::Ready(result),
+        }
+    }
+
+    /// Reserve a slot in the channel, blocking the current thread if the
+    /// channel is full. The returned [`Permit`] sends a value into the slot
+    /// without blocking, or gives the slot back when dropped.
+    ///
+    /// This lets the caller make sure there is room before producing a value
+    /// which is expensive to make or impossible to give back. Outstanding
+    /// permits count towards the channel's capacity, and growing the channel
+    /// waits for them, so don't hold on to them for long.
+    ///
+    /// # Examples
+    /// ```
+    /// use atomic_mpmc::channel;
+    ///
+    /// let (sender, receiver) = channel::<i32>(3);
+    ///
+    /// let permit = sender.reserve().unwrap();
+    /// sender.send(1).unwrap();
+    /// permit.send(2);
+    ///
+    /// // Dropping a permit sends nothing.
+    /// drop(sender.reserve().unwrap());
+    ///
+    /// assert_eq!(receiver.try_iter().collect::<Vec<_>>(), [2, 1]);
+    /// ```
+    pub fn reserve(&self) -> Result<Permit<'_, T>, SendError<()>> {
+        self.0.reserve()
+    }
+
+    /// Attempt to reserve a slot in the channel. This function will return
+    /// `Err(SendError((), ErrorCause::WouldBlock))` if the channel is full.
+    ///
+    /// See [`Sender::reserve`] for details.
+    ///
+    /// # Examples
+    /// ```
+    /// use atomic_mpmc::channel;
+    ///
+    /// let (sender, receiver) = channel::<i32>(1);
+    ///
+    /// let permit = sender.try_reserve().unwrap();
+    /// sender.try_reserve().unwrap_err();
+    ///
+    /// permit.send(1);
+    /// assert_eq!(receiver.recv().unwrap(), 1);
+    /// ```
+    pub fn try_reserve(&self) -> Result<Permit<'_, T>, SendError<()>> {
+        self.0.try_reserve()
+    }
+
+    /// Attempt to reserve `count` consecutive slots in the channel at once.
+    /// This function will return `Err(SendError((), ErrorCause::WouldBlock))`
+    /// if fewer slots than that are free.
+    ///
+    /// The returned [`PermitBatch`] sends values into the slots in order,
+    /// and gives back the ones left unused when dropped. Since the slots are
+    /// consecutive, no other sender's values end up between them. See
+    /// [`Sender::reserve`] for details.
+    ///
+    /// # Examples
+    /// ```
+    /// use atomic_mpmc::channel;
+    ///
+    /// let (sender, receiver) = channel::<i32>(3);
+    ///
+    /// let mut permits = sender.try_reserve_many(2).unwrap();
+    /// sender.try_reserve_many(2).unwrap_err();
+    ///
+    /// permits.send(1).unwrap();
+    /// permits.send(2).unwrap();
+    /// assert_eq!(receiver.try_iter().collect::<Vec<_>>(), [1, 2]);
+    /// ```
+    pub fn try_reserve_many(&self, count: usize) -> Result<PermitBatch<'_, T>, SendError<()>> {
+        self.0.try_reserve_many(count)
+    }
+
+    /// Creates a new iterator reserving a slot in the channel each time one is
+    /// free, so values can be produced only once there is room for them.
+    /// The iterator will block when the channel is full, and end once all
+    /// receivers hang up. See [`Sender::reserve`] for details.
+    ///
+    /// # Examples
+    /// ```
+    /// use atomic_mpmc::channel;
+    ///
+    /// let (sender, receiver) = channel::<i32>(3);
+    ///
+    /// for (value, permit) in (1..=3).zip(sender.writable_iter()) {
+    ///     permit.send(value * value);
+    /// }
+    /// assert_eq!(receiver.try_iter().collect::<Vec<_>>(), [1, 4, 9]);
+    /// ```
+    pub fn writable_iter(&self) -> Permits<'_, T> {
+        Permits::new(self)
+    }
+
+    /// Grow the channel's buffer by `additional` slots, keeping all buffered
+    /// values in order. Senders blocked on a full channel are woken up.
+    ///
+    /// The channel must have been made growable with
+    /// [`ChannelBuilder::growable`], otherwise this function returns
+    /// `Err(GrowError::NotGrowable)`.
+    ///
+    /// Growing briefly stops the world: it waits for all in-flight sends and
+    /// receives to finish, holds off new ones, allocates the larger buffer and
+    /// moves every buffered value into it. Blocked operations don't hold it
+    /// up, as they don't count as in flight while parked.
+    ///
+    /// # Examples
+    /// ```
+    /// use atomic_mpmc::ChannelBuilder;
+    ///
+    /// let (sender, receiver) = ChannelBuilder::new(1).growable(true).build::<i32>();
+    ///
+    /// sender.send(1).unwrap();
+    /// assert!(sender.try_send(2).is_err());
+    ///
+    /// sender.try_grow(1).unwrap();
+    /// sender.try_send(2).unwrap();
+    ///
+    /// assert_eq!(receiver.try_iter().collect::<Vec<_>>(), [1, 2]);
+    /// ```
+    pub fn try_grow(&self, additional: usize) -> Result<(), GrowError> {
+        self.0.grow(additional)
+    }
+
+    /// Block the current thread until the channel has room for a value, or
+    /// all receivers have hung up, without sending anything.
+    ///
+    /// With multiple senders, another one may fill the freed slot before this
+    /// thread gets to it, so a following [`Self::try_send`] can still fail.
+    ///
+    /// # Examples
+    /// ```
+    /// use atomic_mpmc::channel;
+    ///
+    /// let (sender, receiver) = channel::<i32>(1);
+    ///
+    /// sender.wait_writable();
+    /// sender.try_send(1).unwrap();
+    /// ```
+    pub fn wait_writable(&self) {
+        self.0.wait_writable()
+    }
+
+    /// Block the current thread until every value in the channel has been
+    /// received. This function will return `Err(SendError((), ErrorCause::HungUp))`
+    /// if all receivers hang up first.
+    ///
+    /// This is only meaningful while no other senders are sending, as they
+    /// could keep the channel from ever becoming empty. A value claimed with
+    /// [`Receiver::reserve`] only counts as received once the permit takes
+    /// it out.
+    ///
+    /// # Examples
+    /// ```
+    /// use atomic_mpmc::channel;
+    ///
+    /// let (sender, receiver) = channel::<i32>(4);
+    ///
+    /// let handle = std::thread::spawn(move || receiver.iter().take(4).sum::<i32>());
+    ///
+    /// sender.send_all([1, 2, 3, 4]).unwrap();
+    /// sender.flush().unwrap();
+    /// assert_eq!(handle.join().unwrap(), 10);
+    /// ```
+    pub fn flush(&self) -> Result<(), SendError<()>> {
+        self.0.flush()
+    }
+
+    /// Drop this sender once every value it sent has been received, blocking
+    /// the current thread until then. This function will return
+    /// `Err(SendError((), ErrorCause::HungUp))` if all receivers hang up
+    /// first.
+    ///
+    /// Unlike [`Self::flush`], values other senders send in the meantime
+    /// don't hold it up.
+    ///
+    /// # Examples
+    /// ```
+    /// use atomic_mpmc::channel;
+    ///
+    /// let (sender, receiver) = channel::<i32>(4);
+    ///
+    /// let handle = std::thread::spawn(move || receiver.iter().sum::<i32>());
+    ///
+    /// sender.send_all([1, 2, 3, 4]).unwrap();
+    /// sender.drop_and_flush().unwrap();
+    /// assert_eq!(handle.join().unwrap(), 10);
+    /// ```
+    pub fn drop_and_flush(self) -> Result<(), SendError<()>> {
+        // Everything this sender sent was written before the current position.
+        self.0.flush_before(self.0.write.load(Ordering::Acquire))
+    }
+
+    /// Declare that nothing more will be sent, as if every sender hung up,
+    /// while keeping this sender around.
+    ///
+    /// Receivers get the values already buffered, and then fail with
+    /// [`ErrorCause::HungUp`]. Blocked receivers are woken up to see it. All
+    /// further sends fail with [`ErrorCause::HungUp`] too.
+    ///
+    /// # Examples
+    /// ```
+    /// use atomic_mpmc::{channel, ErrorCause};
+    ///
+    /// let (sender, receiver) = channel::<i32>(2);
+    ///
+    /// sender.send(1).unwrap();
+    /// sender.finish();
+    ///
+    /// assert_eq!(sender.send(2).unwrap_err().1, ErrorCause::HungUp);
+    /// assert_eq!(receiver.recv().unwrap(), 1);
+    /// assert_eq!(receiver.recv().unwrap_err().0, ErrorCause::HungUp);
+    /// ```
+    pub fn finish(&self) {
+        self.0.finish()
+    }
+
+    /// Close the channel for everyone, giving a reason.
+    ///
+    /// This works like both sides hanging up at once: all further sends fail,
+    /// and receives fail once the buffered values are drained. Instead of
+    /// [`ErrorCause::HungUp`], they fail with [`ErrorCause::Closed`] carrying
+    /// the reason. Blocked senders and receivers are woken up to see it.
+    ///
+    /// If the channel was already closed, the original reason is kept.
+    ///
+    /// # Examples
+    /// ```
+    /// use atomic_mpmc::{channel, ErrorCause};
+    ///
+    /// let (sender, receiver) = channel::<i32>(2);
+    ///
+    /// sender.send(1).unwrap();
+    /// sender.close_with("shutting down");
+    ///
+    /// assert_eq!(sender.send(2).unwrap_err().1, ErrorCause::Closed("shutting down".into()));
+    /// assert_eq!(receiver.recv().unwrap(), 1);
+    /// assert_eq!(receiver.recv().unwrap_err().0, ErrorCause::Closed("shutting down".into()));
+    /// ```
+    pub fn close_with(&self, reason: impl Into<Arc<str>>) {
+        self.0.close(reason.into())
+    }
+
+    /// Returns `true` if all receivers have hung up, so nothing sent can be
+    /// received anymore. Closing the channel doesn't count.
+    ///
+    /// # Examples
+    /// ```
+    /// use atomic_mpmc::channel;
+    ///
+    /// let (sender, receiver) = channel::<i32>(1);
+    /// assert!(!sender.is_disconnected());
+    ///
+    /// drop(receiver);
+    /// assert!(sender.is_disconnected());
+    /// ```
+    pub fn is_disconnected(&self) -> bool {
+        self.0.receivers.load(Ordering::Relaxed) == 0
+    }
+
+    /// Returns the number of values in the channel, including slots reserved
+    /// by a [`Permit`] but not sent yet.
+    ///
+    /// This is only a snapshot, other threads may have sent or received
+    /// values by the time it is returned. It is always in `0..=capacity`
+    /// though, even while other threads are racing with it.
+    ///
+    /// # Examples
+    /// ```
+    /// use atomic_mpmc::channel;
+    ///
+    /// let (sender, receiver) = channel::<i32>(2);
+    ///
+    /// sender.send(1).unwrap();
+    /// assert_eq!(sender.len_clamped(), 1);
+    /// ```
+    pub fn len_clamped(&self) -> usize {
+        let _gate = self.0.enter();
+        self.0.len()
+    }
+
+    /// Returns the number of values the channel has room for. This is the
+    /// capacity it was created with, unless it is growable and has grown
+    /// since, see [`ChannelBuilder::growable`].
+    ///
+    /// # Examples
+    /// ```
+    /// use atomic_mpmc::channel;
+    ///
+    /// let (sender, receiver) = channel::<i32>(4);
+    ///
+    /// sender.send(1).unwrap();
+    /// assert_eq!(sender.capacity(), 4);
+    /// ```
+    pub fn capacity(&self) -> usize {
+        let _gate = self.0.enter();
+        self.0.capacity()
+    }
+
+    /// Returns the number of values which can be sent right now without
+    /// blocking, which is the capacity minus [`Self::len_clamped`].
+    ///
+    /// This is only a snapshot, other threads may have sent or received
+    /// values by the time it is returned. It is always in `0..=capacity`
+    /// though.
+    ///
+    /// # Examples
+    /// ```
+    /// use atomic_mpmc::channel;
+    ///
+    /// let (sender, receiver) = channel::<i32>(4);
+    ///
+    /// sender.send_all([1, 2, 3]).unwrap();
+    /// assert_eq!(sender.capacity_remaining(), 1);
+    /// ```
+    pub fn capacity_remaining(&self) -> usize {
+        let _gate = self.0.enter();
+        self.0.capacity() - self.0.len()
+    }
+
+    /// Returns the number of receivers blocked waiting for a value, like idle
+    /// consumers. This is only a snapshot, which can be out of date by the
+    /// time it is returned.
+    ///
+    /// # Examples
+    /// ```
+    /// use atomic_mpmc::channel;
+    /// use std::{thread, time::Duration};
+    ///
+    /// let (sender, receiver) = channel::<i32>(1);
+    /// assert_eq!(sender.waiting_receivers(), 0);
+    ///
+    /// let handle = thread::spawn(move || receiver.recv());
+    /// while sender.waiting_receivers() == 0 {
+    ///     thread::sleep(Duration::from_millis(1));
+    /// }
+    ///
+    /// sender.send(1).unwrap();
+    /// assert_eq!(handle.join().unwrap(), Ok(1));
+    /// ```
+    pub fn waiting_receivers(&self) -> usize {
+        self.0.readable.sleepers()
+    }
+
+    /// Returns the id of the channel, which is the same for all of its
+    /// senders and receivers. For more information, see [`ChannelId`].
+    pub fn id(&self) -> ChannelId {
+        ChannelId::new(&self.0)
+    }
+
+    /// Returns the name of the channel, if it was given one with
+    /// [`channel_named`] or [`ChannelBuilder::name`].
+    ///
+    /// # Examples
+    /// ```
+    /// use atomic_mpmc::channel_named;
+    ///
+    /// let (sender, receiver) = channel_named::<i32>(1, "jobs");
+    ///
+    /// assert_eq!(sender.name(), Some("jobs"));
+    /// assert_eq!(receiver.name(), Some("jobs"));
+    /// ```
+    pub fn name(&self) -> Option<&str> {
+        self.0.name.as_deref()
+    }
+
+    /// Returns the number of slots claimed for writing so far, for
+    /// correlating senders and receivers in logs. It is not taken modulo the
+    /// capacity, but wraps around at `usize::MAX`.
+    ///
+    /// This is a `Relaxed` snapshot, and may be outdated by the time it is
+    /// returned. A dropped [`Permit`] can move it back by one.
+    ///
+    /// # Examples
+    /// ```
+    /// use atomic_mpmc::channel;
+    ///
+    /// let (sender, receiver) = channel::<i32>(2);
+    ///
+    /// sender.send(1).unwrap();
+    /// assert_eq!(sender.write_position(), 1);
+    /// ```
+    pub fn write_position(&self) -> usize {
+        self.0.write.load(Ordering::Relaxed)
+    }
+
+    /// Returns the largest number of values that were ever buffered in the
+    /// channel at once, including slots reserved by a [`Permit`].
+    ///
+    /// # Examples
+    /// ```
+    /// use atomic_mpmc::channel;
+    ///
+    /// let (sender, receiver) = channel::<i32>(4);
+    ///
+    /// sender.send_all([1, 2, 3]).unwrap();
+    /// receiver.try_iter().for_each(drop);
+    /// sender.send(4).unwrap();
+    ///
+    /// assert_eq!(sender.high_water_mark(), 3);
+    /// ```
+    #[cfg(feature = "metrics")]
+    pub fn high_water_mark(&self) -> usize {
+        self.0.high_water_mark.load(Ordering::Relaxed)
+    }
+
+    /// Returns how many times a send had to block because the channel was
+    /// full. Every blocking send counts once, however long it waited.
+    ///
+    /// # Examples
+    /// ```
+    /// use atomic_mpmc::channel;
+    ///
+    /// let (sender, receiver) = channel::<i32>(1);
+    ///
+    /// sender.send(1).unwrap();
+    /// assert_eq!(sender.block_count(), 0);
+    /// ```
+    #[cfg(feature = "metrics")]
+    pub fn block_count(&self) -> u64 {
+        self.0.write_blocks.load(Ordering::Relaxed)
+    }
+}
+
+impl<T> Drop for Sender<T> {
+    fn drop(&mut self) {
+        self.0.remove_sender();
+    }
+}
+
+impl<T> Clone for Sender<T> {
+    fn clone(&self) -> Self {
+        Self::new(self.0.clone())
+    }
+}
+
+impl<T> fmt::Debug for Sender<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("Sender").field(&self.0).finish()
+    }
+}
+
+/// A receiver for a MPMC channel.
+///
+/// This struct is created by the [`channel`] function. It provides methods for
+/// receiving data from the channel.
+///
+/// This struct implements the [`IntoIterator`] trait, which means that you can
+/// convert it to an iterator over received values.
+///
+/// With many receivers, every value is received by exactly one of them.
+/// Values are claimed in the order they were sent, but receivers on different
+/// threads may get around to returning them in any order.
+///
+/// Ignoring the result of a receive is warned about, as the received value
+/// would be dropped:
+/// ```compile_fail
+/// #![deny(unused_must_use)]
+/// use atomic_mpmc::channel;
+///
+/// let (sender, receiver) = channel::<i32>(1);
+/// sender.send(1).unwrap();
+/// receiver.recv();
+/// ```
+pub struct Receiver<T>(sync::Arc<Channel<T>>);
+
+impl<T> Receiver<T> {
+    fn new(channel: sync::Arc<Channel<T>>) -> Receiver<T> {
+        channel.add_receiver();
+        Receiver(channel)
+    }
+
+    /// Receive a value from the channel. This function will block the current
+    /// thread if the channel is empty.
+    ///
+    /// Once the channel is empty and all senders have hung up, this function
+    /// returns `Err(RecvError(ErrorCause::HungUp))` instead of blocking, so
+    /// it can't wait on senders which are gone. It does wait forever if the
+    /// only sender left belongs to the thread which is waiting.
+    ///
+    /// # Examples
+    /// ```
+    /// use atomic_mpmc::channel;
+    ///
+    /// let (sender, receiver) = channel::<i32>(1);
+    ///
+    /// sender.send(1).unwrap();
+    /// assert_eq!(receiver.recv().unwrap(), 1);
+    /// ```
+    #[must_use = "ignoring the result drops the received value"]
+    pub fn recv(&self) -> Result<T, RecvError> {
+        self.0.read()
+    }
+
+    /// Receive a value from the channel, along with the number of values
+    /// still buffered right after it was claimed. This function will block
+    /// the current thread if the channel is empty.
+    ///
+    /// The count is only a snapshot, other threads may have sent or received
+    /// values by the time it is returned.
+    ///
+    /// # Examples
+    /// ```
+    /// use atomic_mpmc::channel;
+    ///
+    /// let (sender, receiver) = channel::<i32>(2);
+    ///
+    /// sender.send_all([1, 2]).unwrap();
+    /// assert_eq!(receiver.recv_with_len().unwrap(), (1, 1));
+    /// assert_eq!(receiver.recv_with_len().unwrap(), (2, 0));
+    /// ```
+    pub fn recv_with_len(&self) -> Result<(T, usize), RecvError> {
+        let permit = if self.0.blocking {
+            self.0.reserve_read(None, None)?
+        } else {
+            self.0.claim_read(None)?
+        };
+        let len = self.0.len();
+        Ok((permit.recv(), len))
+    }
+
+    /// Receive a value from the channel like [`Self::recv`], along with the
+    /// ring slot it was read from, see [`Sender::send_traced`].
+    ///
+    /// # Examples
+    /// ```
+    /// use atomic_mpmc::channel;
+    ///
+    /// let (sender, receiver) = channel::<i32>(2);
+    ///
+    /// let slot = sender.send_traced(1).unwrap();
+    /// assert_eq!(receiver.recv_traced().unwrap(), (1, slot));
+    /// ```
+    pub fn recv_traced(&self) -> Result<(T, usize), RecvError> {
+        let permit = if self.0.blocking {
+            self.0.reserve_read(None, None)?
+        } else {
+            self.0.claim_read(None)?
+        };
+
+        let slot = permit.slot();
+        Ok((permit.recv(), slot))
+    }
+
+    /// Receive a value from the channel like [`Self::recv`], along with how
+    /// long it waited in the channel since it was sent.
+    ///
+    /// # Examples
+    /// ```
+    /// use atomic_mpmc::channel;
+    /// use std::{thread, time::Duration};
+    ///
+    /// let (sender, receiver) = channel::<i32>(1);
+    ///
+    /// sender.send(1).unwrap();
+    /// thread::sleep(Duration::from_millis(10));
+    ///
+    /// let (value, dwell) = receiver.recv_timed().unwrap();
+    /// assert_eq!(value, 1);
+    /// assert!(dwell >= Duration::from_millis(10));
+    /// ```
+    #[cfg(feature = "timing")]
+    pub fn recv_timed(&self) -> Result<(T, Duration), RecvError> {
+        let permit = if self.0.blocking {
+            self.0.reserve_read(None, None)?
+        } else {
+            self.0.claim_read(None)?
+        };
+
+        let dwell = permit.sent().map_or(Duration::ZERO, |sent| sent.elapsed());
+        Ok((permit.recv(), dwell))
+    }
+
+    /// Receive a value from the channel like [`Self::recv`], along with its
+    /// sequence number: the write position it was sent at, see
+    /// [`Sender::write_position`].
+    ///
+    /// Values sent one after another have consecutive sequence numbers,
+    /// except where a [`Permit`] was reserved in between and dropped while
+    /// others were sent. Sequence numbers wrap around at `usize::MAX`.
+    ///
+    /// # Examples
+    /// ```
+    /// use atomic_mpmc::channel;
+    ///
+    /// let (sender, receiver) = channel::<i32>(2);
+    /// sender.send_all([1, 2]).unwrap();
+    ///
+    /// assert_eq!(receiver.recv_seq().unwrap(), (0, 1));
+    /// assert_eq!(receiver.recv_seq().unwrap(), (1, 2));
+    /// ```
+    pub fn recv_seq(&self) -> Result<(u64, T), RecvError> {
+        let permit = if self.0.blocking {
+            self.0.reserve_read(None, None)?
+        } else {
+            self.0.claim_read(None)?
+        };
+
+        let seq = permit.position() as u64;
+        Ok((seq, permit.recv()))
+    }
+
+    /// Receive the first buffered value matching a predicate, leaving the
+    /// others in place. This function will block the current thread until
+    /// a matching value is sent.
+    ///
+    /// The buffer is not compacted. Instead, the values before the match are
+    /// claimed one by one, and put back once it is found, to be received
+    /// before any others in their original order. Each call therefore looks
+    /// at every buffered value up to the match, and again after each new
+    /// send while blocked, so this is meant for short queues.
+    ///
+    /// The values are only in order for a single consumer. While a scan is in
+    /// progress, other receivers go on with the values after the ones it has
+    /// claimed, and values they put back are only looked at once something
+    /// new is sent.
+    ///
+    /// If all senders hang up before a match is found, this fails, and the
+    /// non-matching values are left in the channel.
+    ///
+    /// # Examples
+    /// ```
+    /// use atomic_mpmc::channel;
+    ///
+    /// let (sender, receiver) = channel::<i32>(4);
+    ///
+    /// sender.send_all([1, 2, 3, 4]).unwrap();
+    /// assert_eq!(receiver.recv_matching(|v| v % 2 == 0).unwrap(), 2);
+    /// assert_eq!(receiver.try_iter().collect::<Vec<_>>(), [1, 3, 4]);
+    /// ```
+    pub fn recv_matching<F: Fn(&T) -> bool>(&self, predicate: F) -> Result<T, RecvError> {
+        self.0.read_matching(predicate)
+    }
+
+    /// Attempt to receive a value from the channel. This function will return
+    /// `Err(RecvError(ErrorCause::WouldBlock))` if the channel is empty, or
+    /// `Err(RecvError(ErrorCause::HungUp))` if it is empty and all senders
+    /// have hung up, so a poller can reliably tell when to stop.
+    ///
+    /// # Examples
+    /// ```
+    /// use atomic_mpmc::channel;
+    ///
+    /// let (sender, receiver) = channel::<i32>(1);
+    ///
+    /// sender.send(1).unwrap();
+    /// assert_eq!(receiver.try_recv().unwrap(), 1);
+    /// assert!(receiver.try_recv().is_err());
+    /// ```
+    #[must_use = "ignoring the result drops the received value"]
+    pub fn try_recv(&self) -> Result<T, RecvError> {
+        self.0.try_read()
+    }
+
+    /// Receive a value from the channel, retrying up to `spins` times while
+    /// the channel is empty before blocking the current thread like
+    /// [`Self::recv`]. For more information, see [`Sender::send_spin`].
+    ///
+    /// # Examples
+    /// ```
+    /// use atomic_mpmc::channel;
+    ///
+    /// let (sender, receiver) = channel::<i32>(1);
+    ///
+    /// sender.send(1).unwrap();
+    /// assert_eq!(receiver.recv_spin(100).unwrap(), 1);
+    /// ```
+    pub fn recv_spin(&self, spins: u32) -> Result<T, RecvError> {
+        for _ in 0..spins {
+            match self.0.try_read() {
+                Err(RecvError(ErrorCause::WouldBlock)) => {}
+                result => return result,
+            }
+            hint::spin_loop();
+        }
+
+        self.0.read()
+    }
+
+    /// Receive a value from the channel, yielding the current thread up to
+    /// `yields` times while the channel is empty before blocking it like
+    /// [`Self::recv`]. Losing a value to another receiver also yields,
+    /// rather than retrying right away.
+    ///
+    /// Unlike [`Self::recv_spin`], this lets other threads on the same core
+    /// run in the meantime, which keeps receivers from starving the senders
+    /// they are waiting on when there are more threads than cores.
+    ///
+    /// # Examples
+    /// ```
+    /// use atomic_mpmc::channel;
+    ///
+    /// let (sender, receiver) = channel::<i32>(1);
+    ///
+    /// sender.send(1).unwrap();
+    /// assert_eq!(receiver.recv_yield(100).unwrap(), 1);
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn recv_yield(&self, yields: u32) -> Result<T, RecvError> {
+        for _ in 0..yields {
+            match self.0.try_read_yield() {
+                Err(RecvError(ErrorCause::WouldBlock)) => {}
+                result => return result,
+            }
+            std::thread::yield_now();
+        }
+
+        self.0.read()
+    }
+
+    /// Receive a value from the channel, blocking the current thread if the
+    /// channel is empty. Unlike [`Self::recv`], this function returns `None`
+    /// once the channel is hung up, which makes for tidy shutdown loops.
+    ///
+    /// # Examples
+    /// ```
+    /// use atomic_mpmc::channel;
+    ///
+    /// let (sender, receiver) = channel::<i32>(3);
+    ///
+    /// sender.send_all([1, 2, 3]).unwrap();
+    /// drop(sender);
+    ///
+    /// let mut sum = 0;
+    /// while let Some(value) = receiver.recv_or_closed() {
+    ///     sum += value;
+    /// }
+    /// assert_eq!(sum, 6);
+    /// ```
+    pub fn recv_or_closed(&self) -> Option<T> {
+        self.recv().ok()
+    }
+
+    /// Receive a value from the channel, blocking the current thread if the
+    /// channel is empty. Once the channel is hung up, this function calls `f`
+    /// for a fallback value instead of returning an error. `f` is not called
+    /// as long as there are values left to receive.
+    ///
+    /// # Examples
+    /// ```
+    /// use atomic_mpmc::channel;
+    ///
+    /// let (sender, receiver) = channel::<i32>(1);
+    ///
+    /// sender.send(1).unwrap();
+    /// drop(sender);
+    ///
+    /// assert_eq!(receiver.recv_or_else(|| 0), 1);
+    /// assert_eq!(receiver.recv_or_else(|| 0), 0);
+    /// ```
+    pub fn recv_or_else<F: FnOnce() -> T>(&self, f: F) -> T {
+        self.recv().unwrap_or_else(|_| f())
+    }
+
+    /// Receive a value from the channel, blocking the current thread for at
+    /// most `timeout` if the channel is empty. This function will return
+    /// `Err(RecvTimeoutError::Timeout)` if no value arrived in time, and
+    /// `Err(RecvTimeoutError::Disconnected)` if the channel is empty and
+    /// hung up or closed.
+    ///
+    /// # Examples
+    /// ```
+    /// use atomic_mpmc::{channel, RecvTimeoutError};
+    /// use std::time::Duration;
+    ///
+    /// let (sender, receiver) = channel::<i32>(1);
+    ///
+    /// sender.send(1).unwrap();
+    /// assert_eq!(receiver.recv_timeout(Duration::from_millis(10)).unwrap(), 1);
+    ///
+    /// let err = receiver.recv_timeout(Duration::from_millis(10)).unwrap_err();
+    /// assert_eq!(err, RecvTimeoutError::Timeout);
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn recv_timeout(&self, timeout: Duration) -> Result<T, RecvTimeoutError> {
+        Ok(self.0.read_until(Some(Instant::now() + timeout))?)
+    }
+
+    /// Receive a value from the channel like [`Self::recv_timeout`], along
+    /// with how long it actually took. This helps tune polling intervals.
+    ///
+    /// This function will return `Err(RecvError(ErrorCause::Timeout))` if no
+    /// value arrived in time, in which case the time taken is at least
+    /// `timeout`.
+    ///
+    /// # Examples
+    /// ```
+    /// use atomic_mpmc::{channel, ErrorCause};
+    /// use std::time::Duration;
+    ///
+    /// let (sender, receiver) = channel::<i32>(1);
+    ///
+    /// let (result, elapsed) = receiver.recv_timeout_elapsed(Duration::from_millis(10));
+    /// assert_eq!(result.unwrap_err().0, ErrorCause::Timeout);
+    /// assert!(elapsed >= Duration::from_millis(10));
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn recv_timeout_elapsed(&self, timeout: Duration) -> (Result<T, RecvError>, Duration) {
+        let start = Instant::now();
+        let result = self.0.read_until(Some(start + timeout));
+        (result, start.elapsed())
+    }
+
+    /// Receive values into `buf` until the deadline passes, returning how
+    /// many were received. This function will block the current thread
+    /// while the channel is empty.
+    ///
+    /// Returns early once the channel is empty and hung up or closed. No
+    /// error is reported, as the values received so far are more useful,
+    /// so follow up with [`Receiver::try_recv`] or [`Receiver::is_disconnected`]
+    /// if it matters.
+    ///
+    /// # Examples
+    /// ```
+    /// use atomic_mpmc::channel;
+    /// use std::time::{Duration, Instant};
+    ///
+    /// let (sender, receiver) = channel::<i32>(4);
+    /// sender.send_all([1, 2]).unwrap();
+    ///
+    /// let mut buf = Vec::new();
+    /// let deadline = Instant::now() + Duration::from_millis(10);
+    /// assert_eq!(receiver.recv_until(deadline, &mut buf), 2);
+    /// assert_eq!(buf, [1, 2]);
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn recv_until(&self, deadline: Instant, buf: &mut Vec<T>) -> usize {
+        let len = buf.len();
+        while Instant::now() < deadline {
+            match self.0.read_until(Some(deadline)) {
+                Ok(value) => buf.push(value),
+                Err(_) => break,
+            }
+        }
+
+        buf.len() - len
+    }
+
+    /// Receive up to `n` values from the channel, without blocking. Returns
+    /// fewer values, or none, if the channel doesn't have that many.
+    ///
+    /// Unlike receiving them one by one, the values which are ready are
+    /// claimed all at once.
+    ///
+    /// # Examples
+    /// ```
+    /// use atomic_mpmc::channel;
+    ///
+    /// let (sender, receiver) = channel::<i32>(4);
+    /// sender.send_all([1, 2, 3]).unwrap();
+    ///
+    /// assert_eq!(receiver.try_recv_n(2), [1, 2]);
+    /// assert_eq!(receiver.try_recv_n(2), [3]);
+    /// assert!(receiver.try_recv_n(2).is_empty());
+    /// ```
+    pub fn try_recv_n(&self, n: usize) -> Vec<T> {
+        self.0.try_read_n(n)
+    }
+
+    /// Receive values into `dst`, blocking until there is at least one, and
+    /// then copying every other pending value that fits, without blocking
+    /// again. Returns how many values were received, which is at least one,
+    /// unless `dst` is empty.
+    ///
+    /// The values ready at once are claimed all together, and copied straight
+    /// into `dst`, a run of slots at a time.
+    ///
+    /// # Examples
+    /// ```
+    /// use atomic_mpmc::channel;
+    ///
+    /// let (sender, receiver) = channel::<u8>(4);
+    /// sender.send_all(*b"abc").unwrap();
+    ///
+    /// let mut buf = [0; 8];
+    /// assert_eq!(receiver.recv_copy_into(&mut buf).unwrap(), 3);
+    /// assert_eq!(&buf[..3], b"abc");
+    /// ```
+    pub fn recv_copy_into(&self, dst: &mut [T]) -> Result<usize, RecvError>
+    where
+        T: Copy,
+    {
+        if dst.is_empty() {
+            return Ok(0);
+        }
+
+        let copied = self.0.try_read_copy(dst);
+        if copied > 0 {
+            return Ok(copied);
+        }
+
+        // Nothing was ready, so block for one value, then top up.
+        dst[0] = self.recv()?;
+        Ok(1 + self.0.try_read_copy(&mut dst[1..]))
+    }
+
+    /// Receive a value into `buf`, blocking until there is one, and then every
+    /// other pending value, without blocking again. Returns how many values
+    /// were received, which is at least one.
+    ///
+    /// This saves waking up for every value when they come in bursts.
+    ///
+    /// # Examples
+    /// ```
+    /// use atomic_mpmc::channel;
+    ///
+    /// let (sender, receiver) = channel::<i32>(4);
+    /// sender.send_all([1, 2, 3]).unwrap();
+    ///
+    /// let mut buf = Vec::new();
+    /// assert_eq!(receiver.recv_available(&mut buf).unwrap(), 3);
+    /// assert_eq!(buf, [1, 2, 3]);
+    /// ```
+    pub fn recv_available(&self, buf: &mut Vec<T>) -> Result<usize, RecvError> {
+        let len = buf.len();
+        buf.push(self.recv()?);
+        buf.extend(self.try_iter());
+
+        Ok(buf.len() - len)
+    }
+
+    /// Receive exactly `n` values into `buf`, blocking until all of them
+    /// are there. If the channel hangs up first, this function returns the
+    /// error, and leaves the values received so far in `buf`.
+    ///
+    /// # Examples
+    /// ```
+    /// use atomic_mpmc::{channel, ErrorCause};
+    ///
+    /// let (sender, receiver) = channel::<i32>(4);
+    /// sender.send_all([1, 2, 3]).unwrap();
+    ///
+    /// let mut buf = Vec::new();
+    /// receiver.recv_exact(2, &mut buf).unwrap();
+    /// assert_eq!(buf, [1, 2]);
+    ///
+    /// drop(sender);
+    /// buf.clear();
+    /// assert_eq!(receiver.recv_exact(2, &mut buf).unwrap_err().0, ErrorCause::HungUp);
+    /// assert_eq!(buf, [3]);
+    /// ```
+    pub fn recv_exact(&self, n: usize, buf: &mut Vec<T>) -> Result<(), RecvError> {
+        buf.reserve(n);
+        for _ in 0..n {
+            buf.push(self.recv()?);
+        }
+
+        Ok(())
+    }
+
+    /// Receive exactly `n` values, blocking until all of them are there, and
+    /// return them in the order they were received. This fits a fan-in, where
+    /// a known number of producers each send one result.
+    ///
+    /// If the channel hangs up first, this function returns the values
+    /// received so far in the [`PartialRecvError`].
+    ///
+    /// # Examples
+    /// ```
+    /// use atomic_mpmc::{channel, ErrorCause};
+    /// use std::thread;
+    ///
+    /// let (sender, receiver) = channel::<i32>(4);
+    ///
+    /// for i in 0..3 {
+    ///     let sender = sender.clone();
+    ///     thread::spawn(move || sender.send(i).unwrap());
+    /// }
+    /// drop(sender);
+    ///
+    /// let mut values = receiver.recv_n(3).unwrap();
+    /// values.sort();
+    /// assert_eq!(values, [0, 1, 2]);
+    ///
+    /// let err = receiver.recv_n(1).unwrap_err();
+    /// assert_eq!(err.1, ErrorCause::HungUp);
+    /// assert!(err.0.is_empty());
+    /// ```
+    pub fn recv_n(&self, n: usize) -> Result<Vec<T>, PartialRecvError<T>> {
+        let mut values = Vec::new();
+        match self.recv_exact(n, &mut values) {
+            Ok(()) => Ok(values),
+            Err(RecvError(cause)) => Err(PartialRecvError(values, cause)),
+        }
+    }
+
+    /// Attempt to receive a value from the channel, registering the task to
+    /// be woken up if the channel is empty. This is the building block for
+    /// futures and streams of the channel's values.
+    ///
+    /// Returns `Poll::Ready` with a value, or with an error once the channel
+    /// is hung up, and `Poll::Pending` otherwise.
+    ///
+    /// # Examples
+    /// ```
+    /// use atomic_mpmc::channel;
+    /// use futures::task::{noop_waker, Context, Poll};
+    ///
+    /// let (sender, receiver) = channel::<i32>(1);
+    /// let waker = noop_waker();
+    /// let mut cx = Context::from_waker(&waker);
+    ///
+    /// assert!(receiver.poll_recv(&mut cx).is_pending());
+    /// sender.send(1).unwrap();
+    /// assert_eq!(receiver.poll_recv(&mut cx), Poll::Ready(Ok(1)));
+    /// ```
+    #[cfg(feature = "async")]
+    pub fn poll_recv(&self, cx: &mut Context<'_>) -> Poll<Result<T, RecvError>> {
+        match self.0.try_read() {
+            Err(RecvError(ErrorCause::WouldBlock)) => {}
+            result => return Poll::Ready(result),
+        }
+
+        // Check again after registering, so a value sent in between can't
+        // be missed.
+        self.0.readable.register(cx.waker());
+        match self.0.try_read() {
+            Err(RecvError(ErrorCause::WouldBlock)) => Poll::Pending,
+            result => Poll::Ready(result),
+        }
+    }
+
+    /// Turn this receiver into a stream of the values of the channel, which
+    /// owns it and can be spawned as a task. The stream ends once the channel
+    /// is hung up and empty. For more information, see [`RecvStream`].
+    ///
+    /// # Examples
+    /// ```
+    /// use atomic_mpmc::channel;
+    /// use futures::{executor::block_on, StreamExt};
+    ///
+    /// let (sender, receiver) = channel::<i32>(2);
+    ///
+    /// sender.send_all([1, 2]).unwrap();
+    /// drop(sender);
+    ///
+    /// assert_eq!(block_on(receiver.into_stream().collect::<Vec<_>>()), [1, 2]);
+    /// ```
+    #[cfg(feature = "async")]
+    pub fn into_stream(self) -> RecvStream<T> {
+        RecvStream::new(self)
+    }
+
+    /// Block the current thread until the channel likely has a value, or all
+    /// senders have hung up, without receiving anything.
+    ///
+    /// With multiple receivers, another one may take the value before this
+    /// thread gets to it, so a following [`Self::try_recv`] can still fail.
+    ///
+    /// # Examples
+    /// ```
+    /// use atomic_mpmc::channel;
+    ///
+    /// let (sender, receiver) = channel::<i32>(1);
+    ///
+    /// sender.send(1).unwrap();
+    /// receiver.wait_readable();
+    /// assert_eq!(receiver.try_recv().unwrap(), 1);
+    /// ```
+    pub fn wait_readable(&self) {
+        self.0.wait_readable()
+    }
+
+    /// Receive a value from the channel, blocking the current thread if the
+    /// channel is empty, until `cancel` is set. This function will return
+    /// `Err(RecvError(ErrorCause::Cancelled))` if it gave up because of that.
+    ///
+    /// Setting the flag doesn't wake the thread up, so it checks the flag at
+    /// least every 10 milliseconds while blocked. The channel itself is left
+    /// untouched by the cancellation.
+    ///
+    /// # Examples
+    /// ```
+    /// use atomic_mpmc::{channel, ErrorCause};
+    /// use std::sync::atomic::AtomicBool;
+    ///
+    /// let (sender, receiver) = channel::<i32>(1);
+    /// let cancel = AtomicBool::new(true);
+    ///
+    /// sender.send(1).unwrap();
+    /// assert_eq!(receiver.recv_cancellable(&cancel).unwrap(), 1);
+    /// assert_eq!(receiver.recv_cancellable(&cancel).unwrap_err().0, ErrorCause::Cancelled);
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn recv_cancellable(&self, cancel: &AtomicBool) -> Result<T, RecvError> {
+        self.0
+            .read_cancellable(None, Some((cancel, Duration::from_millis(10))))
+    }
+
+    /// Receive a value from the channel, blocking the current thread if the
+    /// channel is empty, until `flag` is set. This function will return
+    /// `Err(RecvError(ErrorCause::Cancelled))` if it gave up because of that.
+    ///
+    /// Like [`Self::recv_cancellable`], but checks the flag at least every
+    /// `poll_interval` while blocked, trading how soon it notices for how
+    /// often the thread wakes up.
+    ///
+    /// # Examples
+    /// ```
+    /// use atomic_mpmc::{channel, ErrorCause};
+    /// use std::{sync::atomic::{AtomicBool, Ordering}, thread, time::Duration};
+    ///
+    /// let (sender, receiver) = channel::<i32>(1);
+    /// let flag = AtomicBool::new(false);
+    ///
+    /// thread::scope(|scope| {
+    ///     scope.spawn(|| {
+    ///         thread::sleep(Duration::from_millis(10));
+    ///         flag.store(true, Ordering::Release);
+    ///     });
+    ///
+    ///     let err = receiver.recv_interruptible(&flag, Duration::from_millis(1));
+    ///     assert_eq!(err.unwrap_err().0, ErrorCause::Cancelled);
+    /// });
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn recv_interruptible(
+        &self,
+        flag: &AtomicBool,
+        poll_interval: Duration,
+    ) -> Result<T, RecvError> {
+        self.0.read_cancellable(None, Some((flag, poll_interval)))
+    }
+
+    /// Claim the next value in the channel, blocking the current thread if the
+    /// channel is empty. The returned [`RecvPermit`] receives the value without
+    /// blocking, or leaves it in the channel when dropped.
+    ///
+    /// This lets the caller commit to a value only once it is ready to handle
+    /// it. While the permit is held, other receivers go on with the values
+    /// after it. A value left in the channel by a dropped permit is the next
+    /// one received, even if later values were received in the meantime.
+    ///
+    /// Outstanding permits keep their values' slots taken, and growing the
+    /// channel waits for them, so don't hold on to them for long.
+    ///
+    /// # Examples
+    /// ```
+    /// use atomic_mpmc::channel;
+    ///
+    /// let (sender, receiver) = channel::<i32>(2);
+    /// sender.send_all([1, 2]).unwrap();
+    ///
+    /// let permit = receiver.reserve().unwrap();
+    /// assert_eq!(receiver.recv().unwrap(), 2);
+    ///
+    /// // Dropping a permit leaves its value in the channel.
+    /// drop(permit);
+    /// assert_eq!(receiver.recv().unwrap(), 1);
+    /// ```
+    pub fn reserve(&self) -> Result<RecvPermit<'_, T>, RecvError> {
+        self.0.reserve_read(None, None)
+    }
+
+    /// Attempt to claim the next value in the channel. This function will
+    /// return `Err(RecvError(ErrorCause::WouldBlock))` if the channel is empty.
+    ///
+    /// See [`Receiver::reserve`] for details.
+    ///
+    /// # Examples
+    /// ```
+    /// use atomic_mpmc::channel;
+    ///
+    /// let (sender, receiver) = channel::<i32>(1);
+    /// receiver.try_reserve().unwrap_err();
+    ///
+    /// sender.send(1).unwrap();
+    /// assert_eq!(receiver.try_reserve().unwrap().recv(), 1);
+    /// ```
+    pub fn try_reserve(&self) -> Result<RecvPermit<'_, T>, RecvError> {
+        self.0.claim_read(None)
+    }
+
+    /// Attempt to look at the next value in the channel without receiving it.
+    /// This function will return `Err(RecvError(ErrorCause::WouldBlock))` if
+    /// the channel is empty.
+    ///
+    /// The value is set aside while the returned [`PeekGuard`] is held, so
+    /// with multiple consumers, others may receive later values in the
+    /// meantime. See [`Receiver::reserve`] for details.
+    ///
+    /// # Examples
+    /// ```
+    /// use atomic_mpmc::{channel, ErrorCause};
+    ///
+    /// let (sender, receiver) = channel::<i32>(2);
+    /// assert_eq!(receiver.try_peek().unwrap_err().0, ErrorCause::WouldBlock);
+    ///
+    /// sender.send(1).unwrap();
+    /// if *receiver.try_peek().unwrap() == 1 {
+    ///     assert_eq!(receiver.recv().unwrap(), 1);
+    /// }
+    /// ```
+    pub fn try_peek(&self) -> Result<PeekGuard<'_, T>, RecvError> {
+        self.0.claim_read(None).map(PeekGuard::new)
+    }
+
+    /// Receive a value from the channel to change it in place, and then
+    /// either keep it or send it again. This function will block the current
+    /// thread if the channel is empty.
+    ///
+    /// This is meant for values which take several passes to process: each
+    /// pass can update the value, and [`RequeueGuard::requeue`] it behind the
+    /// others for the next one.
+    ///
+    /// # Examples
+    /// ```
+    /// use atomic_mpmc::channel;
+    ///
+    /// let (sender, receiver) = channel::<i32>(1);
+    /// sender.send(1).unwrap();
+    ///
+    /// let mut guard = receiver.recv_ref_mut().unwrap();
+    /// *guard *= 2;
+    /// assert_eq!(guard.take(), 2);
+    /// ```
+    pub fn recv_ref_mut(&self) -> Result<RequeueGuard<'_, T>, RecvError> {
+        self.reserve().map(RequeueGuard::new)
+    }
+
+    /// Close the channel for everyone, giving a reason.
+    /// For more information, see [`Sender::close_with`].
+    ///
+    /// # Examples
+    /// ```
+    /// use atomic_mpmc::{channel, ErrorCause};
+    ///
+    /// let (sender, receiver) = channel::<i32>(1);
+    ///
+    /// receiver.close_with("no more work");
+    /// assert_eq!(sender.send(1).unwrap_err().1, ErrorCause::Closed("no more work".into()));
+    /// ```
+    pub fn close_with(&self, reason: impl Into<Arc<str>>) {
+        self.0.close(reason.into())
+    }
+
+    /// Returns `true` if all senders have hung up, or one of them called
+    /// [`Sender::finish`], so nothing more will be sent. Values sent before
+    /// may still be buffered. Closing the channel doesn't count.
     ///
     /// # Examples
     /// ```
     /// use atomic_mpmc::channel;
     ///
     /// let (sender, receiver) = channel::<i32>(1);
+    /// assert!(!receiver.is_disconnected());
+    ///
+    /// drop(sender);
+    /// assert!(receiver.is_disconnected());
+    /// ```
+    pub fn is_disconnected(&self) -> bool {
+        self.0.senders_gone()
+    }
+
+    /// Returns the number of values in the channel.
+    /// For more information, see [`Sender::len_clamped`].
+    ///
+    /// # Examples
+    /// ```
+    /// use atomic_mpmc::channel;
+    ///
+    /// let (sender, receiver) = channel::<i32>(2);
+    ///
+    /// sender.send_all([1, 2]).unwrap();
+    /// receiver.recv().unwrap();
+    /// assert_eq!(receiver.len_clamped(), 1);
+    /// ```
+    pub fn len_clamped(&self) -> usize {
+        let _gate = self.0.enter();
+        self.0.len()
+    }
+
+    /// Returns the number of values the channel has room for.
+    /// For more information, see [`Sender::capacity`].
+    ///
+    /// # Examples
+    /// ```
+    /// use atomic_mpmc::channel;
+    ///
+    /// let (sender, receiver) = channel::<i32>(4);
     ///
-    /// sender.send(1).unwrap();
-    /// sender.try_send(2).unwrap_err();
+    /// assert_eq!(receiver.capacity(), 4);
     /// ```
-    pub fn try_send(&self, value: T) -> Result<(), SendError<T>> {
-        self.0.try_write(value)
-    }
-}
-
-impl<T> Drop for Sender<T> {
-    fn drop(&mut self) {
-        self.0.senders.fetch_sub(1, Ordering::Relaxed);
+    pub fn capacity(&self) -> usize {
+        let _gate = self.0.enter();
+        self.0.capacity()
     }
-}
 
-impl<T> Clone for Sender<T> {
-    fn clone(&self) -> Self {
-        Self::new(self.0.clone())
+    /// Returns the id of the channel, which is the same for all of its
+    /// senders and receivers. For more information, see [`ChannelId`].
+    pub fn id(&self) -> ChannelId {
+        ChannelId::new(&self.0)
     }
-}
-
-/// A receiver for a MPMC channel.
-///
-/// This struct is created by the [`channel`] function. It provides methods for
-/// receiving data from the channel.
-///
-/// This struct implements the [`IntoIterator`] trait, which means that you can
-/// convert it to an iterator over received values.
-#[derive(Debug)]
-pub struct Receiver<T>(Arc<Channel<T>>);
 
-impl<T> Receiver<T> {
-    fn new(channel: Arc<Channel<T>>) -> Receiver<T> {
-        channel.receivers.fetch_add(1, Ordering::Relaxed);
-        Receiver(channel)
+    /// Returns the name of the channel, if it was given one with
+    /// [`channel_named`] or [`ChannelBuilder::name`].
+    ///
+    /// # Examples
+    /// ```
+    /// use atomic_mpmc::channel_named;
+    ///
+    /// let (sender, receiver) = channel_named::<i32>(1, "jobs");
+    ///
+    /// assert_eq!(sender.name(), Some("jobs"));
+    /// assert_eq!(receiver.name(), Some("jobs"));
+    /// ```
+    pub fn name(&self) -> Option<&str> {
+        self.0.name.as_deref()
     }
 
-    /// Receive a value from the channel. This function will block the current
-    /// thread if the channel is empty.
+    /// Returns the number of slots claimed for reading so far, see
+    /// [`Sender::write_position`]. A dropped [`RecvPermit`] can move it back
+    /// by one.
     ///
     /// # Examples
     /// ```
     /// use atomic_mpmc::channel;
     ///
-    /// let (sender, receiver) = channel::<i32>(1);
+    /// let (sender, receiver) = channel::<i32>(2);
     ///
     /// sender.send(1).unwrap();
-    /// assert_eq!(receiver.recv().unwrap(), 1);
+    /// receiver.recv().unwrap();
+    /// assert_eq!(receiver.read_position(), 1);
     /// ```
-    pub fn recv(&self) -> Result<T, RecvError> {
-        self.0.read()
+    pub fn read_position(&self) -> usize {
+        self.0.read.load(Ordering::Relaxed)
     }
 
-    /// Attempt to receive a value from the channel. This function will return
-    /// `Err(RecvError(ErrorCause::WouldBlock))` if the channel is empty.
+    /// Returns how many times a receive had to block because the channel
+    /// was empty. Every blocking receive counts once, however long it waited.
     ///
     /// # Examples
     /// ```
     /// use atomic_mpmc::channel;
+    /// use std::time::Duration;
     ///
     /// let (sender, receiver) = channel::<i32>(1);
     ///
-    /// sender.send(1).unwrap();
-    /// assert_eq!(receiver.try_recv().unwrap(), 1);
-    /// assert!(receiver.try_recv().is_err());
+    /// receiver.recv_timeout(Duration::from_millis(10)).unwrap_err();
+    /// assert_eq!(receiver.block_count(), 1);
     /// ```
-    pub fn try_recv(&self) -> Result<T, RecvError> {
-        self.0.try_read()
+    #[cfg(feature = "metrics")]
+    pub fn block_count(&self) -> u64 {
+        self.0.read_blocks.load(Ordering::Relaxed)
     }
 
     /// Creates an iterator over the values of this channel.
@@ -412,11 +3377,263 @@ impl<T> Receiver<T> {
         TryIter::new(self)
     }
 
+    /// Folds every pending value of the channel into an accumulator, without
+    /// blocking. For more information, see [`Self::try_iter`].
+    ///
+    /// # Examples
+    /// ```
+    /// use atomic_mpmc::channel;
+    ///
+    /// let (sender, receiver) = channel::<i32>(3);
+    ///
+    /// sender.send_all([1, 2, 3]).unwrap();
+    /// assert_eq!(receiver.fold_pending(0, |sum, v| sum + v), 6);
+    /// ```
+    pub fn fold_pending<B, F: FnMut(B, T) -> B>(&self, init: B, f: F) -> B {
+        self.try_iter().fold(init, f)
+    }
+
+    /// Folds every value of the channel into an accumulator, blocking until
+    /// the channel is hung up. For more information, see [`Self::iter`].
+    ///
+    /// # Examples
+    /// ```
+    /// use atomic_mpmc::channel;
+    ///
+    /// let (sender, receiver) = channel::<i32>(3);
+    ///
+    /// sender.send_all([1, 2, 3]).unwrap();
+    /// drop(sender);
+    /// assert_eq!(receiver.fold_until_hangup(1, |product, v| product * v), 6);
+    /// ```
+    pub fn fold_until_hangup<B, F: FnMut(B, T) -> B>(&self, init: B, f: F) -> B {
+        self.iter().fold(init, f)
+    }
+
+    /// Drops every value in the channel, without receiving them. Values sent
+    /// while clearing are left in the channel. Returns the number of values
+    /// dropped.
+    ///
+    /// # Examples
+    /// ```
+    /// use atomic_mpmc::channel;
+    ///
+    /// let (sender, receiver) = channel::<i32>(3);
+    ///
+    /// sender.send_all([1, 2, 3]).unwrap();
+    /// assert_eq!(receiver.clear(), 3);
+    /// receiver.try_recv().unwrap_err();
+    /// ```
+    pub fn clear(&self) -> usize {
+        self.0.clear()
+    }
+
+    /// Creates a new iterator over the values which are pending right now.
+    ///
+    /// Unlike [`Self::try_iter`], values sent after the iterator was created
+    /// are left for later, which bounds the work done by each iteration.
+    ///
+    /// # Examples
+    /// ```
+    /// use atomic_mpmc::channel;
+    ///
+    /// let (sender, receiver) = channel::<i32>(4);
+    ///
+    /// sender.send_all([1, 2]).unwrap();
+    ///
+    /// let mut iter = receiver.try_iter_snapshot();
+    /// assert_eq!(iter.next().unwrap(), 1);
+    /// sender.send(3).unwrap();
+    /// assert_eq!(iter.next().unwrap(), 2);
+    /// assert!(iter.next().is_none());
+    ///
+    /// assert_eq!(receiver.try_recv().unwrap(), 3);
+    /// ```
+    pub fn try_iter_snapshot(&self) -> SnapshotIter<'_, T> {
+        SnapshotIter::new(self, self.0.write.load(Ordering::Acquire))
+    }
+
+    /// Creates an iterator over batches of values of this channel.
+    ///
+    /// Each batch waits for a first value, then keeps collecting values until
+    /// it holds `max` of them or `window` has elapsed since the first one
+    /// arrived, whichever comes first. Iteration ends when the channel is
+    /// hung up and empty.
+    ///
+    /// # Panics
+    /// Panics if `max` is zero, as every batch holds at least one value.
+    ///
+    /// # Examples
+    /// ```
+    /// use atomic_mpmc::channel;
+    /// use std::time::Duration;
+    ///
+    /// let (sender, receiver) = channel::<i32>(8);
+    ///
+    /// sender.send_all([1, 2, 3, 4, 5]).unwrap();
+    /// drop(sender);
+    ///
+    /// let mut batches = receiver.batches(2, Duration::from_millis(10));
+    /// assert_eq!(batches.next().unwrap(), [1, 2]);
+    /// assert_eq!(batches.next().unwrap(), [3, 4]);
+    /// assert_eq!(batches.next().unwrap(), [5]);
+    /// assert!(batches.next().is_none());
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn batches(&self, max: usize, window: Duration) -> Batches<&Self> {
+        assert!(
+            max > 0,
+            "batches must be allowed to hold at least one value"
+        );
+        Batches::new(self, max, window)
+    }
+
+    /// Creates an iterator over the values of this channel, which ends once
+    /// no value arrived for `timeout` since the last one, or the channel is
+    /// hung up. Unlike a fixed deadline, the timer starts over with every
+    /// value received.
+    ///
+    /// # Examples
+    /// ```
+    /// use atomic_mpmc::channel;
+    /// use std::time::Duration;
+    ///
+    /// let (sender, receiver) = channel::<i32>(4);
+    ///
+    /// sender.send_all([1, 2]).unwrap();
+    ///
+    /// let mut iter = receiver.iter_idle_timeout(Duration::from_millis(10));
+    /// assert_eq!(iter.next(), Some(1));
+    /// assert_eq!(iter.next(), Some(2));
+    /// assert_eq!(iter.next(), None);
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn iter_idle_timeout(&self, timeout: Duration) -> IdleIter<&Self> {
+        IdleIter::new(self, timeout)
+    }
+
     /// Turn this channel into an iterator over pending values.
     /// For more information, see [`Self::try_iter`].
     pub fn into_try_iter(self) -> TryIter<Self> {
         TryIter::new(self)
     }
+
+    /// Drop this receiver, returning the values pending in the channel, in
+    /// order.
+    ///
+    /// Only the values sent before the call are taken, so busy senders can't
+    /// keep it going. If other receivers are still around, they may take some
+    /// of those values first, and the rest are left for them.
+    ///
+    /// # Examples
+    /// ```
+    /// use atomic_mpmc::channel;
+    ///
+    /// let (sender, receiver) = channel::<i32>(4);
+    ///
+    /// sender.send_all([1, 2]).unwrap();
+    /// assert_eq!(receiver.into_remaining(), [1, 2]);
+    /// ```
+    pub fn into_remaining(self) -> Vec<T> {
+        self.try_iter_snapshot().collect()
+    }
+
+    /// Turn this receiver into an iterator over the values pending in the
+    /// channel, which ends once it has yielded them, without waiting for
+    /// more. Works like [`Self::try_iter_snapshot`], and drops the receiver
+    /// at the end.
+    ///
+    /// # Examples
+    /// ```
+    /// use atomic_mpmc::channel;
+    ///
+    /// let (sender, receiver) = channel::<i32>(4);
+    ///
+    /// sender.send_all([1, 2]).unwrap();
+    /// let mut iter = receiver.drain_into_iter();
+    /// sender.send(3).unwrap();
+    ///
+    /// assert_eq!(iter.collect::<Vec<_>>(), [1, 2]);
+    /// ```
+    pub fn drain_into_iter(self) -> DrainIter<T> {
+        let end = self.0.write.load(Ordering::Acquire);
+        DrainIter::new(self, end)
+    }
+
+    /// Turn this channel into a receiver which transforms every value it
+    /// receives. The result can be iterated over like the channel itself.
+    ///
+    /// # Examples
+    /// ```
+    /// use atomic_mpmc::channel;
+    ///
+    /// let (sender, receiver) = channel::<i32>(2);
+    /// let receiver = receiver.map(|v| v * 10);
+    ///
+    /// sender.send_all([1, 2]).unwrap();
+    /// drop(sender);
+    ///
+    /// assert_eq!(receiver.into_iter().collect::<Vec<_>>(), [10, 20]);
+    /// ```
+    pub fn map<U, F: Fn(T) -> U>(self, f: F) -> Map<Self, F> {
+        Map::new(self, f)
+    }
+
+    /// Turn this channel into a receiver which skips the values not matching
+    /// a predicate. The result can be iterated over like the channel itself.
+    ///
+    /// # Examples
+    /// ```
+    /// use atomic_mpmc::channel;
+    ///
+    /// let (sender, receiver) = channel::<i32>(4);
+    /// let receiver = receiver.filter(|v| v % 2 == 0);
+    ///
+    /// sender.send_all([1, 2, 3, 4]).unwrap();
+    /// assert_eq!(receiver.try_iter().collect::<Vec<_>>(), [2, 4]);
+    /// ```
+    pub fn filter<P: Fn(&T) -> bool>(self, predicate: P) -> Filter<Self, P> {
+        Filter::new(self, predicate)
+    }
+
+    /// Turn this channel into a receiver which transforms every value it
+    /// receives, and skips the ones which transform into `None`. The result
+    /// can be iterated over like the channel itself.
+    ///
+    /// # Examples
+    /// ```
+    /// use atomic_mpmc::channel;
+    ///
+    /// let (sender, receiver) = channel::<&str>(3);
+    /// let receiver = receiver.filter_map(|v| v.parse::<i32>().ok());
+    ///
+    /// sender.send_all(["1", "two", "3"]).unwrap();
+    /// assert_eq!(receiver.try_iter().collect::<Vec<_>>(), [1, 3]);
+    /// ```
+    pub fn filter_map<U, F: Fn(T) -> Option<U>>(self, f: F) -> FilterMap<Self, F> {
+        FilterMap::new(self, f)
+    }
+
+    /// Turn this channel into a receiver which skips the values equal to the
+    /// one received before, so only changes come through. The result can be
+    /// iterated over like the channel itself.
+    ///
+    /// # Examples
+    /// ```
+    /// use atomic_mpmc::channel;
+    ///
+    /// let (sender, receiver) = channel::<i32>(4);
+    /// let receiver = receiver.dedup();
+    ///
+    /// sender.send_all([1, 1, 2, 1]).unwrap();
+    /// assert_eq!(receiver.try_iter().collect::<Vec<_>>(), [1, 2, 1]);
+    /// ```
+    pub fn dedup(self) -> Dedup<Self>
+    where
+        T: PartialEq + Clone,
+    {
+        Dedup::new(self)
+    }
 }
 
 impl<T> IntoIterator for Receiver<T> {
@@ -430,7 +3647,7 @@ impl<T> IntoIterator for Receiver<T> {
 
 impl<T> Drop for Receiver<T> {
     fn drop(&mut self) {
-        self.0.receivers.fetch_sub(1, Ordering::Relaxed);
+        self.0.remove_receiver();
     }
 }
 
@@ -440,6 +3657,12 @@ impl<T> Clone for Receiver<T> {
     }
 }
 
+impl<T> fmt::Debug for Receiver<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("Receiver").field(&self.0).finish()
+    }
+}
+
 /// Creates a multi-producer, multi-consumer channel.
 ///
 /// The channel will have a buffer of size `capacity`
@@ -449,6 +3672,12 @@ impl<T> Clone for Receiver<T> {
 /// cloneable and implement [`Send`], [`Sync`], and [`Clone`], meaning
 /// that they can be used across thread boundaries.
 ///
+/// # Panics
+/// Panics if `capacity` is zero. Such a channel would never have room for a
+/// value, so it is not a rendezvous channel, and every send would block. Use
+/// [`channel_nonzero`] to rule it out at compile time, or
+/// [`ChannelBuilder::growable`] for a channel which starts out empty.
+///
 /// # Examples
 /// ```
 /// use atomic_mpmc::channel;
@@ -463,6 +3692,216 @@ impl<T> Clone for Receiver<T> {
 /// assert_eq!(value, 1);
 /// ```
 pub fn channel<T>(capacity: usize) -> (Sender<T>, Receiver<T>) {
-    let channel = Arc::new(Channel::new(capacity));
+    ChannelBuilder::new(capacity).build()
+}
+
+/// Creates a multi-producer, multi-consumer channel, which is guaranteed to
+/// have room for at least one value.
+///
+/// Works like [`channel`], but can't be given a capacity of zero, so it never
+/// panics.
+///
+/// # Examples
+/// ```
+/// use atomic_mpmc::channel_nonzero;
+/// use std::num::NonZeroUsize;
+///
+/// let (sender, receiver) = channel_nonzero::<i32>(NonZeroUsize::new(1).unwrap());
+///
+/// sender.send(1).unwrap();
+/// assert_eq!(receiver.recv().unwrap(), 1);
+/// ```
+pub fn channel_nonzero<T>(capacity: NonZeroUsize) -> (Sender<T>, Receiver<T>) {
+    channel(capacity.get())
+}
+
+/// Creates a bounded multi-producer, multi-consumer channel.
+///
+/// This is an alias of [`channel`], for those who prefer to spell out that
+/// the channel's buffer has a fixed size.
+///
+/// # Examples
+/// ```
+/// use atomic_mpmc::bounded;
+///
+/// let (sender, receiver) = bounded::<i32>(1);
+///
+/// sender.send(1).unwrap();
+/// assert_eq!(receiver.recv().unwrap(), 1);
+/// ```
+pub fn bounded<T>(capacity: usize) -> (Sender<T>, Receiver<T>) {
+    channel(capacity)
+}
+
+/// Creates a multi-producer, multi-consumer channel like [`channel`], and
+/// also returns the capacity it ended up with, for logging it. This is the
+/// same as [`Sender::capacity`] of the returned sender.
+///
+/// # Examples
+/// ```
+/// use atomic_mpmc::channel_logged;
+///
+/// let (sender, receiver, capacity) = channel_logged::<i32>(10);
+///
+/// assert_eq!(capacity, 10);
+/// assert_eq!(sender.capacity(), capacity);
+/// ```
+pub fn channel_logged<T>(capacity: usize) -> (Sender<T>, Receiver<T>, usize) {
+    let (sender, receiver) = channel(capacity);
+    let capacity = sender.capacity();
+    (sender, receiver, capacity)
+}
+
+/// Creates a multi-producer, multi-consumer channel with a name, which both
+/// halves report with `name`, and which shows up in their debug output.
+///
+/// Works like [`channel`], and is short for [`ChannelBuilder::name`].
+///
+/// # Examples
+/// ```
+/// use atomic_mpmc::channel_named;
+///
+/// let (sender, receiver) = channel_named::<i32>(1, "jobs");
+///
+/// assert_eq!(sender.name(), Some("jobs"));
+/// assert!(format!("{receiver:?}").contains("jobs"));
+/// ```
+pub fn channel_named<T>(capacity: usize, name: impl Into<Arc<str>>) -> (Sender<T>, Receiver<T>) {
+    ChannelBuilder::new(capacity).name(name).build()
+}
+
+/// Creates a multi-producer, multi-consumer channel, with values from an
+/// iterator already buffered.
+///
+/// Works like [`channel`], but the values are readable right away. At most
+/// `capacity` values are taken from the iterator, and the rest are ignored.
+///
+/// # Panics
+/// Panics if `capacity` is zero, like [`channel`].
+///
+/// # Examples
+/// ```
+/// use atomic_mpmc::channel_from_iter;
+///
+/// let (sender, receiver) = channel_from_iter([1, 2], 3);
+///
+/// sender.send(3).unwrap();
+/// assert_eq!(receiver.try_iter().collect::<Vec<_>>(), [1, 2, 3]);
+/// ```
+pub fn channel_from_iter<T, I: IntoIterator<Item = T>>(
+    iter: I,
+    capacity: usize,
+) -> (Sender<T>, Receiver<T>) {
+    let mut channel = Channel::new(ChannelBuilder::new(capacity));
+    channel.prefill(iter);
+
+    let channel = sync::Arc::new(channel);
     (Sender::new(channel.clone()), Receiver::new(channel))
 }
+
+/// Creates a multi-producer, multi-consumer channel, with the values of a
+/// vector already buffered in order.
+///
+/// The channel's capacity is the length of the vector plus `extra_capacity`,
+/// so every value fits, with room for `extra_capacity` more on top.
+///
+/// # Panics
+/// Panics if the vector is empty and `extra_capacity` is zero, like
+/// [`channel`] does for a capacity of zero.
+///
+/// # Examples
+/// ```
+/// use atomic_mpmc::bounded_from_vec;
+///
+/// let (sender, receiver) = bounded_from_vec(vec![1, 2], 1);
+///
+/// sender.send(3).unwrap();
+/// sender.try_send(4).unwrap_err();
+/// assert_eq!(receiver.try_iter().collect::<Vec<_>>(), [1, 2, 3]);
+/// ```
+pub fn bounded_from_vec<T>(values: Vec<T>, extra_capacity: usize) -> (Sender<T>, Receiver<T>) {
+    let capacity = values.len() + extra_capacity;
+    channel_from_iter(values, capacity)
+}
+
+/// Creates a single-producer, single-consumer channel.
+///
+/// The [`UniqueSender`] and [`UniqueReceiver`] returned by this function
+/// can't be cloned, and their operations take `&mut self`, so there is only
+/// ever one of each using the channel. This lets them skip the atomic
+/// compare-and-swap loops which sort out racing producers and consumers.
+///
+/// # Examples
+/// ```
+/// use atomic_mpmc::channel_single;
+///
+/// let (mut sender, mut receiver) = channel_single::<i32>(1);
+///
+/// sender.send(1).unwrap();
+/// assert_eq!(receiver.recv().unwrap(), 1);
+/// ```
+pub fn channel_single<T>(capacity: usize) -> (UniqueSender<T>, UniqueReceiver<T>) {
+    let builder = ChannelBuilder {
+        single: true,
+        ..ChannelBuilder::new(capacity)
+    };
+    let (sender, receiver) = builder.build();
+    (UniqueSender::new(sender), UniqueReceiver::new(receiver))
+}
+
+/// Creates a multi-producer, multi-consumer channel whose handles borrow
+/// `channel` instead of sharing it through an [`Arc`].
+///
+/// The buffer can't live in the handles themselves, so it is created up
+/// front with [`ScopedChannel::new`] and may sit on the stack. The handles
+/// work like [`Sender`] and [`Receiver`], and can be shared with threads
+/// spawned by [`std::thread::scope`], as long as they don't outlive the
+/// borrow.
+///
+/// # Examples
+/// ```
+/// use atomic_mpmc::{scoped_channel, ScopedChannel};
+/// use std::thread;
+///
+/// let channel = ScopedChannel::<i32>::new(4);
+/// let (sender, receiver) = scoped_channel(&channel);
+///
+/// thread::scope(|scope| {
+///     for i in 0..4 {
+///         let sender = sender.clone();
+///         scope.spawn(move || sender.send(i).unwrap());
+///     }
+///     drop(sender);
+///
+///     assert_eq!(receiver.iter().sum::<i32>(), 6);
+/// });
+/// ```
+pub fn scoped_channel<T>(
+    channel: &ScopedChannel<T>,
+) -> (ScopedSender<'_, T>, ScopedReceiver<'_, T>) {
+    channel.split()
+}
+
+/// Creates a channel shared by handles which can both send and receive.
+///
+/// This is handy when every thread both produces and consumes, like in a
+/// worker pool feeding itself. The returned [`Endpoint`] can be cloned for
+/// each thread, and the channel hangs up once all of them are dropped.
+///
+/// # Examples
+/// ```
+/// use atomic_mpmc::duplex;
+///
+/// let endpoint = duplex::<i32>(2);
+/// let other = endpoint.clone();
+///
+/// endpoint.send(1).unwrap();
+/// other.send(2).unwrap();
+///
+/// assert_eq!(other.recv().unwrap(), 1);
+/// assert_eq!(endpoint.recv().unwrap(), 2);
+/// ```
+pub fn duplex<T>(capacity: usize) -> Endpoint<T> {
+    let (sender, receiver) = channel(capacity);
+    Endpoint::new(sender, receiver)
+}
@@ -0,0 +1,304 @@
+//! A single-threaded channel, for when all producers and consumers live on
+//! the same thread, like in a single-threaded async executor.
+//!
+//! It works like the main channel, but without any atomics or locks: the
+//! handles share the channel through an [`Rc`], and can't be sent to other
+//! threads. Since nothing else could make room or send a value while the
+//! only thread is blocked, there are no blocking operations. Instead,
+//! [`Sender::send`] and [`Receiver::recv`] are `async`, and wait for the
+//! other side to get around to it.
+//!
+//! As the values never leave the thread either, they don't need to be
+//! [`Send`], so this is also the channel for types like [`Rc`].
+//!
+//! # Examples
+//! ```
+//! use atomic_mpmc::local;
+//!
+//! let (sender, receiver) = local::channel::<i32>(1);
+//!
+//! sender.try_send(1).unwrap();
+//! assert_eq!(receiver.try_recv().unwrap(), 1);
+//! ```
+//!
+//! The handles are not [`Send`]:
+//! ```compile_fail
+//! use atomic_mpmc::local;
+//!
+//! fn assert_send<T: Send>(_: T) {}
+//!
+//! let (sender, receiver) = local::channel::<i32>(1);
+//! assert_send(sender);
+//! ```
+
+use alloc::{collections::VecDeque, rc::Rc, vec::Vec};
+use core::{
+    cell::{Cell, RefCell},
+    fmt,
+    future::poll_fn,
+    task::{Poll, Waker},
+};
+
+use crate::{ErrorCause, RecvError, SendError};
+
+/// Tasks waiting for the channel to change.
+#[derive(Debug, Default)]
+struct Wakers(RefCell<Vec<Waker>>);
+
+impl Wakers {
+    fn register(&self, waker: &Waker) {
+        let mut wakers = self.0.borrow_mut();
+        if !wakers.iter().any(|w| w.will_wake(waker)) {
+            wakers.push(waker.clone());
+        }
+    }
+
+    fn wake(&self) {
+        let wakers = core::mem::take(&mut *self.0.borrow_mut());
+        for waker in wakers {
+            waker.wake();
+        }
+    }
+}
+
+struct Channel<T> {
+    buffer: RefCell<VecDeque<T>>,
+    capacity: usize,
+
+    receivers: Cell<usize>,
+    senders: Cell<usize>,
+
+    writable: Wakers,
+    readable: Wakers,
+}
+
+impl<T> Channel<T> {
+    fn try_write(&self, value: T) -> Result<(), SendError<T>> {
+        if self.receivers.get() == 0 {
+            return Err(SendError(value, ErrorCause::HungUp));
+        }
+
+        let mut buffer = self.buffer.borrow_mut();
+        if buffer.len() >= self.capacity {
+            return Err(SendError(value, ErrorCause::WouldBlock));
+        }
+
+        buffer.push_back(value);
+        drop(buffer);
+
+        self.readable.wake();
+        Ok(())
+    }
+
+    fn try_read(&self) -> Result<T, RecvError> {
+        let value = self.buffer.borrow_mut().pop_front();
+        match value {
+            Some(value) => {
+                self.writable.wake();
+                Ok(value)
+            }
+            None if self.senders.get() == 0 => Err(RecvError(ErrorCause::HungUp)),
+            None => Err(RecvError(ErrorCause::WouldBlock)),
+        }
+    }
+}
+
+impl<T> fmt::Debug for Channel<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let senders = self.senders.get();
+        let receivers = self.receivers.get();
+
+        f.debug_struct("Channel")
+            .field("capacity", &self.capacity)
+            .field("len", &self.buffer.borrow().len())
+            .field("sender_count", &senders)
+            .field("receiver_count", &receivers)
+            .field("closed", &(senders == 0 || receivers == 0))
+            .finish()
+    }
+}
+
+/// The sending half of a single-threaded channel, created by [`channel`].
+pub struct Sender<T>(Rc<Channel<T>>);
+
+impl<T> Sender<T> {
+    fn new(channel: Rc<Channel<T>>) -> Self {
+        channel.senders.set(channel.senders.get() + 1);
+        Self(channel)
+    }
+
+    /// Send a value to the channel, waiting until there is room for it.
+    ///
+    /// # Examples
+    /// ```
+    /// use atomic_mpmc::local;
+    /// use futures::executor::block_on;
+    ///
+    /// let (sender, receiver) = local::channel::<i32>(1);
+    ///
+    /// block_on(sender.send(1)).unwrap();
+    /// assert_eq!(receiver.try_recv().unwrap(), 1);
+    /// ```
+    pub async fn send(&self, value: T) -> Result<(), SendError<T>> {
+        let mut value = Some(value);
+        poll_fn(|cx| {
+            let pending = value.take().expect("polled after completion");
+            match self.0.try_write(pending) {
+                Err(SendError(pending, ErrorCause::WouldBlock)) => {
+                    value = Some(pending);
+                    self.0.writable.register(cx.waker());
+                    Poll::Pending
+                }
+                result => Poll::Ready(result),
+            }
+        })
+        .await
+    }
+
+    /// Attempt to send a value to the channel. This function will return
+    /// `Err(SendError(value, ErrorCause::WouldBlock))` if the channel is full.
+    ///
+    /// # Examples
+    /// ```
+    /// use atomic_mpmc::local;
+    ///
+    /// let (sender, receiver) = local::channel::<i32>(1);
+    ///
+    /// sender.try_send(1).unwrap();
+    /// sender.try_send(2).unwrap_err();
+    /// ```
+    pub fn try_send(&self, value: T) -> Result<(), SendError<T>> {
+        self.0.try_write(value)
+    }
+}
+
+impl<T> Drop for Sender<T> {
+    fn drop(&mut self) {
+        let senders = self.0.senders.get() - 1;
+        self.0.senders.set(senders);
+
+        // Wake up receivers waiting for values which will never come.
+        if senders == 0 {
+            self.0.readable.wake();
+        }
+    }
+}
+
+impl<T> Clone for Sender<T> {
+    fn clone(&self) -> Self {
+        Self::new(self.0.clone())
+    }
+}
+
+impl<T> fmt::Debug for Sender<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("Sender").field(&self.0).finish()
+    }
+}
+
+/// The receiving half of a single-threaded channel, created by [`channel`].
+pub struct Receiver<T>(Rc<Channel<T>>);
+
+impl<T> Receiver<T> {
+    fn new(channel: Rc<Channel<T>>) -> Self {
+        channel.receivers.set(channel.receivers.get() + 1);
+        Self(channel)
+    }
+
+    /// Receive a value from the channel, waiting until there is one.
+    ///
+    /// # Cancel safety
+    /// A value is only taken out of the channel by the poll which returns
+    /// it. Dropping the future before then, even after it was woken up for
+    /// a value, leaves the value in the channel for the next receiver. This
+    /// makes it fine to use in `select!` loops or a `FuturesUnordered`.
+    ///
+    /// # Examples
+    /// ```
+    /// use atomic_mpmc::local;
+    /// use futures::executor::block_on;
+    ///
+    /// let (sender, receiver) = local::channel::<i32>(1);
+    ///
+    /// sender.try_send(1).unwrap();
+    /// assert_eq!(block_on(receiver.recv()).unwrap(), 1);
+    /// ```
+    pub async fn recv(&self) -> Result<T, RecvError> {
+        poll_fn(|cx| match self.0.try_read() {
+            Err(RecvError(ErrorCause::WouldBlock)) => {
+                self.0.readable.register(cx.waker());
+                Poll::Pending
+            }
+            result => Poll::Ready(result),
+        })
+        .await
+    }
+
+    /// Attempt to receive a value from the channel. This function will return
+    /// `Err(RecvError(ErrorCause::WouldBlock))` if the channel is empty.
+    ///
+    /// # Examples
+    /// ```
+    /// use atomic_mpmc::local;
+    ///
+    /// let (sender, receiver) = local::channel::<i32>(1);
+    ///
+    /// receiver.try_recv().unwrap_err();
+    /// ```
+    pub fn try_recv(&self) -> Result<T, RecvError> {
+        self.0.try_read()
+    }
+}
+
+impl<T> Drop for Receiver<T> {
+    fn drop(&mut self) {
+        let receivers = self.0.receivers.get() - 1;
+        self.0.receivers.set(receivers);
+
+        // Wake up senders waiting for room which will never be used.
+        if receivers == 0 {
+            self.0.writable.wake();
+        }
+    }
+}
+
+impl<T> Clone for Receiver<T> {
+    fn clone(&self) -> Self {
+        Self::new(self.0.clone())
+    }
+}
+
+impl<T> fmt::Debug for Receiver<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("Receiver").field(&self.0).finish()
+    }
+}
+
+/// Creates a single-threaded multi-producer, multi-consumer channel.
+///
+/// The channel will have a buffer of size `capacity`. For more information,
+/// see the [module documentation](self).
+///
+/// # Examples
+/// ```
+/// use atomic_mpmc::local;
+///
+/// let (sender, receiver) = local::channel::<i32>(10);
+///
+/// sender.try_send(1).unwrap();
+/// assert_eq!(receiver.try_recv().unwrap(), 1);
+/// ```
+pub fn channel<T>(capacity: usize) -> (Sender<T>, Receiver<T>) {
+    let channel = Rc::new(Channel {
+        buffer: RefCell::new(VecDeque::with_capacity(capacity)),
+        capacity,
+
+        receivers: Cell::new(0),
+        senders: Cell::new(0),
+
+        writable: Wakers::default(),
+        readable: Wakers::default(),
+    });
+
+    (Sender::new(channel.clone()), Receiver::new(channel))
+}
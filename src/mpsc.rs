@@ -0,0 +1,175 @@
+//! A drop-in replacement for [`std::sync::mpsc`]'s bounded channel.
+//!
+//! The handles here wrap the crate's [`Sender`](crate::Sender) and
+//! [`Receiver`](crate::Receiver), but their methods have the same names and
+//! signatures as in `std`, and return `std`'s error types. Code written
+//! against [`std::sync::mpsc::sync_channel`] can switch over by changing its
+//! imports.
+//!
+//! `std`'s unbounded `channel` has no counterpart, as this crate's channels
+//! always have a fixed capacity. The errors also lose some detail, like the
+//! reason the channel was closed with. Use the crate's own types for that.
+//!
+//! Producers which have to stay on `std` for now can be bridged over with
+//! [`bridge_from_std`].
+//!
+//! # Examples
+//! ```
+//! use atomic_mpmc::mpsc::{sync_channel, TryRecvError};
+//!
+//! let (sender, receiver) = sync_channel::<i32>(1);
+//!
+//! sender.send(1).unwrap();
+//! assert_eq!(receiver.recv().unwrap(), 1);
+//! assert_eq!(receiver.try_recv().unwrap_err(), TryRecvError::Empty);
+//! ```
+
+use std::time::Duration;
+
+pub use std::sync::mpsc::{RecvError, RecvTimeoutError, SendError, TryRecvError, TrySendError};
+
+use crate::{Iter, TryIter};
+
+/// The sending half of a channel, created by [`sync_channel`]. Works like
+/// [`std::sync::mpsc::SyncSender`].
+#[derive(Debug, Clone)]
+pub struct SyncSender<T>(crate::Sender<T>);
+
+impl<T> SyncSender<T> {
+    /// Send a value to the channel, blocking while it is full.
+    /// For more information, see [`Sender::send`](crate::Sender::send).
+    pub fn send(&self, value: T) -> Result<(), SendError<T>> {
+        Ok(self.0.send(value)?)
+    }
+
+    /// Attempt to send a value to the channel, without blocking.
+    /// For more information, see [`Sender::try_send`](crate::Sender::try_send).
+    pub fn try_send(&self, value: T) -> Result<(), TrySendError<T>> {
+        Ok(self.0.try_send(value)?)
+    }
+}
+
+impl<T> From<SyncSender<T>> for crate::Sender<T> {
+    fn from(sender: SyncSender<T>) -> Self {
+        sender.0
+    }
+}
+
+/// The receiving half of a channel, created by [`sync_channel`]. Works like
+/// [`std::sync::mpsc::Receiver`], except that it can be cloned.
+#[derive(Debug, Clone)]
+pub struct Receiver<T>(crate::Receiver<T>);
+
+impl<T> Receiver<T> {
+    /// Receive a value from the channel, blocking while it is empty.
+    /// For more information, see [`Receiver::recv`](crate::Receiver::recv).
+    pub fn recv(&self) -> Result<T, RecvError> {
+        Ok(self.0.recv()?)
+    }
+
+    /// Attempt to receive a value from the channel, without blocking.
+    /// For more information, see [`Receiver::try_recv`](crate::Receiver::try_recv).
+    pub fn try_recv(&self) -> Result<T, TryRecvError> {
+        Ok(self.0.try_recv()?)
+    }
+
+    /// Receive a value from the channel, blocking for at most `timeout`.
+    /// For more information, see
+    /// [`Receiver::recv_timeout`](crate::Receiver::recv_timeout).
+    pub fn recv_timeout(&self, timeout: Duration) -> Result<T, RecvTimeoutError> {
+        Ok(self.0.recv_timeout(timeout)?)
+    }
+
+    /// Returns an iterator over the values of the channel, blocking between
+    /// them. For more information, see [`Receiver::iter`](crate::Receiver::iter).
+    pub fn iter(&self) -> Iter<&crate::Receiver<T>> {
+        self.0.iter()
+    }
+
+    /// Returns an iterator over the pending values of the channel.
+    /// For more information, see [`Receiver::try_iter`](crate::Receiver::try_iter).
+    pub fn try_iter(&self) -> TryIter<&crate::Receiver<T>> {
+        self.0.try_iter()
+    }
+}
+
+impl<T> From<Receiver<T>> for crate::Receiver<T> {
+    fn from(receiver: Receiver<T>) -> Self {
+        receiver.0
+    }
+}
+
+impl<T> IntoIterator for Receiver<T> {
+    type Item = T;
+    type IntoIter = Iter<crate::Receiver<T>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.into_iter()
+    }
+}
+
+impl<'a, T> IntoIterator for &'a Receiver<T> {
+    type Item = T;
+    type IntoIter = Iter<&'a crate::Receiver<T>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+/// Creates a channel with a buffer of size `bound`, like
+/// [`std::sync::mpsc::sync_channel`]. Unlike in `std`, a `bound` of 0 gives
+/// a channel that is never writable, see [`channel`](crate::channel).
+///
+/// # Examples
+/// ```
+/// use atomic_mpmc::mpsc::sync_channel;
+///
+/// let (sender, receiver) = sync_channel::<i32>(10);
+///
+/// sender.send(1).unwrap();
+/// assert_eq!(receiver.recv().unwrap(), 1);
+/// ```
+pub fn sync_channel<T>(bound: usize) -> (SyncSender<T>, Receiver<T>) {
+    let (sender, receiver) = crate::channel(bound);
+    (SyncSender(sender), Receiver(receiver))
+}
+
+/// Forwards the values of a [`std::sync::mpsc::Receiver`] into a new channel
+/// with a buffer of size `capacity`, and returns its receiver. This helps
+/// migrating code a piece at a time, as the producers can stay on `std`.
+///
+/// The values are pumped across by a thread of their own, which costs a
+/// thread for as long as the bridge is up, and a handoff per value. The
+/// thread ends, and the channel hangs up, once the `std` side hangs up. If
+/// all receivers of the channel hang up first, the thread ends with the
+/// next value it tries to forward, dropping it.
+///
+/// # Examples
+/// ```
+/// use atomic_mpmc::mpsc::bridge_from_std;
+///
+/// let (sender, receiver) = std::sync::mpsc::channel::<i32>();
+/// let receiver = bridge_from_std(receiver, 4);
+///
+/// sender.send(1).unwrap();
+/// sender.send(2).unwrap();
+/// drop(sender);
+///
+/// assert_eq!(receiver.iter().collect::<Vec<_>>(), [1, 2]);
+/// ```
+pub fn bridge_from_std<T: Send + 'static>(
+    receiver: std::sync::mpsc::Receiver<T>,
+    capacity: usize,
+) -> crate::Receiver<T> {
+    let (sender, bridged) = crate::channel(capacity);
+    std::thread::spawn(move || {
+        for value in receiver {
+            if sender.send(value).is_err() {
+                break;
+            }
+        }
+    });
+
+    bridged
+}
@@ -0,0 +1,420 @@
+use core::{
+    fmt,
+    ops::{Deref, DerefMut},
+};
+
+use crate::{sync::RwLockReadGuard, Channel, ErrorCause, Node, SendError};
+
+/// A slot reserved in a channel, created by [`Sender::reserve`](crate::Sender::reserve).
+///
+/// The slot counts towards the channel's capacity until the permit is either
+/// used by [`Permit::send`], or dropped. Dropping it gives the slot back
+/// without sending anything.
+///
+/// # Examples
+/// ```
+/// use atomic_mpmc::channel;
+///
+/// let (sender, receiver) = channel(1);
+///
+/// let permit = sender.reserve().unwrap();
+/// assert!(sender.try_send(1).is_err());
+///
+/// permit.send(2);
+/// assert_eq!(receiver.recv().unwrap(), 2);
+/// ```
+pub struct Permit<'a, T> {
+    channel: &'a Channel<T>,
+    node: Option<&'a Node<T>>,
+    index: usize,
+    _gate: Option<RwLockReadGuard<'a, ()>>,
+}
+
+impl<'a, T> Permit<'a, T> {
+    pub(crate) fn new(
+        channel: &'a Channel<T>,
+        node: &'a Node<T>,
+        index: usize,
+        gate: Option<RwLockReadGuard<'a, ()>>,
+    ) -> Self {
+        Self {
+            channel,
+            node: Some(node),
+            index,
+            _gate: gate,
+        }
+    }
+
+    /// Returns the ring slot the permit is for.
+    pub(crate) fn slot(&self) -> usize {
+        self.index % self.channel.capacity()
+    }
+
+    /// Sends a value like [`Permit::send`], returning the position and the
+    /// ring slot it was written to.
+    pub(crate) fn send_positioned(self, value: T) -> (usize, usize) {
+        let sent = (self.index, self.slot());
+        self.send(value);
+        sent
+    }
+
+    /// Sends a value into the reserved slot. This never blocks or fails.
+    ///
+    /// The value is delivered even if every receiver hung up after the slot
+    /// was reserved, in which case it is dropped along with the channel.
+    #[inline(always)]
+    pub fn send(mut self, value: T) {
+        if let Some(node) = self.node.take() {
+            unsafe {
+                // SAFETY: The node was claimed for this permit, which has not
+                // used or given it up yet.
+                self.channel.fill(node, value);
+            }
+        }
+    }
+}
+
+impl<T> Drop for Permit<'_, T> {
+    fn drop(&mut self) {
+        if self.node.take().is_some() {
+            self.channel.release(self.index);
+        }
+    }
+}
+
+impl<T> fmt::Debug for Permit<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Permit")
+            .field("index", &self.index)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Consecutive slots reserved in a channel, created by
+/// [`Sender::try_reserve_many`](crate::Sender::try_reserve_many).
+///
+/// Values are sent into the slots in order. The slots count towards the
+/// channel's capacity until they are used, or the batch is dropped. Dropping
+/// it gives the unused slots back without sending anything.
+///
+/// # Examples
+/// ```
+/// use atomic_mpmc::channel;
+///
+/// let (sender, receiver) = channel(3);
+///
+/// let mut permits = sender.try_reserve_many(3).unwrap();
+/// permits.send(1).unwrap();
+/// drop(permits);
+///
+/// sender.send(2).unwrap();
+/// assert_eq!(receiver.try_iter().collect::<Vec<_>>(), [1, 2]);
+/// ```
+pub struct PermitBatch<'a, T> {
+    channel: &'a Channel<T>,
+    next: usize,
+    end: usize,
+    _gate: Option<RwLockReadGuard<'a, ()>>,
+}
+
+impl<'a, T> PermitBatch<'a, T> {
+    pub(crate) fn new(
+        channel: &'a Channel<T>,
+        next: usize,
+        end: usize,
+        gate: Option<RwLockReadGuard<'a, ()>>,
+    ) -> Self {
+        Self {
+            channel,
+            next,
+            end,
+            _gate: gate,
+        }
+    }
+
+    /// Returns the number of slots left.
+    pub fn len(&self) -> usize {
+        self.end.wrapping_sub(self.next)
+    }
+
+    /// Returns whether all slots have been used.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Sends a value into the next reserved slot. This never blocks, and
+    /// only fails with [`ErrorCause::WouldBlock`](crate::ErrorCause::WouldBlock)
+    /// if all slots have been used.
+    ///
+    /// Like [`Permit::send`], the value is delivered even if every receiver
+    /// hung up after the slots were reserved.
+    #[inline(always)]
+    pub fn send(&mut self, value: T) -> Result<(), SendError<T>> {
+        if self.is_empty() {
+            return Err(SendError(value, ErrorCause::WouldBlock));
+        }
+
+        let index = self.next;
+        self.next = index.wrapping_add(1);
+        unsafe {
+            // SAFETY: The node was claimed for this batch, and not used yet,
+            // since the slots are used in order.
+            self.channel.fill(self.channel.node(index), value);
+        }
+        Ok(())
+    }
+}
+
+impl<T> Drop for PermitBatch<'_, T> {
+    fn drop(&mut self) {
+        // Give back the last slot first, so the write position can move back
+        // over all of them if nobody claimed a slot after the batch.
+        while !self.is_empty() {
+            self.end = self.end.wrapping_sub(1);
+            self.channel.release(self.end);
+        }
+    }
+}
+
+impl<T> fmt::Debug for PermitBatch<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PermitBatch")
+            .field("next", &self.next)
+            .field("end", &self.end)
+            .finish_non_exhaustive()
+    }
+}
+
+/// A value claimed in a channel, created by [`Receiver::reserve`](crate::Receiver::reserve).
+///
+/// The value stays in the channel until the permit is either used by
+/// [`RecvPermit::recv`], or dropped. Dropping it leaves the value for the
+/// next receive, on this or any other receiver.
+///
+/// # Examples
+/// ```
+/// use atomic_mpmc::channel;
+///
+/// let (sender, receiver) = channel(2);
+/// sender.send(1).unwrap();
+///
+/// let permit = receiver.reserve().unwrap();
+/// drop(permit);
+///
+/// let permit = receiver.reserve().unwrap();
+/// assert_eq!(permit.recv(), 1);
+/// ```
+pub struct RecvPermit<'a, T> {
+    channel: &'a Channel<T>,
+    node: Option<&'a Node<T>>,
+    index: usize,
+    _gate: Option<RwLockReadGuard<'a, ()>>,
+}
+
+impl<'a, T> RecvPermit<'a, T> {
+    pub(crate) fn new(
+        channel: &'a Channel<T>,
+        node: &'a Node<T>,
+        index: usize,
+        gate: Option<RwLockReadGuard<'a, ()>>,
+    ) -> Self {
+        Self {
+            channel,
+            node: Some(node),
+            index,
+            _gate: gate,
+        }
+    }
+
+    /// Returns the ring slot the permit was claimed from.
+    pub(crate) fn slot(&self) -> usize {
+        self.index % self.channel.capacity()
+    }
+
+    /// Returns the position the claimed value was written at.
+    pub(crate) fn position(&self) -> usize {
+        self.index
+    }
+
+    /// Returns a reference to the claimed value, without receiving it.
+    ///
+    /// # Examples
+    /// ```
+    /// use atomic_mpmc::channel;
+    ///
+    /// let (sender, receiver) = channel(1);
+    /// sender.send(1).unwrap();
+    ///
+    /// let permit = receiver.reserve().unwrap();
+    /// assert_eq!(*permit.get(), 1);
+    /// ```
+    #[inline(always)]
+    pub fn get(&self) -> &T {
+        let node = self.node.expect("permit is only used once");
+        unsafe {
+            // SAFETY: The node was claimed for this permit, so it is hot and
+            // nobody else is reading from it.
+            node.data.with(|data| (*data).assume_init_ref())
+        }
+    }
+
+    /// Returns a mutable reference to the claimed value, without receiving it.
+    #[inline(always)]
+    pub(crate) fn get_mut(&mut self) -> &mut T {
+        let node = self.node.expect("permit is only used once");
+        unsafe {
+            // SAFETY: The node was claimed for this permit, so it is hot and
+            // nobody else is reading from it.
+            node.data.with_mut(|data| (*data).assume_init_mut())
+        }
+    }
+
+    /// Returns when the claimed value was sent.
+    #[cfg(feature = "timing")]
+    pub(crate) fn sent(&self) -> Option<std::time::Instant> {
+        let node = self.node.expect("permit is only used once");
+        unsafe {
+            // SAFETY: The node was claimed for this permit, so its send time
+            // is not being written.
+            node.sent.with(|sent| *sent)
+        }
+    }
+
+    /// Receives the claimed value. This never blocks or fails.
+    #[inline(always)]
+    pub fn recv(mut self) -> T {
+        self.node.take().expect("permit is only used once");
+        unsafe {
+            // SAFETY: The node was claimed for this permit, which has not
+            // used or given it up yet.
+            self.channel.take(self.index)
+        }
+    }
+
+    /// Drops the claimed value in place.
+    pub(crate) fn discard(mut self) {
+        self.node.take().expect("permit is only used once");
+        unsafe {
+            // SAFETY: The node was claimed for this permit, which has not
+            // used or given it up yet.
+            self.channel.discard(self.index)
+        }
+    }
+}
+
+impl<T> Drop for RecvPermit<'_, T> {
+    fn drop(&mut self) {
+        if self.node.take().is_some() {
+            self.channel.unclaim_read(self.index);
+        }
+    }
+}
+
+impl<T> fmt::Debug for RecvPermit<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RecvPermit")
+            .field("index", &self.index)
+            .finish_non_exhaustive()
+    }
+}
+
+/// A look at the next value in a channel, created by
+/// [`Receiver::try_peek`](crate::Receiver::try_peek).
+///
+/// While the guard is held, the value is set aside: other receivers go on
+/// with the values after it. Once the guard is dropped, the value is the next
+/// one received. With a single consumer, the peeked value is therefore always
+/// the one its next receive returns.
+///
+/// # Examples
+/// ```
+/// use atomic_mpmc::channel;
+///
+/// let (sender, receiver) = channel(1);
+/// sender.send(1).unwrap();
+///
+/// let peeked = receiver.try_peek().unwrap();
+/// assert_eq!(*peeked, 1);
+/// drop(peeked);
+///
+/// assert_eq!(receiver.recv().unwrap(), 1);
+/// ```
+#[derive(Debug)]
+pub struct PeekGuard<'a, T>(RecvPermit<'a, T>);
+
+impl<'a, T> PeekGuard<'a, T> {
+    pub(crate) fn new(permit: RecvPermit<'a, T>) -> Self {
+        Self(permit)
+    }
+
+    /// Receives the peeked value after all. This never blocks or fails.
+    pub fn take(self) -> T {
+        self.0.recv()
+    }
+}
+
+impl<T> Deref for PeekGuard<'_, T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        self.0.get()
+    }
+}
+
+/// A claimed value which may be changed in place, created by
+/// [`Receiver::recv_ref_mut`](crate::Receiver::recv_ref_mut).
+///
+/// The value is either received with [`RequeueGuard::take`], or sent again
+/// with [`RequeueGuard::requeue`], to be received after the values already
+/// buffered. Dropping the guard puts the value back at the front, changes
+/// included, like dropping a [`RecvPermit`].
+///
+/// # Examples
+/// ```
+/// use atomic_mpmc::channel;
+///
+/// let (sender, receiver) = channel(2);
+/// sender.send_all([1, 2]).unwrap();
+///
+/// let mut guard = receiver.recv_ref_mut().unwrap();
+/// *guard += 10;
+/// guard.requeue().unwrap();
+///
+/// assert_eq!(receiver.recv().unwrap(), 2);
+/// assert_eq!(receiver.recv().unwrap(), 11);
+/// ```
+#[derive(Debug)]
+pub struct RequeueGuard<'a, T>(RecvPermit<'a, T>);
+
+impl<'a, T> RequeueGuard<'a, T> {
+    pub(crate) fn new(permit: RecvPermit<'a, T>) -> Self {
+        Self(permit)
+    }
+
+    /// Receives the value. This never blocks or fails.
+    pub fn take(self) -> T {
+        self.0.recv()
+    }
+
+    /// Sends the value to the back of the channel, like
+    /// [`Sender::send`](crate::Sender::send). Its slot is freed first, but
+    /// other senders may take it, so this can block if the channel is full.
+    pub fn requeue(self) -> Result<(), SendError<T>> {
+        let channel = self.0.channel;
+        channel.write(self.0.recv())
+    }
+}
+
+impl<T> Deref for RequeueGuard<'_, T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        self.0.get()
+    }
+}
+
+impl<T> DerefMut for RequeueGuard<'_, T> {
+    fn deref_mut(&mut self) -> &mut T {
+        self.0.get_mut()
+    }
+}
@@ -0,0 +1,221 @@
+//! A channel with priority levels, for when some values should jump the
+//! queue, like urgent tasks in a scheduler.
+//!
+//! Every level has a ring buffer of its own, and receiving always takes from
+//! the highest level with values in it. Values of the same level are still
+//! received in the order they were sent.
+//!
+//! This is strict priority: as long as higher levels keep getting values,
+//! lower ones are never received from. If that is a problem, the receiver
+//! may have to keep up with the high levels, or some of their values may have
+//! to be sent at a lower level from time to time.
+//!
+//! # Examples
+//! ```
+//! use atomic_mpmc::priority;
+//!
+//! let (sender, receiver) = priority::channel::<&str>(4, 2);
+//!
+//! sender.send_with_priority("later", 0).unwrap();
+//! sender.send_with_priority("first", 1).unwrap();
+//!
+//! assert_eq!(receiver.recv().unwrap(), "first");
+//! assert_eq!(receiver.recv().unwrap(), "later");
+//! ```
+
+use alloc::{sync::Arc, vec::Vec};
+use core::{fmt, mem};
+
+use crate::{
+    waiter::{self, Waiter},
+    ErrorCause, RecvError, SendError,
+};
+
+/// The sending half of a priority channel, created by [`channel`].
+pub struct Sender<T> {
+    levels: Vec<crate::Sender<T>>,
+    readable: Arc<Waiter>,
+}
+
+impl<T> Sender<T> {
+    /// Send a value to the channel at a priority level, in
+    /// `0..levels`. Higher levels are received first. This function will
+    /// block the current thread if the level is full.
+    ///
+    /// # Panics
+    /// Panics if the level is out of range.
+    ///
+    /// # Examples
+    /// ```
+    /// use atomic_mpmc::priority;
+    ///
+    /// let (sender, receiver) = priority::channel::<i32>(1, 3);
+    ///
+    /// sender.send_with_priority(1, 2).unwrap();
+    /// ```
+    pub fn send_with_priority(&self, value: T, level: usize) -> Result<(), SendError<T>> {
+        self.levels[level].send(value)?;
+        self.readable.set();
+        Ok(())
+    }
+
+    /// Attempt to send a value to the channel at a priority level. This
+    /// function will return `Err(SendError(value, ErrorCause::WouldBlock))`
+    /// if the level is full.
+    ///
+    /// # Panics
+    /// Panics if the level is out of range.
+    ///
+    /// # Examples
+    /// ```
+    /// use atomic_mpmc::priority;
+    ///
+    /// let (sender, receiver) = priority::channel::<i32>(1, 2);
+    ///
+    /// sender.try_send_with_priority(1, 0).unwrap();
+    /// sender.try_send_with_priority(2, 0).unwrap_err();
+    /// sender.try_send_with_priority(3, 1).unwrap();
+    /// ```
+    pub fn try_send_with_priority(&self, value: T, level: usize) -> Result<(), SendError<T>> {
+        self.levels[level].try_send(value)?;
+        self.readable.set();
+        Ok(())
+    }
+}
+
+impl<T> Drop for Sender<T> {
+    fn drop(&mut self) {
+        // Hang up on every level first, then wake up all blocked receivers,
+        // in case this was the last sender.
+        drop(mem::take(&mut self.levels));
+        self.readable.set();
+    }
+}
+
+impl<T> Clone for Sender<T> {
+    fn clone(&self) -> Self {
+        Self {
+            levels: self.levels.clone(),
+            readable: self.readable.clone(),
+        }
+    }
+}
+
+impl<T> fmt::Debug for Sender<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("Sender").field(&self.levels).finish()
+    }
+}
+
+/// The receiving half of a priority channel, created by [`channel`].
+pub struct Receiver<T> {
+    levels: Vec<crate::Receiver<T>>,
+    readable: Arc<Waiter>,
+}
+
+impl<T> Receiver<T> {
+    /// Receive the next value of the highest level with any. This function
+    /// will block the current thread if the channel is empty.
+    ///
+    /// # Examples
+    /// ```
+    /// use atomic_mpmc::priority;
+    ///
+    /// let (sender, receiver) = priority::channel::<i32>(1, 2);
+    ///
+    /// sender.send_with_priority(1, 0).unwrap();
+    /// assert_eq!(receiver.recv().unwrap(), 1);
+    /// ```
+    pub fn recv(&self) -> Result<T, RecvError> {
+        loop {
+            match self.try_recv() {
+                Err(RecvError(ErrorCause::WouldBlock)) => {}
+                result => return result,
+            }
+
+            // Prepare before checking again, so a value sent in between
+            // is either seen by the check or sets the waiter.
+            let sleep = self.readable.prepare();
+            match self.try_recv() {
+                Err(RecvError(ErrorCause::WouldBlock)) => {}
+                result => return result,
+            }
+
+            sleep.wait();
+        }
+    }
+
+    /// Attempt to receive the next value of the highest level with any. This
+    /// function will return `Err(RecvError(ErrorCause::WouldBlock))` if the
+    /// channel is empty.
+    ///
+    /// # Examples
+    /// ```
+    /// use atomic_mpmc::priority;
+    ///
+    /// let (sender, receiver) = priority::channel::<i32>(1, 2);
+    ///
+    /// receiver.try_recv().unwrap_err();
+    /// ```
+    pub fn try_recv(&self) -> Result<T, RecvError> {
+        let mut hung_up = true;
+        for level in self.levels.iter().rev() {
+            match level.try_recv() {
+                Ok(value) => return Ok(value),
+                Err(RecvError(ErrorCause::WouldBlock)) => hung_up = false,
+                // Hung up levels stay empty, so move on to the next one.
+                Err(_) => {}
+            }
+        }
+
+        if hung_up {
+            Err(RecvError(ErrorCause::HungUp))
+        } else {
+            Err(RecvError(ErrorCause::WouldBlock))
+        }
+    }
+}
+
+impl<T> Clone for Receiver<T> {
+    fn clone(&self) -> Self {
+        Self {
+            levels: self.levels.clone(),
+            readable: self.readable.clone(),
+        }
+    }
+}
+
+impl<T> fmt::Debug for Receiver<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("Receiver").field(&self.levels).finish()
+    }
+}
+
+/// Creates a multi-producer, multi-consumer channel with `levels` priority
+/// levels, each with a buffer of size `capacity`. For more information, see
+/// the [module documentation](self).
+///
+/// # Examples
+/// ```
+/// use atomic_mpmc::priority;
+///
+/// let (sender, receiver) = priority::channel::<i32>(10, 3);
+///
+/// sender.send_with_priority(1, 0).unwrap();
+/// assert_eq!(receiver.recv().unwrap(), 1);
+/// ```
+pub fn channel<T>(capacity: usize, levels: usize) -> (Sender<T>, Receiver<T>) {
+    let (senders, receivers) = (0..levels).map(|_| crate::channel(capacity)).unzip();
+    let readable = Arc::new(Waiter::new(waiter::default_strategy(false)));
+
+    (
+        Sender {
+            levels: senders,
+            readable: readable.clone(),
+        },
+        Receiver {
+            levels: receivers,
+            readable,
+        },
+    )
+}
@@ -0,0 +1,189 @@
+use core::{fmt, time::Duration};
+#[cfg(feature = "async")]
+use core::{
+    future::Future,
+    pin::Pin,
+    task::{Context, Poll},
+};
+use std::time::Instant;
+
+use crate::{
+    sync::{Arc, Ordering},
+    Channel, ErrorCause, RecvError,
+};
+
+/// A handle on a value sent with
+/// [`Sender::send_tracked`](crate::Sender::send_tracked), which tells when a
+/// receiver took it out of the channel.
+///
+/// A value counts as received once it is taken out, including by
+/// [`Receiver::clear`](crate::Receiver::clear) and the like, which drop it.
+/// Values dropped along with the channel never are, so the receipt fails
+/// with [`ErrorCause::HungUp`] once all receivers hung up. It keeps the
+/// channel's buffer alive, but not the channel itself.
+///
+/// With the `async` feature, it is also a future of the same result as
+/// [`Receipt::wait`].
+///
+/// # Examples
+/// ```
+/// use atomic_mpmc::channel;
+///
+/// let (sender, receiver) = channel::<i32>(1);
+///
+/// let receipt = sender.send_tracked(1).unwrap();
+/// assert!(!receipt.is_received());
+///
+/// assert_eq!(receiver.recv().unwrap(), 1);
+/// assert!(receipt.is_received());
+/// ```
+pub struct Receipt<T> {
+    channel: Arc<Channel<T>>,
+    position: usize,
+}
+
+impl<T> Receipt<T> {
+    pub(crate) fn new(channel: Arc<Channel<T>>, position: usize) -> Self {
+        Self { channel, position }
+    }
+
+    /// Returns whether the value was received.
+    ///
+    /// # Examples
+    /// ```
+    /// use atomic_mpmc::channel;
+    ///
+    /// let (sender, receiver) = channel::<i32>(2);
+    ///
+    /// let first = sender.send_tracked(1).unwrap();
+    /// let second = sender.send_tracked(2).unwrap();
+    ///
+    /// receiver.recv().unwrap();
+    /// assert!(first.is_received());
+    /// assert!(!second.is_received());
+    /// ```
+    pub fn is_received(&self) -> bool {
+        let _gate = self.channel.enter();
+
+        // The node can only be written to again once the value was taken
+        // out, so a later position counts too.
+        let done = self
+            .channel
+            .node(self.position)
+            .done
+            .load(Ordering::Acquire);
+        done.wrapping_sub(self.position) as isize >= 0
+    }
+
+    /// Checks whether the value was received, or never will be.
+    fn check(&self) -> Option<Result<(), RecvError>> {
+        if self.is_received() {
+            return Some(Ok(()));
+        }
+        if self.channel.receivers.load(Ordering::Acquire) == 0 {
+            // The last receiver may have taken the value right before it
+            // hung up, so look again.
+            return Some(if self.is_received() {
+                Ok(())
+            } else {
+                Err(RecvError(ErrorCause::HungUp))
+            });
+        }
+        None
+    }
+
+    /// Block the current thread until the value was received. This function
+    /// will return `Err(RecvError(ErrorCause::HungUp))` if all receivers hang
+    /// up before one takes it.
+    ///
+    /// # Examples
+    /// ```
+    /// use atomic_mpmc::channel;
+    /// use std::thread;
+    ///
+    /// let (sender, receiver) = channel::<i32>(1);
+    ///
+    /// let receipt = sender.send_tracked(1).unwrap();
+    /// let handle = thread::spawn(move || receiver.recv().unwrap());
+    ///
+    /// receipt.wait().unwrap();
+    /// assert_eq!(handle.join().unwrap(), 1);
+    /// ```
+    pub fn wait(&self) -> Result<(), RecvError> {
+        self.wait_until(None)
+    }
+
+    /// Block the current thread until the value was received, or the
+    /// timeout passes. This function will return
+    /// `Err(RecvError(ErrorCause::Timeout))` if it passes first.
+    ///
+    /// # Examples
+    /// ```
+    /// use atomic_mpmc::{channel, ErrorCause};
+    /// use std::time::Duration;
+    ///
+    /// let (sender, receiver) = channel::<i32>(1);
+    ///
+    /// let receipt = sender.send_tracked(1).unwrap();
+    /// let err = receipt.wait_timeout(Duration::from_millis(10)).unwrap_err();
+    /// assert_eq!(err.0, ErrorCause::Timeout);
+    /// ```
+    pub fn wait_timeout(&self, timeout: Duration) -> Result<(), RecvError> {
+        self.wait_until(Some(Instant::now() + timeout))
+    }
+
+    fn wait_until(&self, deadline: Option<Instant>) -> Result<(), RecvError> {
+        loop {
+            if let Some(result) = self.check() {
+                return result;
+            }
+
+            // Taking a value out, and the last receiver hanging up, both set
+            // the waiter of the blocked writers, which is all this waits for.
+            let sleep = self.channel.writable.prepare();
+            if let Some(result) = self.check() {
+                return result;
+            }
+
+            match deadline {
+                Some(deadline) => {
+                    if !sleep.wait_until(deadline) {
+                        return self.check().unwrap_or(Err(RecvError(ErrorCause::Timeout)));
+                    }
+                }
+                None => sleep.wait(),
+            }
+        }
+    }
+}
+
+#[cfg(feature = "async")]
+impl<T> Future for Receipt<T> {
+    type Output = Result<(), RecvError>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        if let Some(result) = self.check() {
+            return Poll::Ready(result);
+        }
+
+        // Register before checking again, so a value taken in between is
+        // either seen by the check or wakes the task.
+        self.channel.writable.register(cx.waker());
+        match self.check() {
+            Some(result) => Poll::Ready(result),
+            None => Poll::Pending,
+        }
+    }
+}
+
+// The receipt never pins the channel in place.
+impl<T> Unpin for Receipt<T> {}
+
+impl<T> fmt::Debug for Receipt<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Receipt")
+            .field("position", &self.position)
+            .field("received", &self.is_received())
+            .finish()
+    }
+}
@@ -0,0 +1,205 @@
+use core::fmt;
+use sealed::sealed;
+#[cfg(feature = "std")]
+use std::time::Instant;
+
+use crate::{iterator, Channel, ChannelBuilder, Iter, RecvError, SendError, TryIter};
+
+/// The buffer of a channel whose handles borrow it, rather than sharing it
+/// through an [`Arc`](alloc::sync::Arc). Split it into handles with
+/// [`scoped_channel`](crate::scoped_channel).
+///
+/// This saves allocating the shared state separately, and lets it live on
+/// the stack, as long as all handles are gone by the time it is dropped.
+/// That fits threads spawned with [`std::thread::scope`].
+pub struct ScopedChannel<T>(pub(crate) Channel<T>);
+
+impl<T> ScopedChannel<T> {
+    /// Creates the buffer of a channel of size `capacity`.
+    pub fn new(capacity: usize) -> Self {
+        Self(Channel::new(ChannelBuilder::new(capacity)))
+    }
+
+    pub(crate) fn split(&self) -> (ScopedSender<'_, T>, ScopedReceiver<'_, T>) {
+        (ScopedSender::new(&self.0), ScopedReceiver::new(&self.0))
+    }
+}
+
+impl<T> fmt::Debug for ScopedChannel<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("ScopedChannel").field(&self.0).finish()
+    }
+}
+
+/// The sending half of a scoped channel, created by
+/// [`scoped_channel`](crate::scoped_channel). Works like
+/// [`Sender`](crate::Sender), but borrows the channel for `'a`.
+pub struct ScopedSender<'a, T>(&'a Channel<T>);
+
+impl<'a, T> ScopedSender<'a, T> {
+    fn new(channel: &'a Channel<T>) -> Self {
+        channel.add_sender();
+        Self(channel)
+    }
+
+    /// Send a value to the channel. This function will block the current thread
+    /// if the channel is full.
+    ///
+    /// # Examples
+    /// ```
+    /// use atomic_mpmc::{scoped_channel, ScopedChannel};
+    ///
+    /// let channel = ScopedChannel::<i32>::new(1);
+    /// let (sender, receiver) = scoped_channel(&channel);
+    ///
+    /// sender.send(1).unwrap();
+    /// ```
+    pub fn send(&self, value: T) -> Result<(), SendError<T>> {
+        self.0.write(value)
+    }
+
+    /// Attempt to send a value to the channel. This function will return
+    /// `Err(SendError(value, ErrorCause::WouldBlock))` if the channel is full.
+    ///
+    /// # Examples
+    /// ```
+    /// use atomic_mpmc::{scoped_channel, ScopedChannel};
+    ///
+    /// let channel = ScopedChannel::<i32>::new(1);
+    /// let (sender, receiver) = scoped_channel(&channel);
+    ///
+    /// sender.send(1).unwrap();
+    /// sender.try_send(2).unwrap_err();
+    /// ```
+    pub fn try_send(&self, value: T) -> Result<(), SendError<T>> {
+        self.0.try_write(value)
+    }
+}
+
+impl<T> Drop for ScopedSender<'_, T> {
+    fn drop(&mut self) {
+        self.0.remove_sender();
+    }
+}
+
+impl<T> Clone for ScopedSender<'_, T> {
+    fn clone(&self) -> Self {
+        Self::new(self.0)
+    }
+}
+
+impl<T> fmt::Debug for ScopedSender<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("ScopedSender").field(&self.0).finish()
+    }
+}
+
+/// The receiving half of a scoped channel, created by
+/// [`scoped_channel`](crate::scoped_channel). Works like
+/// [`Receiver`](crate::Receiver), but borrows the channel for `'a`.
+pub struct ScopedReceiver<'a, T>(&'a Channel<T>);
+
+impl<'a, T> ScopedReceiver<'a, T> {
+    fn new(channel: &'a Channel<T>) -> Self {
+        channel.add_receiver();
+        Self(channel)
+    }
+
+    /// Receive a value from the channel. This function will block the current
+    /// thread if the channel is empty.
+    ///
+    /// # Examples
+    /// ```
+    /// use atomic_mpmc::{scoped_channel, ScopedChannel};
+    ///
+    /// let channel = ScopedChannel::<i32>::new(1);
+    /// let (sender, receiver) = scoped_channel(&channel);
+    ///
+    /// sender.send(1).unwrap();
+    /// assert_eq!(receiver.recv().unwrap(), 1);
+    /// ```
+    pub fn recv(&self) -> Result<T, RecvError> {
+        self.0.read()
+    }
+
+    /// Attempt to receive a value from the channel. This function will return
+    /// `Err(RecvError(ErrorCause::WouldBlock))` if the channel is empty.
+    ///
+    /// # Examples
+    /// ```
+    /// use atomic_mpmc::{scoped_channel, ScopedChannel};
+    ///
+    /// let channel = ScopedChannel::<i32>::new(1);
+    /// let (sender, receiver) = scoped_channel(&channel);
+    ///
+    /// receiver.try_recv().unwrap_err();
+    /// ```
+    pub fn try_recv(&self) -> Result<T, RecvError> {
+        self.0.try_read()
+    }
+
+    /// Creates an iterator over the values of this channel.
+    /// For more information, see [`Receiver::iter`](crate::Receiver::iter).
+    pub fn iter(&self) -> Iter<&Self> {
+        Iter::new(self)
+    }
+
+    /// Creates an iterator over the pending values of this channel.
+    /// For more information, see [`Receiver::try_iter`](crate::Receiver::try_iter).
+    pub fn try_iter(&self) -> TryIter<&Self> {
+        TryIter::new(self)
+    }
+}
+
+#[sealed]
+impl<T> iterator::Recv for ScopedReceiver<'_, T> {
+    type Item = T;
+    fn recv(&self) -> Result<Self::Item, RecvError> {
+        self.recv()
+    }
+
+    fn try_recv(&self) -> Result<Self::Item, RecvError> {
+        self.try_recv()
+    }
+
+    #[cfg(feature = "std")]
+    fn recv_deadline(&self, deadline: Instant) -> Result<Self::Item, RecvError> {
+        self.0.read_until(Some(deadline))
+    }
+}
+
+impl<'a, T> IntoIterator for ScopedReceiver<'a, T> {
+    type Item = T;
+    type IntoIter = Iter<Self>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        Iter::new(self)
+    }
+}
+
+impl<'a, 'b, T> IntoIterator for &'b ScopedReceiver<'a, T> {
+    type Item = T;
+    type IntoIter = Iter<Self>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        Iter::new(self)
+    }
+}
+
+impl<T> Drop for ScopedReceiver<'_, T> {
+    fn drop(&mut self) {
+        self.0.remove_receiver();
+    }
+}
+
+impl<T> Clone for ScopedReceiver<'_, T> {
+    fn clone(&self) -> Self {
+        Self::new(self.0)
+    }
+}
+
+impl<T> fmt::Debug for ScopedReceiver<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("ScopedReceiver").field(&self.0).finish()
+    }
+}
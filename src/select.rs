@@ -0,0 +1,227 @@
+use alloc::{sync::Arc, vec::Vec};
+use core::{cell::Cell, fmt, task::Waker, time::Duration};
+use std::{
+    task::Wake,
+    thread::{self, Thread},
+    time::Instant,
+};
+
+use crate::{ErrorCause, Receiver, RecvError};
+
+/// Wakes up a thread selecting on channels, when one of them is set.
+struct Unparker(Thread);
+
+impl Wake for Unparker {
+    fn wake(self: Arc<Self>) {
+        self.0.unpark();
+    }
+
+    fn wake_by_ref(self: &Arc<Self>) {
+        self.0.unpark();
+    }
+}
+
+std::thread_local! {
+    // One per thread, so registering it again on a channel which stayed
+    // empty doesn't pile up wakers.
+    static WAKER: Waker = Waker::from(Arc::new(Unparker(thread::current())));
+}
+
+/// Receives from whichever of several channels is ready first.
+///
+/// A channel is ready once it has a value, or once receiving from it would
+/// fail for good, like when all its senders hung up. Each selection receives
+/// from one channel only, and reports which by the index [`Select::recv`]
+/// returned for it. Channels are checked in turns, starting after the one
+/// selected last, so a busy channel doesn't starve the others.
+///
+/// # Examples
+/// ```
+/// use atomic_mpmc::{channel, Select};
+///
+/// let (first_sender, first_receiver) = channel::<i32>(1);
+/// let (second_sender, second_receiver) = channel::<i32>(1);
+///
+/// let mut select = Select::new();
+/// let first = select.recv(&first_receiver);
+/// let second = select.recv(&second_receiver);
+///
+/// second_sender.send(2).unwrap();
+/// assert_eq!(select.select(), (second, Ok(2)));
+///
+/// drop(first_sender);
+/// assert_eq!(select.select().0, first);
+/// ```
+pub struct Select<'a, T> {
+    receivers: Vec<&'a Receiver<T>>,
+
+    /// Index of the receiver to check first.
+    next: Cell<usize>,
+}
+
+impl<'a, T> Select<'a, T> {
+    /// Creates a selection over no channels yet.
+    ///
+    /// # Examples
+    /// ```
+    /// use atomic_mpmc::Select;
+    ///
+    /// let select = Select::<i32>::new();
+    /// assert!(select.try_select().is_none());
+    /// ```
+    pub fn new() -> Self {
+        Self {
+            receivers: Vec::new(),
+            next: Cell::new(0),
+        }
+    }
+
+    /// Adds a channel to select from, returning the index selections report
+    /// it by. Indices count up from zero in the order channels were added.
+    ///
+    /// # Examples
+    /// ```
+    /// use atomic_mpmc::{channel, Select};
+    ///
+    /// let (_sender, receiver) = channel::<i32>(1);
+    ///
+    /// let mut select = Select::new();
+    /// assert_eq!(select.recv(&receiver), 0);
+    /// assert_eq!(select.recv(&receiver), 1);
+    /// ```
+    pub fn recv(&mut self, receiver: &'a Receiver<T>) -> usize {
+        self.receivers.push(receiver);
+        self.receivers.len() - 1
+    }
+
+    /// Receives from a channel which is ready, without blocking. Returns the
+    /// channel's index and what receiving from it gave, or `None` if no
+    /// channel is ready.
+    ///
+    /// # Examples
+    /// ```
+    /// use atomic_mpmc::{channel, Select};
+    ///
+    /// let (sender, receiver) = channel::<i32>(1);
+    ///
+    /// let mut select = Select::new();
+    /// let index = select.recv(&receiver);
+    ///
+    /// assert!(select.try_select().is_none());
+    /// sender.send(1).unwrap();
+    /// assert_eq!(select.try_select(), Some((index, Ok(1))));
+    /// ```
+    pub fn try_select(&self) -> Option<(usize, Result<T, RecvError>)> {
+        let len = self.receivers.len();
+        let start = self.next.get();
+        for offset in 0..len {
+            let index = (start + offset) % len;
+            match self.receivers[index].0.try_read() {
+                Err(RecvError(ErrorCause::WouldBlock | ErrorCause::Contended)) => {}
+                result => {
+                    self.next.set((index + 1) % len);
+                    return Some((index, result));
+                }
+            }
+        }
+        None
+    }
+
+    /// Blocks the current thread until a channel is ready, and receives from
+    /// it. Returns the channel's index and what receiving from it gave.
+    ///
+    /// # Panics
+    /// Panics if no channel was added, as this would block forever.
+    ///
+    /// # Examples
+    /// ```
+    /// use atomic_mpmc::{channel, Select};
+    /// use std::thread;
+    ///
+    /// let (_first_sender, first_receiver) = channel::<i32>(1);
+    /// let (second_sender, second_receiver) = channel::<i32>(1);
+    ///
+    /// let mut select = Select::new();
+    /// select.recv(&first_receiver);
+    /// let second = select.recv(&second_receiver);
+    ///
+    /// let handle = thread::spawn(move || second_sender.send(2).unwrap());
+    /// assert_eq!(select.select(), (second, Ok(2)));
+    /// handle.join().unwrap();
+    /// ```
+    pub fn select(&self) -> (usize, Result<T, RecvError>) {
+        assert!(!self.receivers.is_empty(), "no channels to select from");
+        self.select_until(None).unwrap()
+    }
+
+    /// Blocks the current thread until a channel is ready, and receives from
+    /// it, or until the timeout passes. Returns the channel's index and what
+    /// receiving from it gave, or `None` if the timeout passed first.
+    ///
+    /// A channel hanging up while waiting makes it ready too, so this returns
+    /// its index with `Err(RecvError(ErrorCause::HungUp))` right away.
+    ///
+    /// # Examples
+    /// ```
+    /// use atomic_mpmc::{channel, Select};
+    /// use std::time::Duration;
+    ///
+    /// let (_first_sender, first_receiver) = channel::<i32>(1);
+    /// let (_second_sender, second_receiver) = channel::<i32>(1);
+    ///
+    /// let mut select = Select::new();
+    /// select.recv(&first_receiver);
+    /// select.recv(&second_receiver);
+    ///
+    /// assert!(select.select_timeout(Duration::from_millis(10)).is_none());
+    /// ```
+    pub fn select_timeout(&self, timeout: Duration) -> Option<(usize, Result<T, RecvError>)> {
+        self.select_until(Some(Instant::now() + timeout))
+    }
+
+    fn select_until(&self, deadline: Option<Instant>) -> Option<(usize, Result<T, RecvError>)> {
+        loop {
+            if let Some(selected) = self.try_select() {
+                return Some(selected);
+            }
+
+            // Register before checking again, so a value sent in between is
+            // either seen by the check or unparks us. Wakers left behind on
+            // the other channels only cause spurious unparks, which parking
+            // allows for anyway.
+            WAKER.with(|waker| {
+                for receiver in &self.receivers {
+                    receiver.0.readable.register(waker);
+                }
+            });
+            if let Some(selected) = self.try_select() {
+                return Some(selected);
+            }
+
+            match deadline {
+                Some(deadline) => {
+                    let now = Instant::now();
+                    if deadline <= now {
+                        return None;
+                    }
+                    thread::park_timeout(deadline - now);
+                }
+                None => thread::park(),
+            }
+        }
+    }
+}
+
+impl<T> Default for Select<'_, T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> fmt::Debug for Select<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Select")
+            .field("receivers", &self.receivers)
+            .finish()
+    }
+}
@@ -0,0 +1,74 @@
+use core::{
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+use futures_sink::Sink;
+
+use crate::{ErrorCause, Overflow, SendError, Sender};
+
+/// A [`Sender`] is a [`Sink`] which sends values to the channel without
+/// blocking.
+///
+/// `poll_ready` waits for a free slot, and `start_send` writes the value into
+/// it. Flushing and closing complete immediately, since every value goes
+/// straight into the channel. Errors carry the value if there was one.
+///
+/// With multiple producers, another sender may take the free slot between
+/// `poll_ready` and `start_send`, in which case `start_send` fails with
+/// [`ErrorCause::WouldBlock`].
+///
+/// Both follow the channel's [`Overflow`] policy. With
+/// [`Overflow::DropOldest`], a full channel is ready as long as there is a
+/// value to drop.
+///
+/// # Examples
+/// ```
+/// use atomic_mpmc::channel;
+/// use futures::{executor::block_on, stream, StreamExt};
+///
+/// let (sender, receiver) = channel::<i32>(3);
+///
+/// block_on(stream::iter([1, 2, 3].map(Ok)).forward(sender)).unwrap();
+/// assert_eq!(receiver.iter().collect::<Vec<_>>(), [1, 2, 3]);
+/// ```
+impl<T> Sink<T> for Sender<T> {
+    type Error = SendError<Option<T>>;
+
+    fn poll_ready(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        let channel = &self.0;
+        if channel.check_receivers(()).is_err() {
+            return Poll::Ready(Err(SendError(None, ErrorCause::HungUp)));
+        }
+
+        // Dropping the oldest value makes room too, see `start_send`.
+        let ready = || {
+            !channel.is_full() || (channel.overflow == Overflow::DropOldest && !channel.is_empty())
+        };
+        if ready() {
+            return Poll::Ready(Ok(()));
+        }
+
+        // Check again after registering, so a freed slot can't be missed.
+        channel.writable.register(cx.waker());
+        if ready() {
+            return Poll::Ready(Ok(()));
+        }
+
+        Poll::Pending
+    }
+
+    fn start_send(self: Pin<&mut Self>, item: T) -> Result<(), Self::Error> {
+        self.0
+            .try_write_overflowing(item)
+            .map_err(|SendError(value, cause)| SendError(Some(value), cause))
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn poll_close(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+}
@@ -0,0 +1,69 @@
+use core::{
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+use futures_core::{FusedStream, Stream};
+
+use crate::Receiver;
+
+/// A stream of the values of a channel, created by
+/// [`Receiver::into_stream`].
+///
+/// It owns the receiver, so it can be moved into a task of its own, and
+/// keeps the channel alive for as long as it is around. The stream ends once
+/// the channel is hung up and empty, and lets go of the receiver then.
+///
+/// # Examples
+/// ```
+/// use atomic_mpmc::channel;
+/// use futures::{executor::block_on, StreamExt};
+///
+/// let (sender, receiver) = channel::<i32>(3);
+///
+/// sender.send_all([1, 2, 3]).unwrap();
+/// drop(sender);
+///
+/// let stream = receiver.into_stream();
+/// assert_eq!(block_on(stream.collect::<Vec<_>>()), [1, 2, 3]);
+/// ```
+#[derive(Debug)]
+pub struct RecvStream<T> {
+    receiver: Option<Receiver<T>>,
+}
+
+impl<T> RecvStream<T> {
+    pub(crate) fn new(receiver: Receiver<T>) -> Self {
+        Self {
+            receiver: Some(receiver),
+        }
+    }
+}
+
+impl<T> Stream for RecvStream<T> {
+    type Item = T;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<T>> {
+        let Some(receiver) = &self.receiver else {
+            return Poll::Ready(None);
+        };
+
+        match receiver.poll_recv(cx) {
+            Poll::Ready(Ok(value)) => Poll::Ready(Some(value)),
+            Poll::Ready(Err(_)) => {
+                self.receiver = None;
+                Poll::Ready(None)
+            }
+            Poll::Pending => Poll::Pending,
+        }
+    }
+}
+
+impl<T> FusedStream for RecvStream<T> {
+    fn is_terminated(&self) -> bool {
+        self.receiver.is_none()
+    }
+}
+
+// The stream never pins the receiver in place.
+impl<T> Unpin for RecvStream<T> {}
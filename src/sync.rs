@@ -0,0 +1,383 @@
+//! The locks the channel uses: `parking_lot`'s and `std`'s with the `std`
+//! feature, and spin locks without it. Also the memory orderings it uses,
+//! which the `seqcst` feature upgrades, and the atomic types and the cell
+//! holding the values, which are `loom`'s when built with `--cfg loom`, as
+//! are the locks.
+//!
+//! # Orderings
+//!
+//! Values are handed over through the `hot` flag of their node. A writer
+//! stores the value, then sets the flag with `Release`. A reader loads the
+//! flag with `Acquire` before it reads the value, so it sees all of the
+//! write. Taking the value clears the flag with `Release` in turn, and
+//! writers check it with `Acquire` before they write to the node again, so
+//! the read is done by then.
+//!
+//! The `write` and `read` positions are claimed with `AcqRel`
+//! compare-and-swaps, and loaded with `Acquire`, so whoever claims a node
+//! sees what the last thread to claim it did. Nothing relies on the order of
+//! the loads of the two positions: a stale one only makes the channel look
+//! fuller or emptier than it is, and the claim fails or is retried.
+//!
+//! The waiters use `SeqCst` fences between setting their flag and checking
+//! the channel again, so a thread going to sleep either sees the value which
+//! would have woken it, or its flag is seen by the thread sending it.
+
+#[cfg(not(feature = "seqcst"))]
+pub(crate) use core::sync::atomic::Ordering;
+
+/// Stands in for [`core::sync::atomic::Ordering`], with every ordering
+/// upgraded to `SeqCst`. All atomic operations then happen in a single total
+/// order, which makes the channel easier to reason about and to check with
+/// model checkers, at the cost of speed.
+#[cfg(feature = "seqcst")]
+#[allow(non_snake_case, non_upper_case_globals)]
+pub(crate) mod Ordering {
+    use core::sync::atomic::Ordering;
+
+    pub const Relaxed: Ordering = Ordering::SeqCst;
+    pub const Acquire: Ordering = Ordering::SeqCst;
+    pub const Release: Ordering = Ordering::SeqCst;
+    pub const AcqRel: Ordering = Ordering::SeqCst;
+    pub const SeqCst: Ordering = Ordering::SeqCst;
+}
+
+/// The atomic types the channel is built from. Under `--cfg loom` they are
+/// `loom`'s, so that its model checker sees every operation on them.
+pub(crate) mod atomic {
+    #[cfg(all(feature = "metrics", not(loom)))]
+    pub(crate) use core::sync::atomic::AtomicU64;
+    #[cfg(not(loom))]
+    pub(crate) use core::sync::atomic::{fence, AtomicBool, AtomicUsize};
+
+    #[cfg(all(feature = "metrics", loom))]
+    pub(crate) use loom::sync::atomic::AtomicU64;
+    #[cfg(loom)]
+    pub(crate) use loom::sync::atomic::{fence, AtomicBool, AtomicUsize};
+}
+
+/// The cell the values sit in. Under `--cfg loom` it is `loom`'s, which
+/// checks that no access to a value races with another.
+pub(crate) mod cell {
+    #[cfg(loom)]
+    pub(crate) use loom::cell::UnsafeCell;
+
+    /// [`core::cell::UnsafeCell`], behind the API of `loom`'s, which only
+    /// hands out pointers to the value for the length of a closure.
+    #[cfg(not(loom))]
+    #[derive(Debug)]
+    #[repr(transparent)]
+    pub(crate) struct UnsafeCell<T>(core::cell::UnsafeCell<T>);
+
+    #[cfg(not(loom))]
+    impl<T> UnsafeCell<T> {
+        #[inline(always)]
+        pub(crate) const fn new(value: T) -> Self {
+            Self(core::cell::UnsafeCell::new(value))
+        }
+
+        #[inline(always)]
+        pub(crate) fn with<R>(&self, f: impl FnOnce(*const T) -> R) -> R {
+            f(self.0.get())
+        }
+
+        #[inline(always)]
+        pub(crate) fn with_mut<R>(&self, f: impl FnOnce(*mut T) -> R) -> R {
+            f(self.0.get())
+        }
+    }
+}
+
+/// What the channel is shared through. Under `--cfg loom` it is `loom`'s,
+/// so that dropping the channel is ordered after everything done to it.
+#[cfg(not(loom))]
+pub(crate) use alloc::sync::Arc;
+#[cfg(loom)]
+pub(crate) use loom::sync::Arc;
+
+#[cfg(all(feature = "std", not(loom)))]
+pub(crate) use parking_lot::{Condvar, Mutex, RwLock, RwLockReadGuard};
+#[cfg(feature = "std")]
+pub(crate) use std::sync::OnceLock;
+
+#[cfg(all(feature = "std", loom))]
+pub(crate) use loom_sync::{Condvar, Mutex, RwLock, RwLockReadGuard};
+
+#[cfg(not(feature = "std"))]
+pub(crate) use spin::{Mutex, OnceLock, RwLock, RwLockReadGuard};
+
+/// `loom`'s locks, behind the API of `parking_lot`'s, so that its model
+/// checker sees threads blocking on them. Waits never time out, as `loom`
+/// has no clock.
+#[cfg(all(feature = "std", loom))]
+mod loom_sync {
+    use core::ops::{Deref, DerefMut};
+    use std::time::Instant;
+
+    pub(crate) type RwLockReadGuard<'a, T> = loom::sync::RwLockReadGuard<'a, T>;
+
+    #[derive(Debug, Default)]
+    pub(crate) struct Mutex<T>(loom::sync::Mutex<T>);
+
+    impl<T> Mutex<T> {
+        pub fn new(value: T) -> Self {
+            Self(loom::sync::Mutex::new(value))
+        }
+
+        pub fn lock(&self) -> MutexGuard<'_, T> {
+            MutexGuard(Some(self.0.lock().unwrap()))
+        }
+
+        pub fn try_lock(&self) -> Option<MutexGuard<'_, T>> {
+            self.0.try_lock().ok().map(|guard| MutexGuard(Some(guard)))
+        }
+    }
+
+    /// Only empty while a [`Condvar`] waits with it.
+    #[derive(Debug)]
+    pub(crate) struct MutexGuard<'a, T>(Option<loom::sync::MutexGuard<'a, T>>);
+
+    impl<T> Deref for MutexGuard<'_, T> {
+        type Target = T;
+
+        fn deref(&self) -> &T {
+            self.0.as_ref().unwrap()
+        }
+    }
+
+    impl<T> DerefMut for MutexGuard<'_, T> {
+        fn deref_mut(&mut self) -> &mut T {
+            self.0.as_mut().unwrap()
+        }
+    }
+
+    #[derive(Debug, Default)]
+    pub(crate) struct Condvar(loom::sync::Condvar);
+
+    impl Condvar {
+        pub fn new() -> Self {
+            Self(loom::sync::Condvar::new())
+        }
+
+        pub fn wait<T>(&self, guard: &mut MutexGuard<'_, T>) {
+            let inner = guard.0.take().unwrap();
+            guard.0 = Some(self.0.wait(inner).unwrap());
+        }
+
+        pub fn wait_until<T>(
+            &self,
+            guard: &mut MutexGuard<'_, T>,
+            _: Instant,
+        ) -> WaitTimeoutResult {
+            self.wait(guard);
+            WaitTimeoutResult
+        }
+
+        pub fn notify_all(&self) {
+            self.0.notify_all();
+        }
+    }
+
+    pub(crate) struct WaitTimeoutResult;
+
+    impl WaitTimeoutResult {
+        pub fn timed_out(&self) -> bool {
+            false
+        }
+    }
+
+    #[derive(Debug, Default)]
+    pub(crate) struct RwLock<T>(loom::sync::RwLock<T>);
+
+    impl<T> RwLock<T> {
+        pub fn new(value: T) -> Self {
+            Self(loom::sync::RwLock::new(value))
+        }
+
+        pub fn read(&self) -> RwLockReadGuard<'_, T> {
+            self.0.read().unwrap()
+        }
+
+        /// Writers only ever wait for readers to leave, so reading again
+        /// while reading can't deadlock.
+        pub fn read_recursive(&self) -> RwLockReadGuard<'_, T> {
+            self.read()
+        }
+
+        pub fn write(&self) -> loom::sync::RwLockWriteGuard<'_, T> {
+            self.0.write().unwrap()
+        }
+    }
+}
+
+#[cfg(not(feature = "std"))]
+mod spin {
+    use core::{
+        cell::UnsafeCell,
+        fmt, hint,
+        mem::MaybeUninit,
+        sync::atomic::{AtomicBool, AtomicU8, AtomicUsize},
+    };
+
+    use super::Ordering;
+
+    use lock_api::{GuardSend, RawMutex, RawRwLock, RawRwLockRecursive};
+
+    pub(crate) type Mutex<T> = lock_api::Mutex<RawSpinMutex, T>;
+    pub(crate) type RwLock<T> = lock_api::RwLock<RawSpinRwLock, T>;
+    pub(crate) type RwLockReadGuard<'a, T> = lock_api::RwLockReadGuard<'a, RawSpinRwLock, T>;
+
+    pub(crate) struct RawSpinMutex(AtomicBool);
+
+    unsafe impl RawMutex for RawSpinMutex {
+        #[allow(clippy::declare_interior_mutable_const)]
+        const INIT: Self = Self(AtomicBool::new(false));
+
+        type GuardMarker = GuardSend;
+
+        fn lock(&self) {
+            while !self.try_lock() {
+                hint::spin_loop();
+            }
+        }
+
+        fn try_lock(&self) -> bool {
+            self.0
+                .compare_exchange_weak(false, true, Ordering::Acquire, Ordering::Relaxed)
+                .is_ok()
+        }
+
+        unsafe fn unlock(&self) {
+            self.0.store(false, Ordering::Release);
+        }
+    }
+
+    /// The writer holds the lowest bit, the readers count in the others.
+    pub(crate) struct RawSpinRwLock(AtomicUsize);
+
+    const WRITER: usize = 1;
+    const READER: usize = 2;
+
+    unsafe impl RawRwLock for RawSpinRwLock {
+        #[allow(clippy::declare_interior_mutable_const)]
+        const INIT: Self = Self(AtomicUsize::new(0));
+
+        type GuardMarker = GuardSend;
+
+        fn lock_shared(&self) {
+            while !self.try_lock_shared() {
+                hint::spin_loop();
+            }
+        }
+
+        fn try_lock_shared(&self) -> bool {
+            let state = self.0.fetch_add(READER, Ordering::Acquire);
+            if state & WRITER != 0 {
+                self.0.fetch_sub(READER, Ordering::Relaxed);
+                return false;
+            }
+            true
+        }
+
+        unsafe fn unlock_shared(&self) {
+            self.0.fetch_sub(READER, Ordering::Release);
+        }
+
+        fn lock_exclusive(&self) {
+            while !self.try_lock_exclusive() {
+                hint::spin_loop();
+            }
+        }
+
+        fn try_lock_exclusive(&self) -> bool {
+            self.0
+                .compare_exchange_weak(0, WRITER, Ordering::Acquire, Ordering::Relaxed)
+                .is_ok()
+        }
+
+        unsafe fn unlock_exclusive(&self) {
+            self.0.fetch_and(!WRITER, Ordering::Release);
+        }
+    }
+
+    // Writers never wait for the readers to get out of the way while holding
+    // the lock, so reading again while reading can't deadlock.
+    unsafe impl RawRwLockRecursive for RawSpinRwLock {
+        fn lock_shared_recursive(&self) {
+            self.lock_shared();
+        }
+
+        fn try_lock_shared_recursive(&self) -> bool {
+            self.try_lock_shared()
+        }
+    }
+
+    const EMPTY: u8 = 0;
+    const SETTING: u8 = 1;
+    const SET: u8 = 2;
+
+    /// A cell which can be set once, like `std::sync::OnceLock`.
+    pub(crate) struct OnceLock<T> {
+        state: AtomicU8,
+        value: UnsafeCell<MaybeUninit<T>>,
+    }
+
+    impl<T> OnceLock<T> {
+        pub const fn new() -> Self {
+            Self {
+                state: AtomicU8::new(EMPTY),
+                value: UnsafeCell::new(MaybeUninit::uninit()),
+            }
+        }
+
+        pub fn get(&self) -> Option<&T> {
+            if self.state.load(Ordering::Acquire) != SET {
+                return None;
+            }
+
+            Some(unsafe {
+                // SAFETY: The value was written before the state was set, and
+                // is never written again.
+                (*self.value.get()).assume_init_ref()
+            })
+        }
+
+        pub fn set(&self, value: T) -> Result<(), T> {
+            if self
+                .state
+                .compare_exchange(EMPTY, SETTING, Ordering::Acquire, Ordering::Acquire)
+                .is_err()
+            {
+                return Err(value);
+            }
+
+            unsafe {
+                // SAFETY: Only the thread which moved the state to `SETTING`
+                // writes the value, and nobody reads it until it is `SET`.
+                (*self.value.get()).write(value);
+            }
+            self.state.store(SET, Ordering::Release);
+            Ok(())
+        }
+    }
+
+    impl<T> Drop for OnceLock<T> {
+        fn drop(&mut self) {
+            if *self.state.get_mut() == SET {
+                unsafe {
+                    // SAFETY: The value was set, and is never used again.
+                    self.value.get_mut().assume_init_drop();
+                }
+            }
+        }
+    }
+
+    impl<T: fmt::Debug> fmt::Debug for OnceLock<T> {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            f.debug_tuple("OnceLock").field(&self.get()).finish()
+        }
+    }
+
+    unsafe impl<T: Send> Send for OnceLock<T> {}
+    unsafe impl<T: Send + Sync> Sync for OnceLock<T> {}
+}
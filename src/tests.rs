@@ -1,4 +1,10 @@
-use std::{cell::Cell, mem::forget};
+use std::{
+    cell::{Cell, RefCell},
+    mem::forget,
+    sync::atomic::AtomicUsize,
+    thread,
+    time::{Duration, Instant},
+};
 
 use super::*;
 
@@ -81,3 +87,3678 @@ fn test_sender_hang_up() {
     drop(sender);
     assert!(receiver.recv().is_err());
 }
+
+#[test]
+fn test_builder() {
+    let (sender, receiver) = ChannelBuilder::new(1)
+        .capacity(2)
+        .name("builder")
+        .build::<u32>();
+
+    assert_eq!(sender.0.capacity(), 2);
+    assert_eq!(receiver.0.name.as_deref(), Some("builder"));
+
+    sender.send(1).unwrap();
+    sender.send(2).unwrap();
+    assert!(sender.try_send(3).is_err());
+
+    assert_eq!(receiver.recv().unwrap(), 1);
+    assert_eq!(receiver.recv().unwrap(), 2);
+}
+
+#[test]
+fn test_builder_overflow_reject() {
+    let (sender, receiver) = ChannelBuilder::new(1)
+        .overflow(Overflow::Reject)
+        .build::<u32>();
+
+    sender.send(1).unwrap();
+    assert_eq!(
+        sender.send(2).unwrap_err(),
+        SendError(2, ErrorCause::WouldBlock)
+    );
+    assert_eq!(receiver.recv().unwrap(), 1);
+
+    // Receiving still blocks.
+    assert_eq!(
+        receiver.recv_timeout(Duration::from_millis(10)),
+        Err(RecvTimeoutError::Timeout)
+    );
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn test_builder_overflow_drop_oldest() {
+    let (sender, receiver) = ChannelBuilder::new(2)
+        .overflow(Overflow::DropOldest)
+        .build::<u32>();
+
+    sender.send_all(1..=5).unwrap();
+    assert_eq!(sender.0.len(), 2);
+    assert_eq!(receiver.recv().unwrap(), 4);
+
+    // A claimed value is not dropped, so with nothing else to drop, the
+    // send waits for the receiver to be done with it.
+    let permit = receiver.reserve().unwrap();
+    sender.send(6).unwrap();
+    let handle = {
+        let sender = sender.clone();
+        thread::spawn(move || sender.send(7))
+    };
+
+    let start = Instant::now();
+    while sender.0.writable.sleepers() == 0 {
+        assert!(start.elapsed() < Duration::from_secs(5));
+        thread::sleep(Duration::from_millis(1));
+    }
+
+    assert_eq!(permit.recv(), 5);
+    handle.join().unwrap().unwrap();
+    assert_eq!(receiver.try_iter().collect::<Vec<_>>(), [7]);
+
+    drop(receiver);
+    assert_eq!(
+        sender.send(8).unwrap_err(),
+        SendError(8, ErrorCause::HungUp)
+    );
+}
+
+#[test]
+fn test_builder_overflow_traced() {
+    let (sender, receiver) = ChannelBuilder::new(1)
+        .overflow(Overflow::Reject)
+        .build::<u32>();
+    assert_eq!(sender.send_traced(1).unwrap(), 0);
+    assert_eq!(
+        sender.send_traced(2).unwrap_err(),
+        SendError(2, ErrorCause::WouldBlock)
+    );
+    assert_eq!(receiver.try_iter().collect::<Vec<_>>(), [1]);
+
+    let (sender, receiver) = ChannelBuilder::new(2)
+        .overflow(Overflow::DropOldest)
+        .build::<u32>();
+    let slots = (1..=3)
+        .map(|value| sender.send_traced(value).unwrap())
+        .collect::<Vec<_>>();
+    assert_eq!(slots, [0, 1, 0]);
+    assert_eq!(receiver.try_iter().collect::<Vec<_>>(), [2, 3]);
+}
+
+#[cfg(feature = "receipts")]
+#[test]
+fn test_builder_overflow_tracked() {
+    let (sender, receiver) = ChannelBuilder::new(1)
+        .overflow(Overflow::Reject)
+        .build::<u32>();
+    let receipt = sender.send_tracked(1).unwrap();
+    assert_eq!(
+        sender.send_tracked(2).unwrap_err(),
+        SendError(2, ErrorCause::WouldBlock)
+    );
+    assert_eq!(receiver.recv().unwrap(), 1);
+    assert!(receipt.is_received());
+
+    // The dropped value counts as taken out, but the latest one doesn't.
+    let (sender, receiver) = ChannelBuilder::new(1)
+        .overflow(Overflow::DropOldest)
+        .build::<u32>();
+    let dropped = sender.send_tracked(1).unwrap();
+    let latest = sender.send_tracked(2).unwrap();
+    assert!(dropped.is_received());
+    assert!(!latest.is_received());
+    assert_eq!(receiver.recv().unwrap(), 2);
+    assert!(latest.is_received());
+}
+
+#[cfg(feature = "async")]
+#[test]
+fn test_builder_overflow_poll_send() {
+    use futures::{
+        sink::Sink,
+        task::{noop_waker, Context, Poll},
+    };
+    use std::pin::Pin;
+
+    let (mut sender, receiver) = ChannelBuilder::new(1)
+        .overflow(Overflow::DropOldest)
+        .build::<u32>();
+    let waker = noop_waker();
+    let mut cx = Context::from_waker(&waker);
+
+    let mut value = Some(1);
+    assert_eq!(sender.poll_send(&mut value, &mut cx), Poll::Ready(Ok(())));
+    let mut value = Some(2);
+    assert_eq!(sender.poll_send(&mut value, &mut cx), Poll::Ready(Ok(())));
+
+    // The sink is ready while full, as it can drop the oldest value.
+    let mut sink = Pin::new(&mut sender);
+    assert!(sink.as_mut().poll_ready(&mut cx).is_ready());
+    sink.as_mut().start_send(3).unwrap();
+    assert_eq!(receiver.try_iter().collect::<Vec<_>>(), [3]);
+
+    // Unless that is claimed, which leaves nothing to drop.
+    sender.send(4).unwrap();
+    let permit = receiver.reserve().unwrap();
+    let mut value = Some(5);
+    assert!(sender.poll_send(&mut value, &mut cx).is_pending());
+    assert_eq!(value, Some(5));
+    assert!(Pin::new(&mut sender).poll_ready(&mut cx).is_pending());
+    assert_eq!(permit.recv(), 4);
+}
+
+#[test]
+fn test_bounded() {
+    let (sender, receiver) = bounded::<u32>(1);
+
+    assert!(sender.0.name.is_none());
+
+    sender.send(1).unwrap();
+    assert!(sender.try_send(2).is_err());
+    assert_eq!(receiver.recv().unwrap(), 1);
+}
+
+#[test]
+fn test_debug() {
+    let (sender, receiver) = channel::<u32>(4);
+    sender.send(1).unwrap();
+    sender.send(2).unwrap();
+
+    let debug = format!("{receiver:?}");
+    assert!(debug.starts_with("Receiver("));
+    assert!(debug.contains("capacity: 4"));
+    assert!(debug.contains("len: 2"));
+    assert!(debug.contains("sender_count: 1"));
+    assert!(debug.contains("receiver_count: 1"));
+    assert!(debug.contains("closed: false"));
+    assert!(!debug.contains("Node"));
+    assert!(!debug.contains("hot"));
+
+    drop(receiver);
+    assert!(format!("{sender:?}").contains("closed: true"));
+}
+
+#[test]
+#[cfg(not(any(feature = "timing", feature = "padded", feature = "receipts")))]
+fn test_zst_node_size() {
+    use std::mem::size_of;
+    assert_eq!(size_of::<Node<()>>(), size_of::<AtomicBool>());
+}
+
+#[test]
+#[cfg(all(feature = "receipts", not(any(feature = "timing", feature = "padded"))))]
+fn test_receipts_node_size() {
+    use std::mem::size_of;
+    // The flag is padded out to the alignment of the position.
+    assert_eq!(size_of::<Node<()>>(), 2 * size_of::<AtomicUsize>());
+}
+
+#[test]
+#[cfg(feature = "padded")]
+fn test_padded_node_size() {
+    use std::mem::{align_of, size_of};
+    assert_eq!(align_of::<Node<u8>>(), 64);
+    assert_eq!(size_of::<Node<()>>(), 64);
+    assert_eq!(size_of::<Node<[u8; 100]>>(), 128);
+}
+
+#[test]
+fn test_zst_read_and_write() {
+    let (sender, receiver) = channel::<()>(3);
+
+    for _ in 0..3 {
+        sender.send(()).unwrap();
+    }
+    assert_eq!(sender.try_send(()).unwrap_err().1, ErrorCause::WouldBlock);
+
+    for _ in 0..3 {
+        receiver.recv().unwrap();
+    }
+    assert_eq!(receiver.try_recv().unwrap_err().0, ErrorCause::WouldBlock);
+
+    // Wrap around the buffer a few times.
+    for _ in 0..10 {
+        sender.send(()).unwrap();
+        sender.send(()).unwrap();
+        receiver.recv().unwrap();
+        receiver.recv().unwrap();
+    }
+    assert_eq!(receiver.try_iter().count(), 0);
+}
+
+#[test]
+fn test_zst_hang_up() {
+    let (sender, receiver) = channel::<()>(2);
+
+    sender.send(()).unwrap();
+    drop(sender);
+
+    assert_eq!(receiver.try_iter().count(), 1);
+    assert_eq!(receiver.recv().unwrap_err().0, ErrorCause::HungUp);
+
+    let (sender, receiver) = channel::<()>(2);
+    drop(receiver);
+    assert_eq!(sender.send(()).unwrap_err().1, ErrorCause::HungUp);
+}
+
+#[test]
+fn test_zst_drop() {
+    static DROPS: AtomicUsize = AtomicUsize::new(0);
+    struct Marker;
+
+    impl Drop for Marker {
+        fn drop(&mut self) {
+            DROPS.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    let (sender, receiver) = channel::<Marker>(4);
+    for _ in 0..4 {
+        sender.send(Marker).unwrap();
+    }
+
+    // Receive and drop one value, forget another.
+    drop(receiver.recv().unwrap());
+    forget(receiver.recv().unwrap());
+    assert_eq!(DROPS.load(Ordering::Relaxed), 1);
+
+    // The remaining two values are dropped with the channel.
+    drop(sender);
+    drop(receiver);
+    assert_eq!(DROPS.load(Ordering::Relaxed), 3);
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn test_send_all() {
+    let (sender, receiver) = channel::<u32>(8);
+
+    let handle = std::thread::spawn(move || receiver.into_iter().collect::<Vec<_>>());
+
+    assert_eq!(sender.send_all(0..1000).unwrap(), 1000);
+    drop(sender);
+
+    assert_eq!(handle.join().unwrap(), (0..1000).collect::<Vec<_>>());
+}
+
+#[test]
+fn test_send_all_hang_up() {
+    let (sender, receiver) = channel::<u32>(8);
+    let receiver = RefCell::new(Some(receiver));
+
+    // Hang up once the iterator reaches 5.
+    let iter = (0..1000).inspect(|&i| {
+        if i == 5 {
+            let receiver = receiver.borrow_mut().take().unwrap();
+            assert_eq!(receiver.try_iter().collect::<Vec<_>>(), [0, 1, 2, 3, 4]);
+        }
+    });
+
+    let (sent, err) = sender.send_all(iter).unwrap_err();
+    assert_eq!(sent, 5);
+    assert_eq!(err.1, ErrorCause::HungUp);
+    assert_eq!(err.into_inner(), 5);
+}
+
+#[test]
+fn test_recv_timeout() {
+    let (sender, receiver) = channel::<u32>(1);
+
+    assert_eq!(
+        receiver
+            .recv_timeout(Duration::from_millis(10))
+            .unwrap_err(),
+        RecvTimeoutError::Timeout
+    );
+
+    sender.send(1).unwrap();
+    assert_eq!(receiver.recv_timeout(Duration::from_millis(10)).unwrap(), 1);
+
+    drop(sender);
+    assert_eq!(
+        receiver
+            .recv_timeout(Duration::from_millis(10))
+            .unwrap_err(),
+        RecvTimeoutError::Disconnected
+    );
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn test_recv_timeout_elapsed() {
+    let (sender, receiver) = channel::<u32>(1);
+    let timeout = Duration::from_millis(30);
+
+    let (result, elapsed) = receiver.recv_timeout_elapsed(timeout);
+    assert_eq!(result.unwrap_err().0, ErrorCause::Timeout);
+    assert!(elapsed >= timeout);
+    assert!(elapsed < timeout * 10, "{elapsed:?}");
+
+    // A ready value takes hardly any time.
+    sender.send(1).unwrap();
+    let (result, elapsed) = receiver.recv_timeout_elapsed(timeout);
+    assert_eq!(result.unwrap(), 1);
+    assert!(elapsed < timeout, "{elapsed:?}");
+
+    // Neither does a hang-up.
+    drop(sender);
+    let (result, elapsed) = receiver.recv_timeout_elapsed(timeout);
+    assert_eq!(result.unwrap_err().0, ErrorCause::HungUp);
+    assert!(elapsed < timeout, "{elapsed:?}");
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn test_recv_wakes_on_hang_up() {
+    let (sender, receiver) = channel::<u32>(1);
+
+    let handle = thread::spawn(move || receiver.recv());
+    thread::sleep(Duration::from_millis(50));
+    drop(sender);
+
+    assert_eq!(handle.join().unwrap().unwrap_err().0, ErrorCause::HungUp);
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn test_send_wakes_on_hang_up() {
+    let (sender, receiver) = channel::<u32>(1);
+    sender.send(1).unwrap();
+
+    let handle = thread::spawn(move || sender.send(2));
+    thread::sleep(Duration::from_millis(50));
+    drop(receiver);
+
+    assert_eq!(handle.join().unwrap().unwrap_err().1, ErrorCause::HungUp);
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn test_batches() {
+    let (sender, receiver) = channel::<u32>(16);
+
+    let handle = thread::spawn(move || {
+        // Two bursts, separated by much more than the window.
+        sender.send_all(0..3).unwrap();
+        thread::sleep(Duration::from_millis(300));
+        sender.send_all(3..8).unwrap();
+    });
+
+    let batches = receiver
+        .batches(4, Duration::from_millis(100))
+        .collect::<Vec<_>>();
+    handle.join().unwrap();
+
+    assert_eq!(batches, [vec![0, 1, 2], vec![3, 4, 5, 6], vec![7]]);
+}
+
+#[test]
+#[should_panic(expected = "at least one value")]
+fn test_batches_zero_max() {
+    let (_sender, receiver) = channel::<u32>(1);
+    let _ = receiver.batches(0, Duration::from_millis(10));
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn test_blocking_many_producers() {
+    let (sender, receiver) = channel::<u32>(4);
+
+    let handles = (0..4)
+        .map(|i| {
+            let sender = sender.clone();
+            thread::spawn(move || sender.send_all(i * 1000..(i + 1) * 1000).unwrap())
+        })
+        .collect::<Vec<_>>();
+    drop(sender);
+
+    let mut received = receiver.into_iter().collect::<Vec<_>>();
+    for handle in handles {
+        handle.join().unwrap();
+    }
+
+    received.sort_unstable();
+    assert_eq!(received, (0..4000).collect::<Vec<_>>());
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn test_fair_producers() {
+    const PRODUCERS: usize = 4;
+    const ITEMS: usize = 25;
+
+    let (sender, receiver) = ChannelBuilder::new(1).fair(true).build::<usize>();
+
+    let handles = (0..PRODUCERS)
+        .map(|i| {
+            let sender = sender.clone();
+            thread::spawn(move || sender.send_all(std::iter::repeat_n(i, ITEMS)).unwrap())
+        })
+        .collect::<Vec<_>>();
+    drop(sender);
+
+    let mut received = Vec::new();
+    while let Ok(i) = receiver.recv() {
+        // Slow consumer, so the producers pile up.
+        thread::sleep(Duration::from_millis(1));
+        received.push(i);
+    }
+    for handle in handles {
+        handle.join().unwrap();
+    }
+
+    assert_eq!(received.len(), PRODUCERS * ITEMS);
+
+    // Once in the queue, a producer waits for at most every other producer.
+    for i in 0..PRODUCERS {
+        let positions = received
+            .iter()
+            .enumerate()
+            .filter_map(|(pos, &j)| (i == j).then_some(pos))
+            .collect::<Vec<_>>();
+        for gap in positions.windows(2) {
+            assert!(gap[1] - gap[0] <= 2 * PRODUCERS, "producer {i} starved");
+        }
+    }
+}
+
+#[test]
+fn test_fair_try_send() {
+    let (sender, receiver) = ChannelBuilder::new(2).fair(true).build::<u32>();
+
+    sender.send(1).unwrap();
+    sender.try_send(2).unwrap();
+    assert_eq!(sender.try_send(3).unwrap_err().1, ErrorCause::WouldBlock);
+
+    assert_eq!(receiver.try_iter().collect::<Vec<_>>(), [1, 2]);
+}
+
+#[test]
+fn test_try_send_with() {
+    let (sender, receiver) = channel::<u32>(1);
+    sender.send(1).unwrap();
+
+    // Free up a slot on the third retry.
+    let mut retries = 0;
+    sender
+        .try_send_with(2, || {
+            retries += 1;
+            if retries == 3 {
+                assert_eq!(receiver.recv().unwrap(), 1);
+            }
+            retries <= 5
+        })
+        .unwrap();
+    assert_eq!(retries, 3);
+    assert_eq!(receiver.recv().unwrap(), 2);
+
+    // Give up after the permitted retries.
+    sender.send(3).unwrap();
+    let mut retries = 0;
+    let err = sender
+        .try_send_with(4, || {
+            retries += 1;
+            retries <= 5
+        })
+        .unwrap_err();
+    assert_eq!(retries, 6);
+    assert_eq!(err.1, ErrorCause::WouldBlock);
+    assert_eq!(err.into_inner(), 4);
+
+    // Hang ups are never retried.
+    drop(receiver);
+    let err = sender.try_send_with(5, || unreachable!()).unwrap_err();
+    assert_eq!(err.1, ErrorCause::HungUp);
+}
+
+#[cfg(feature = "async")]
+#[tokio::test]
+#[cfg_attr(miri, ignore)]
+async fn test_sink_forward() {
+    use futures::{stream, StreamExt};
+
+    let (sender, receiver) = channel::<u32>(4);
+
+    let handle = thread::spawn(move || receiver.into_iter().collect::<Vec<_>>());
+
+    stream::iter((0..100).map(Ok))
+        .forward(sender)
+        .await
+        .unwrap();
+
+    assert_eq!(handle.join().unwrap(), (0..100).collect::<Vec<_>>());
+}
+
+#[cfg(feature = "async")]
+#[tokio::test]
+async fn test_sink_hang_up() {
+    use futures::SinkExt;
+
+    let (mut sender, receiver) = channel::<u32>(1);
+
+    SinkExt::send(&mut sender, 1).await.unwrap();
+    drop(receiver);
+
+    let err = SinkExt::send(&mut sender, 2).await.unwrap_err();
+    assert_eq!(err.1, ErrorCause::HungUp);
+}
+
+#[cfg(feature = "async")]
+#[tokio::test]
+#[cfg_attr(miri, ignore)]
+async fn test_into_stream() {
+    use futures::{stream::FusedStream, StreamExt};
+
+    let (sender, receiver) = channel::<u32>(4);
+
+    // The stream owns the receiver, so it can run as a task of its own.
+    let task = tokio::spawn(receiver.into_stream().collect::<Vec<_>>());
+
+    let handle = thread::spawn(move || sender.send_all(0..100).unwrap());
+
+    assert_eq!(task.await.unwrap(), (0..100).collect::<Vec<_>>());
+    handle.join().unwrap();
+
+    let (sender, receiver) = channel::<u32>(1);
+    let mut stream = receiver.into_stream();
+    drop(sender);
+
+    assert_eq!(stream.next().await, None);
+    assert!(stream.is_terminated());
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn test_recv_yield_oversubscribed() {
+    const VALUES: usize = 10_000;
+    let (sender, receiver) = channel::<usize>(4);
+
+    // Far more receivers than cores, all of them contending for every value.
+    let handles = (0..16)
+        .map(|_| {
+            let receiver = receiver.clone();
+            thread::spawn(move || {
+                let mut received = Vec::new();
+                while let Ok(value) = receiver.recv_yield(10) {
+                    received.push(value);
+                }
+                received
+            })
+        })
+        .collect::<Vec<_>>();
+    drop(receiver);
+
+    sender.send_all(0..VALUES).unwrap();
+    drop(sender);
+
+    let mut received = handles
+        .into_iter()
+        .flat_map(|handle| handle.join().unwrap())
+        .collect::<Vec<_>>();
+    received.sort_unstable();
+    assert_eq!(received, (0..VALUES).collect::<Vec<_>>());
+}
+
+#[test]
+fn test_channel_logged() {
+    for requested in [1, 3, 64, 1000] {
+        let (sender, receiver, capacity) = channel_logged::<u32>(requested);
+        assert_eq!(capacity, requested);
+        assert_eq!(sender.capacity(), capacity);
+        assert_eq!(receiver.capacity(), capacity);
+    }
+}
+
+#[test]
+fn test_grow() {
+    let (sender, receiver) = ChannelBuilder::new(3).growable(true).build::<u32>();
+
+    // Wrap around, so the buffered values don't start at the first node.
+    sender.send_all([0, 1, 2]).unwrap();
+    assert_eq!(receiver.recv().unwrap(), 0);
+    assert_eq!(receiver.recv().unwrap(), 1);
+    sender.send_all([3, 4]).unwrap();
+    assert!(sender.try_send(5).is_err());
+
+    sender.try_grow(2).unwrap();
+    assert_eq!(sender.0.capacity(), 5);
+
+    sender.send_all([5, 6]).unwrap();
+    assert!(sender.try_send(7).is_err());
+    assert_eq!(receiver.try_iter().collect::<Vec<_>>(), [2, 3, 4, 5, 6]);
+
+    // Keep going around the grown buffer.
+    for i in 0..20 {
+        sender.send(i).unwrap();
+        assert_eq!(receiver.recv().unwrap(), i);
+    }
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn test_grow_wakes_senders() {
+    let (sender, receiver) = ChannelBuilder::new(1).growable(true).build::<u32>();
+    sender.send(0).unwrap();
+
+    let handle = {
+        let sender = sender.clone();
+        thread::spawn(move || sender.send(1))
+    };
+    thread::sleep(Duration::from_millis(50));
+
+    sender.try_grow(1).unwrap();
+    handle.join().unwrap().unwrap();
+
+    assert_eq!(receiver.try_iter().collect::<Vec<_>>(), [0, 1]);
+}
+
+#[test]
+fn test_grow_errors() {
+    let (sender, _receiver) = channel::<u32>(1);
+    assert_eq!(sender.try_grow(1), Err(GrowError::NotGrowable));
+
+    let (sender, _receiver) = ChannelBuilder::new(1).growable(true).build::<u32>();
+    assert_eq!(
+        sender.try_grow(usize::MAX),
+        Err(GrowError::CapacityOverflow)
+    );
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn test_grow_concurrent() {
+    let (sender, receiver) = ChannelBuilder::new(1).growable(true).build::<u32>();
+
+    let producer = {
+        let sender = sender.clone();
+        thread::spawn(move || sender.send_all(0..10000).unwrap())
+    };
+    let consumer = thread::spawn(move || receiver.into_iter().collect::<Vec<_>>());
+
+    for _ in 0..10 {
+        sender.try_grow(1).unwrap();
+        thread::sleep(Duration::from_millis(1));
+    }
+    drop(sender);
+
+    producer.join().unwrap();
+    assert_eq!(consumer.join().unwrap(), (0..10000).collect::<Vec<_>>());
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn test_close_with() {
+    let (sender, receiver) = channel::<u32>(1);
+
+    let handles = (0..3)
+        .map(|_| {
+            let receiver = receiver.clone();
+            thread::spawn(move || receiver.recv())
+        })
+        .collect::<Vec<_>>();
+    thread::sleep(Duration::from_millis(50));
+
+    sender.close_with("done");
+    // The first reason sticks.
+    receiver.close_with("ignored");
+
+    let closed = ErrorCause::Closed("done".into());
+    for handle in handles {
+        assert_eq!(handle.join().unwrap().unwrap_err().0, closed);
+    }
+    assert_eq!(sender.send(1).unwrap_err().1, closed);
+
+    let err = receiver.try_recv().unwrap_err();
+    assert_eq!(err.to_string(), "RecvError: channel closed: done");
+    assert!(format!("{sender:?}").contains("closed: true"));
+}
+
+#[test]
+fn test_try_recv_hang_up() {
+    let (sender, receiver) = channel::<u32>(2);
+    let other = sender.clone();
+
+    // Never sent anything.
+    assert_eq!(receiver.try_recv().unwrap_err().0, ErrorCause::WouldBlock);
+    drop(sender);
+    assert_eq!(receiver.try_recv().unwrap_err().0, ErrorCause::WouldBlock);
+    drop(other);
+    assert_eq!(receiver.try_recv().unwrap_err().0, ErrorCause::HungUp);
+
+    // Drained after wrapping around.
+    let (sender, receiver) = channel::<u32>(2);
+    sender.send_all(0..2).unwrap();
+    assert_eq!(receiver.try_recv().unwrap(), 0);
+    sender.send(2).unwrap();
+    drop(sender);
+
+    assert_eq!(receiver.try_recv().unwrap(), 1);
+    assert_eq!(receiver.try_recv().unwrap(), 2);
+    for _ in 0..3 {
+        assert_eq!(receiver.try_recv().unwrap_err().0, ErrorCause::HungUp);
+    }
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn test_wait_readable() {
+    let (sender, receiver) = channel::<u32>(1);
+
+    let handle = thread::spawn(move || {
+        receiver.wait_readable();
+        receiver.try_recv()
+    });
+    thread::sleep(Duration::from_millis(50));
+    assert!(!handle.is_finished());
+
+    sender.send(1).unwrap();
+    assert_eq!(handle.join().unwrap().unwrap(), 1);
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn test_wait_writable() {
+    let (sender, receiver) = channel::<u32>(1);
+    sender.send(1).unwrap();
+
+    let handle = thread::spawn(move || {
+        sender.wait_writable();
+        sender.try_send(2)
+    });
+    thread::sleep(Duration::from_millis(50));
+    assert!(!handle.is_finished());
+
+    assert_eq!(receiver.recv().unwrap(), 1);
+    handle.join().unwrap().unwrap();
+    assert_eq!(receiver.recv().unwrap(), 2);
+}
+
+#[test]
+fn test_wait_hang_up() {
+    let (sender, receiver) = channel::<u32>(1);
+    sender.send(1).unwrap();
+    drop(receiver);
+    sender.wait_writable();
+
+    let (sender, receiver) = channel::<u32>(1);
+    drop(sender);
+    receiver.wait_readable();
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn test_try_iter_snapshot() {
+    let (sender, receiver) = channel::<u32>(64);
+    sender.send_all(0..5).unwrap();
+
+    let iter = receiver.try_iter_snapshot();
+
+    // Keep sending while iterating.
+    let handle = thread::spawn(move || {
+        for i in 5.. {
+            if sender.send(i).is_err() {
+                break;
+            }
+        }
+    });
+
+    let snapshot = iter
+        .inspect(|_| thread::sleep(Duration::from_millis(10)))
+        .collect::<Vec<_>>();
+    assert_eq!(snapshot, [0, 1, 2, 3, 4]);
+
+    // The rest is still there, in order.
+    assert_eq!(receiver.recv().unwrap(), 5);
+    drop(receiver);
+    handle.join().unwrap();
+}
+
+#[test]
+fn test_error_eq() {
+    let (sender, receiver) = channel::<u32>(1);
+
+    assert_eq!(
+        receiver.try_recv().unwrap_err(),
+        RecvError(ErrorCause::WouldBlock)
+    );
+    assert_ne!(
+        receiver.try_recv().unwrap_err(),
+        RecvError(ErrorCause::HungUp)
+    );
+
+    sender.send(1).unwrap();
+    assert_eq!(
+        sender.try_send(2).unwrap_err(),
+        SendError(2, ErrorCause::WouldBlock)
+    );
+    assert_ne!(
+        sender.try_send(2).unwrap_err(),
+        SendError(3, ErrorCause::WouldBlock)
+    );
+
+    drop(receiver);
+    assert_eq!(
+        sender.send(2).unwrap_err(),
+        SendError(2, ErrorCause::HungUp)
+    );
+}
+
+#[test]
+fn test_send_error_parts() {
+    let (value, cause) = SendError(vec![1, 2], ErrorCause::WouldBlock).into_parts();
+    assert_eq!(value, [1, 2]);
+    assert_eq!(cause, ErrorCause::WouldBlock);
+
+    let err = SendError::from_parts(value, cause);
+    assert_eq!(err, SendError(vec![1, 2], ErrorCause::WouldBlock));
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn test_recv_cancellable() {
+    let (sender, receiver) = channel::<u32>(1);
+    let cancel = Arc::new(AtomicBool::new(false));
+
+    let handle = {
+        let receiver = receiver.clone();
+        let cancel = cancel.clone();
+        thread::spawn(move || receiver.recv_cancellable(&cancel))
+    };
+    thread::sleep(Duration::from_millis(50));
+    assert!(!handle.is_finished());
+
+    let cancelled = Instant::now();
+    cancel.store(true, Ordering::Release);
+    let err = handle.join().unwrap().unwrap_err();
+
+    assert_eq!(err.0, ErrorCause::Cancelled);
+    assert!(cancelled.elapsed() < Duration::from_secs(1));
+
+    // The channel is still usable.
+    sender.send(1).unwrap();
+    assert_eq!(receiver.recv().unwrap(), 1);
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn test_recv_interruptible() {
+    let (sender, receiver) = channel::<u32>(1);
+    let flag = Arc::new(AtomicBool::new(false));
+
+    let handle = {
+        let receiver = receiver.clone();
+        let flag = flag.clone();
+        thread::spawn(move || receiver.recv_interruptible(&flag, Duration::from_millis(2)))
+    };
+    thread::sleep(Duration::from_millis(50));
+    assert!(!handle.is_finished());
+
+    flag.store(true, Ordering::Release);
+    let err = handle.join().unwrap().unwrap_err();
+    assert_eq!(err.0, ErrorCause::Cancelled);
+
+    // A value which is already there wins over the flag.
+    sender.send(1).unwrap();
+    assert_eq!(
+        receiver
+            .recv_interruptible(&flag, Duration::from_millis(2))
+            .unwrap(),
+        1
+    );
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn test_flush() {
+    let (sender, receiver) = channel::<u32>(4);
+    let received = Arc::new(AtomicUsize::new(0));
+
+    let handle = {
+        let received = received.clone();
+        thread::spawn(move || {
+            for _ in receiver.iter() {
+                thread::sleep(Duration::from_millis(5));
+                received.fetch_add(1, Ordering::Relaxed);
+            }
+        })
+    };
+
+    sender.send_all(0..10).unwrap();
+    sender.flush().unwrap();
+    // The last value may still be getting processed.
+    assert!(received.load(Ordering::Relaxed) >= 9);
+
+    drop(sender);
+    handle.join().unwrap();
+    assert_eq!(received.load(Ordering::Relaxed), 10);
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn test_flush_hang_up() {
+    let (sender, receiver) = channel::<u32>(4);
+    sender.flush().unwrap();
+
+    sender.send(1).unwrap();
+    let handle = thread::spawn(move || {
+        thread::sleep(Duration::from_millis(50));
+        drop(receiver);
+    });
+
+    assert_eq!(sender.flush().unwrap_err().1, ErrorCause::HungUp);
+    handle.join().unwrap();
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn test_flush_permit() {
+    let (sender, receiver) = channel::<u32>(2);
+    sender.send(1).unwrap();
+
+    // The value is claimed, but not received yet.
+    let permit = receiver.reserve().unwrap();
+    let handle = thread::spawn(move || sender.flush());
+
+    thread::sleep(Duration::from_millis(50));
+    assert!(!handle.is_finished());
+
+    assert_eq!(permit.recv(), 1);
+    handle.join().unwrap().unwrap();
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn test_drop_and_flush_permit() {
+    let (sender, receiver) = channel::<u32>(2);
+    let other = sender.clone();
+    sender.send(1).unwrap();
+
+    let permit = receiver.reserve().unwrap();
+    let handle = thread::spawn(move || sender.drop_and_flush());
+
+    let start = Instant::now();
+    while other.0.writable.sleepers() == 0 {
+        assert!(start.elapsed() < Duration::from_secs(5));
+        thread::sleep(Duration::from_millis(1));
+    }
+
+    // Values sent since don't hold it up.
+    other.send(2).unwrap();
+    assert!(!handle.is_finished());
+    assert_eq!(permit.recv(), 1);
+    handle.join().unwrap().unwrap();
+    assert_eq!(receiver.try_recv().unwrap(), 2);
+
+    // Nor does one sent into the same node a lap later.
+    other.send(3).unwrap();
+    assert_eq!(receiver.recv().unwrap(), 3);
+    other.send(4).unwrap();
+    other.send(5).unwrap();
+    assert!(other.0.drained_before(3));
+    assert!(!other.0.drained_before(4));
+}
+
+#[test]
+fn test_reserve_send() {
+    let (sender, receiver) = channel::<u32>(2);
+
+    let permit = sender.reserve().unwrap();
+    sender.send(1).unwrap();
+    assert!(sender.try_send(2).is_err());
+    assert!(sender.try_reserve().is_err());
+
+    permit.send(3);
+    assert_eq!(receiver.try_iter().collect::<Vec<_>>(), [3, 1]);
+}
+
+#[test]
+fn test_reserve_drop() {
+    let (sender, receiver) = channel::<u32>(2);
+
+    for _ in 0..5 {
+        drop(sender.reserve().unwrap());
+        sender.send(1).unwrap();
+        drop(sender.try_reserve().unwrap());
+        assert_eq!(receiver.try_recv().unwrap(), 1);
+        assert!(receiver.try_recv().is_err());
+    }
+
+    // A dropped permit doesn't keep its slot.
+    drop(sender.reserve().unwrap());
+    sender.send(1).unwrap();
+    sender.send(2).unwrap();
+    assert_eq!(receiver.try_iter().collect::<Vec<_>>(), [1, 2]);
+
+    // Even when it is not the last one reserved.
+    assert_eq!(receiver.try_iter().count(), 0);
+    let permit = sender.reserve().unwrap();
+    sender.send(3).unwrap();
+    drop(permit);
+    sender.send(4).unwrap();
+    assert_eq!(receiver.try_iter().collect::<Vec<_>>(), [3, 4]);
+
+    // Nor does it leave anything behind for receivers.
+    let permit = sender.reserve().unwrap();
+    sender.send(5).unwrap();
+    drop(permit);
+    assert_eq!(receiver.try_recv().unwrap(), 5);
+    drop(sender.reserve().unwrap());
+    drop(sender);
+    assert_eq!(receiver.try_recv().unwrap_err().0, ErrorCause::HungUp);
+}
+
+#[test]
+fn test_reserve_grow() {
+    let (sender, receiver) = ChannelBuilder::new(2).growable(true).build::<u32>();
+
+    sender.send(1).unwrap();
+    drop(sender.reserve().unwrap());
+    sender.try_grow(2).unwrap();
+
+    sender.send_all(2..5).unwrap();
+    assert_eq!(receiver.try_iter().collect::<Vec<_>>(), [1, 2, 3, 4]);
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn test_reserve_many_producers() {
+    let (sender, receiver) = channel::<usize>(4);
+
+    let handles = (0..4)
+        .map(|producer| {
+            let sender = sender.clone();
+            thread::spawn(move || {
+                for i in 0..1000 {
+                    let permit = sender.reserve().unwrap();
+                    // Every third permit is given back unused.
+                    if i % 3 != 0 {
+                        permit.send(producer * 1000 + i);
+                    }
+                }
+            })
+        })
+        .collect::<Vec<_>>();
+    drop(sender);
+
+    let mut received = receiver.iter().collect::<Vec<_>>();
+    for handle in handles {
+        handle.join().unwrap();
+    }
+
+    received.sort_unstable();
+    let expected = (0..4)
+        .flat_map(|producer| {
+            (0..1000)
+                .filter(|i| i % 3 != 0)
+                .map(move |i| producer * 1000 + i)
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(received, expected);
+}
+
+#[test]
+fn test_recv_reserve() {
+    let (sender, receiver) = channel::<u32>(2);
+    assert!(receiver.try_reserve().is_err());
+
+    sender.send_all([1, 2]).unwrap();
+    let permit = receiver.reserve().unwrap();
+    assert_eq!(permit.recv(), 1);
+
+    // The slot is only freed once the value is received.
+    let permit = receiver.reserve().unwrap();
+    sender.send(3).unwrap();
+    assert!(sender.try_send(4).is_err());
+    assert_eq!(permit.recv(), 2);
+    sender.send(4).unwrap();
+
+    assert_eq!(receiver.try_iter().collect::<Vec<_>>(), [3, 4]);
+}
+
+#[test]
+fn test_recv_reserve_drop() {
+    let (sender, receiver) = channel::<u32>(3);
+
+    // Dropping the last claimed value puts it right back.
+    sender.send_all([1, 2]).unwrap();
+    drop(receiver.reserve().unwrap());
+    assert_eq!(receiver.recv().unwrap(), 1);
+
+    // Otherwise, it is received next.
+    sender.send(3).unwrap();
+    let permit = receiver.reserve().unwrap();
+    assert_eq!(receiver.clone().recv().unwrap(), 3);
+    drop(permit);
+    sender.send(4).unwrap();
+    assert_eq!(receiver.try_iter().collect::<Vec<_>>(), [2, 4]);
+
+    // Counted as buffered, and dropped with the channel.
+    let value = Arc::new(());
+    let (sender, receiver) = channel::<Arc<()>>(3);
+    sender.send_all([value.clone(), value.clone()]).unwrap();
+    let permit = receiver.reserve().unwrap();
+    receiver.recv().unwrap();
+    drop(permit);
+    assert_eq!(receiver.0.len(), 1);
+    assert!(!receiver.0.is_empty());
+    drop((sender, receiver));
+    assert_eq!(Arc::strong_count(&value), 1);
+}
+
+#[test]
+fn test_recv_reserve_grow() {
+    let (sender, receiver) = ChannelBuilder::new(2).growable(true).build::<u32>();
+
+    sender.send_all([1, 2]).unwrap();
+    let permit = receiver.reserve().unwrap();
+    assert_eq!(receiver.recv().unwrap(), 2);
+    drop(permit);
+
+    // The value left behind keeps its slot.
+    sender.try_grow(2).unwrap();
+    sender.send_all(3..5).unwrap();
+    assert!(sender.try_send(5).is_err());
+    assert_eq!(receiver.try_iter().collect::<Vec<_>>(), [1, 3, 4]);
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn test_recv_reserve_many_consumers() {
+    let (sender, receiver) = channel::<usize>(4);
+
+    let handles = (0..4)
+        .map(|_| {
+            let receiver = receiver.clone();
+            thread::spawn(move || {
+                let mut received = Vec::new();
+                let mut aborted = 0;
+                while let Ok(permit) = receiver.reserve() {
+                    // Every third claim is given back.
+                    aborted += 1;
+                    if aborted % 3 != 0 {
+                        received.push(permit.recv());
+                    }
+                }
+                received
+            })
+        })
+        .collect::<Vec<_>>();
+    drop(receiver);
+
+    sender.send_all(0..4000).unwrap();
+    drop(sender);
+
+    let mut received = handles
+        .into_iter()
+        .flat_map(|handle| handle.join().unwrap())
+        .collect::<Vec<_>>();
+    received.sort_unstable();
+    assert_eq!(received, (0..4000).collect::<Vec<_>>());
+}
+
+#[test]
+fn test_is_disconnected() {
+    let (sender, receiver) = channel::<u32>(1);
+    let (sender2, receiver2) = (sender.clone(), receiver.clone());
+    assert!(!sender.is_disconnected());
+    assert!(!receiver.is_disconnected());
+
+    drop(receiver);
+    assert!(!sender.is_disconnected());
+    drop(receiver2);
+    assert!(sender.is_disconnected());
+    assert!(sender2.is_disconnected());
+
+    let (sender, receiver) = channel::<u32>(1);
+    sender.send(1).unwrap();
+    drop(sender);
+    assert!(receiver.is_disconnected());
+    assert_eq!(receiver.recv().unwrap(), 1);
+}
+
+#[test]
+#[cfg(feature = "metrics")]
+fn test_high_water_mark() {
+    let (sender, receiver) = channel::<u32>(8);
+    assert_eq!(sender.high_water_mark(), 0);
+
+    let mut len = 0;
+    let mut peak = 0;
+    for (burst, drain) in [(3, 3), (5, 2), (1, 4), (4, 0), (3, 7)] {
+        sender.send_all(0..burst).unwrap();
+        len += burst;
+        peak = peak.max(len);
+
+        for _ in 0..drain {
+            receiver.recv().unwrap();
+        }
+        len -= drain;
+
+        assert_eq!(sender.high_water_mark(), peak as usize);
+    }
+    assert_eq!(peak, 7);
+}
+
+#[test]
+#[cfg(feature = "metrics")]
+#[cfg_attr(miri, ignore)]
+fn test_block_count() {
+    let (sender, receiver) = channel::<u32>(1);
+    sender.send(0).unwrap();
+    receiver.recv().unwrap();
+    assert_eq!((sender.block_count(), receiver.block_count()), (0, 0));
+
+    // The second send blocks until the first value is received.
+    sender.send(1).unwrap();
+    let handle = {
+        let sender = sender.clone();
+        thread::spawn(move || sender.send(2).unwrap())
+    };
+    thread::sleep(Duration::from_millis(50));
+    assert_eq!(receiver.recv().unwrap(), 1);
+    handle.join().unwrap();
+    assert_eq!(receiver.recv().unwrap(), 2);
+    assert_eq!(sender.block_count(), 1);
+
+    // Receiving blocks until a value is sent.
+    let handle = thread::spawn(move || {
+        thread::sleep(Duration::from_millis(50));
+        sender.send(3).unwrap();
+        sender
+    });
+    assert_eq!(receiver.recv().unwrap(), 3);
+    let sender = handle.join().unwrap();
+    assert_eq!((sender.block_count(), receiver.block_count()), (1, 1));
+}
+
+#[test]
+fn test_channel_single() {
+    let (mut sender, mut receiver) = channel_single::<u32>(2);
+
+    for i in 0..10 {
+        sender.send(i).unwrap();
+        assert_eq!(receiver.recv().unwrap(), i);
+    }
+    sender.send_all([1, 2]).unwrap();
+    assert!(sender.try_send(3).is_err());
+    assert_eq!(receiver.try_iter().collect::<Vec<_>>(), [1, 2]);
+    assert!(receiver.try_recv().is_err());
+
+    drop(receiver);
+    assert!(sender.is_disconnected());
+    assert_eq!(sender.send(1).unwrap_err().1, ErrorCause::HungUp);
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn test_channel_single_threaded() {
+    let (mut sender, receiver) = channel_single::<usize>(4);
+
+    let handle = thread::spawn(move || receiver.into_iter().collect::<Vec<_>>());
+    sender.send_all(0..10000).unwrap();
+    drop(sender);
+
+    assert_eq!(handle.join().unwrap(), (0..10000).collect::<Vec<_>>());
+}
+
+#[test]
+fn test_recv_with_len() {
+    let (sender, receiver) = channel::<u32>(5);
+    sender.send_all(0..5).unwrap();
+
+    let received = (0..5)
+        .map(|_| receiver.recv_with_len().unwrap())
+        .collect::<Vec<_>>();
+    assert_eq!(received, [(0, 4), (1, 3), (2, 2), (3, 1), (4, 0)]);
+
+    drop(sender);
+    assert_eq!(receiver.recv_with_len().unwrap_err().0, ErrorCause::HungUp);
+}
+
+#[test]
+fn test_recv_with_len_non_blocking() {
+    let (sender, receiver) = ChannelBuilder::new(2).blocking(false).build::<u32>();
+
+    // Fails right away on an empty channel, like `recv` does.
+    let start = Instant::now();
+    assert_eq!(
+        receiver.recv_with_len().unwrap_err().0,
+        ErrorCause::WouldBlock
+    );
+    assert!(start.elapsed() < Duration::from_secs(1));
+
+    sender.send_all([1, 2]).unwrap();
+    assert_eq!(receiver.recv_with_len().unwrap(), (1, 1));
+    assert_eq!(receiver.recv_with_len().unwrap(), (2, 0));
+}
+
+#[test]
+fn test_channel_from_iter() {
+    let (sender, receiver) = channel_from_iter([1, 2, 3], 5);
+    assert_eq!(sender.0.len(), 3);
+
+    sender.send_all([4, 5]).unwrap();
+    assert!(sender.try_send(6).is_err());
+    assert_eq!(receiver.try_iter().collect::<Vec<_>>(), [1, 2, 3, 4, 5]);
+
+    // Anything beyond the capacity is left out.
+    let (sender, receiver) = channel_from_iter(0..10, 2);
+    drop(sender);
+    assert_eq!(receiver.iter().collect::<Vec<_>>(), [0, 1]);
+
+    // The values are dropped along with the channel.
+    let value = Arc::new(());
+    drop(channel_from_iter([value.clone(), value.clone()], 2));
+    assert_eq!(Arc::strong_count(&value), 1);
+}
+
+#[test]
+fn test_map_filter() {
+    let (sender, receiver) = channel::<i32>(8);
+    let receiver = receiver.map(|v| v.to_string());
+
+    sender.send_all([1, 2, 3]).unwrap();
+    assert_eq!(receiver.recv().unwrap(), "1");
+    assert_eq!(receiver.try_iter().collect::<Vec<_>>(), ["2", "3"]);
+    assert_eq!(receiver.try_recv().unwrap_err().0, ErrorCause::WouldBlock);
+
+    let (sender, receiver) = channel::<i32>(8);
+    let receiver = receiver.filter(|v| v % 2 == 0);
+
+    sender.send_all([1, 3]).unwrap();
+    assert_eq!(receiver.try_recv().unwrap_err().0, ErrorCause::WouldBlock);
+    sender.send_all(4..10).unwrap();
+    assert_eq!(receiver.recv().unwrap(), 4);
+
+    // Chained, and with the hang-up passed through.
+    let receiver = receiver.map(|v| v * 10).filter(|v| *v != 60);
+    drop(sender);
+    assert_eq!(receiver.iter().collect::<Vec<_>>(), [80]);
+    assert_eq!(receiver.recv().unwrap_err().0, ErrorCause::HungUp);
+}
+
+#[test]
+fn test_dedup() {
+    let (sender, receiver) = channel::<i32>(8);
+    let receiver = receiver.dedup();
+
+    sender.send_all([1, 1, 2, 2, 2, 3]).unwrap();
+    assert_eq!(receiver.try_iter().collect::<Vec<_>>(), [1, 2, 3]);
+
+    // Compared with the last value returned, even across calls.
+    sender.send_all([3, 3]).unwrap();
+    assert_eq!(receiver.try_recv().unwrap_err().0, ErrorCause::WouldBlock);
+    sender.send_all([4, 3]).unwrap();
+    assert_eq!(receiver.recv().unwrap(), 4);
+
+    // Chained, and with the hang-up passed through.
+    let receiver = receiver.map(|v| v / 2).dedup();
+    sender.send_all([2, 5]).unwrap();
+    drop(sender);
+    assert_eq!(receiver.iter().collect::<Vec<_>>(), [1, 2]);
+    assert_eq!(receiver.recv().unwrap_err().0, ErrorCause::HungUp);
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn test_filter_map() {
+    let (sender, receiver) = channel::<String>(8);
+    let receiver = receiver.filter_map(|v| v.parse::<i32>().ok());
+
+    sender
+        .send_all(["1", "x", "2", "", "-3"].map(String::from))
+        .unwrap();
+    assert_eq!(receiver.try_iter().collect::<Vec<_>>(), [1, 2, -3]);
+
+    // Unparseable values alone leave nothing to receive.
+    sender.send("four".into()).unwrap();
+    assert_eq!(receiver.try_recv().unwrap_err().0, ErrorCause::WouldBlock);
+
+    // Blocks past the unparseable values until one parses.
+    let handle = thread::spawn(move || {
+        sender.send("five".into()).unwrap();
+        thread::sleep(Duration::from_millis(10));
+        sender.send("5".into()).unwrap();
+        sender.send("6".into()).unwrap();
+    });
+    assert_eq!(receiver.recv().unwrap(), 5);
+    handle.join().unwrap();
+
+    // Chained, and with the hang-up passed through.
+    let receiver = receiver.filter_map(|v| (v > 0).then_some(v * 10));
+    assert_eq!(receiver.iter().collect::<Vec<_>>(), [60]);
+    assert_eq!(receiver.recv().unwrap_err().0, ErrorCause::HungUp);
+}
+
+#[test]
+fn test_map_into_iter() {
+    let (sender, receiver) = channel::<i32>(8);
+    let receiver = receiver.map(|v| v + 1);
+
+    sender.send_all(0..3).unwrap();
+    drop(sender);
+    assert_eq!(receiver.into_iter().collect::<Vec<_>>(), [1, 2, 3]);
+}
+
+#[test]
+fn test_recv_matching() {
+    let (sender, receiver) = channel::<u32>(8);
+
+    sender.send_all([1, 3, 4, 5, 6, 7]).unwrap();
+    assert_eq!(receiver.recv_matching(|v| v % 2 == 0).unwrap(), 4);
+    assert_eq!(receiver.recv_matching(|v| v % 2 == 0).unwrap(), 6);
+
+    // The rest keep their order, and new values go after them.
+    sender.send(8).unwrap();
+    assert_eq!(receiver.try_iter().collect::<Vec<_>>(), [1, 3, 5, 7, 8]);
+
+    sender.send_all([1, 3]).unwrap();
+    drop(sender);
+    assert_eq!(
+        receiver.recv_matching(|v| v % 2 == 0).unwrap_err().0,
+        ErrorCause::HungUp
+    );
+    assert_eq!(receiver.try_iter().collect::<Vec<_>>(), [1, 3]);
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn test_recv_matching_blocking() {
+    let (sender, receiver) = channel::<u32>(8);
+    sender.send_all([1, 3]).unwrap();
+
+    let handle = thread::spawn(move || {
+        thread::sleep(Duration::from_millis(20));
+        sender.send(5).unwrap();
+        thread::sleep(Duration::from_millis(20));
+        sender.send(6).unwrap();
+        sender.send(7).unwrap();
+    });
+
+    assert_eq!(receiver.recv_matching(|v| v % 2 == 0).unwrap(), 6);
+    handle.join().unwrap();
+    assert_eq!(receiver.try_iter().collect::<Vec<_>>(), [1, 3, 5, 7]);
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn test_writable_iter() {
+    let (sender, receiver) = channel::<u64>(4);
+
+    let handle = thread::spawn(move || receiver.iter().take(100).collect::<Vec<_>>());
+
+    let mut sent = 0;
+    for permit in sender.writable_iter() {
+        permit.send(sent * sent);
+        sent += 1;
+    }
+
+    // Ended once the consumer was done and hung up.
+    let received = handle.join().unwrap();
+    assert_eq!(received, (0..100).map(|i| i * i).collect::<Vec<_>>());
+    assert!(sent >= 100);
+    assert!(sender.writable_iter().next().is_none());
+}
+
+#[test]
+fn test_try_peek() {
+    let (sender, receiver) = channel::<u32>(2);
+    assert_eq!(receiver.try_peek().unwrap_err().0, ErrorCause::WouldBlock);
+
+    sender.send_all([1, 2]).unwrap();
+    assert_eq!(*receiver.try_peek().unwrap(), 1);
+    assert_eq!(*receiver.try_peek().unwrap(), 1);
+    assert_eq!(receiver.try_peek().unwrap().take(), 1);
+    assert_eq!(*receiver.try_peek().unwrap(), 2);
+    assert_eq!(receiver.try_iter().collect::<Vec<_>>(), [2]);
+
+    drop(sender);
+    assert_eq!(receiver.try_peek().unwrap_err().0, ErrorCause::HungUp);
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn test_duplex() {
+    let endpoint = duplex::<usize>(4);
+
+    // Every worker passes each value on, until it has gone around 100 times.
+    let done = Arc::new(AtomicUsize::new(0));
+    let handles = (0..4)
+        .map(|_| {
+            let endpoint = endpoint.clone();
+            let done = done.clone();
+            thread::spawn(move || {
+                while done.load(Ordering::Acquire) < 4 {
+                    match endpoint.try_recv() {
+                        Ok(100) => {
+                            done.fetch_add(1, Ordering::AcqRel);
+                        }
+                        Ok(hops) => endpoint.send(hops + 1).unwrap(),
+                        Err(_) => thread::yield_now(),
+                    }
+                }
+            })
+        })
+        .collect::<Vec<_>>();
+
+    for _ in 0..4 {
+        endpoint.send(0).unwrap();
+    }
+    drop(endpoint);
+    for handle in handles {
+        handle.join().unwrap();
+    }
+}
+
+#[test]
+fn test_duplex_hang_up() {
+    let endpoint = duplex::<u32>(2);
+    let other = endpoint.clone();
+
+    other.send(1).unwrap();
+    drop(other);
+    assert_eq!(endpoint.recv().unwrap(), 1);
+    assert_eq!(endpoint.try_recv().unwrap_err().0, ErrorCause::WouldBlock);
+
+    // The last endpoint holds both sides.
+    let channel = endpoint.sender.0.clone();
+    assert_eq!(channel.senders.load(Ordering::Relaxed), 1);
+    assert_eq!(channel.receivers.load(Ordering::Relaxed), 1);
+    drop(endpoint);
+    assert_eq!(channel.senders.load(Ordering::Relaxed), 0);
+    assert_eq!(channel.receivers.load(Ordering::Relaxed), 0);
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn test_scoped_channel() {
+    let channel = ScopedChannel::<u32>::new(4);
+    let (sender, receiver) = scoped_channel(&channel);
+
+    let sum = thread::scope(|scope| {
+        for _ in 0..4 {
+            let sender = sender.clone();
+            scope.spawn(move || {
+                for i in 0..100 {
+                    sender.send(i).unwrap();
+                }
+            });
+        }
+        drop(sender);
+
+        let handles = (0..2)
+            .map(|_| {
+                let receiver = receiver.clone();
+                scope.spawn(move || receiver.iter().sum::<u32>())
+            })
+            .collect::<Vec<_>>();
+        handles.into_iter().map(|h| h.join().unwrap()).sum::<u32>()
+    });
+    assert_eq!(sum, 4 * (0..100).sum::<u32>());
+    assert_eq!(receiver.recv().unwrap_err().0, ErrorCause::HungUp);
+
+    drop(receiver);
+    assert_eq!(channel.0.senders.load(Ordering::Relaxed), 0);
+    assert_eq!(channel.0.receivers.load(Ordering::Relaxed), 0);
+
+    // The channel can be split again once the handles are gone.
+    let (sender, receiver) = scoped_channel(&channel);
+    sender.send(1).unwrap();
+    assert_eq!(receiver.try_recv().unwrap(), 1);
+    drop(receiver);
+    assert_eq!(sender.send(2).unwrap_err().1, ErrorCause::HungUp);
+}
+
+#[test]
+fn test_channel_nonzero() {
+    let (sender, receiver) = channel_nonzero::<u32>(NonZeroUsize::new(2).unwrap());
+    sender.send_all([1, 2]).unwrap();
+    assert!(sender.try_send(3).is_err());
+    assert_eq!(receiver.try_iter().collect::<Vec<_>>(), [1, 2]);
+}
+
+#[test]
+#[should_panic(expected = "channel_nonzero")]
+fn test_zero_capacity() {
+    channel::<u32>(0);
+}
+
+#[test]
+#[should_panic(expected = "channel_nonzero")]
+fn test_zero_capacity_from_vec() {
+    bounded_from_vec::<u32>(Vec::new(), 0);
+}
+
+#[test]
+fn test_zero_capacity_growable() {
+    // Never has room until it is grown, but doesn't blow up either.
+    let (sender, receiver) = ChannelBuilder::new(0).growable(true).build::<u32>();
+    assert_eq!(sender.try_send(1).unwrap_err().1, ErrorCause::WouldBlock);
+    assert!(sender.try_reserve().is_err());
+    assert_eq!(receiver.try_recv().unwrap_err().0, ErrorCause::WouldBlock);
+    assert_eq!(
+        receiver
+            .recv_timeout(Duration::from_millis(10))
+            .unwrap_err(),
+        RecvTimeoutError::Timeout
+    );
+    assert_eq!(sender.0.len(), 0);
+
+    sender.try_grow(1).unwrap();
+    sender.send(1).unwrap();
+    assert_eq!(receiver.recv().unwrap(), 1);
+
+    drop(receiver);
+    assert_eq!(sender.send(1).unwrap_err().1, ErrorCause::HungUp);
+}
+
+#[test]
+fn test_recv_timeout_error() {
+    let (sender, receiver) = channel::<u32>(1);
+
+    let timeout = Duration::from_millis(10);
+    for expected in [RecvTimeoutError::Timeout, RecvTimeoutError::Disconnected] {
+        match receiver.recv_timeout(timeout) {
+            Err(RecvTimeoutError::Timeout) => assert_eq!(expected, RecvTimeoutError::Timeout),
+            Err(RecvTimeoutError::Disconnected) => {
+                assert_eq!(expected, RecvTimeoutError::Disconnected)
+            }
+            Ok(_) => unreachable!(),
+        }
+        sender.close_with("done");
+    }
+
+    assert_eq!(
+        RecvTimeoutError::from(RecvError(ErrorCause::Timeout)),
+        RecvTimeoutError::Timeout
+    );
+    assert_eq!(
+        RecvTimeoutError::from(RecvError(ErrorCause::HungUp)),
+        RecvTimeoutError::Disconnected
+    );
+    assert_eq!(RecvTimeoutError::Timeout.to_string(), "channel timed out");
+    assert_eq!(
+        RecvTimeoutError::Disconnected.to_string(),
+        "channel disconnected"
+    );
+}
+
+#[test]
+fn test_non_blocking() {
+    let (sender, receiver) = ChannelBuilder::new(1).blocking(false).build::<u32>();
+
+    let start = Instant::now();
+    assert_eq!(receiver.recv().unwrap_err().0, ErrorCause::WouldBlock);
+    sender.send(1).unwrap();
+    assert_eq!(
+        sender.send(2).unwrap_err(),
+        SendError(2, ErrorCause::WouldBlock)
+    );
+    assert!(start.elapsed() < Duration::from_secs(1));
+
+    assert_eq!(receiver.recv().unwrap(), 1);
+    sender.send(3).unwrap();
+    assert_eq!(receiver.iter().collect::<Vec<_>>(), [3]);
+
+    drop(receiver);
+    assert_eq!(sender.send(4).unwrap_err().1, ErrorCause::HungUp);
+}
+
+#[test]
+fn test_local() {
+    let (sender, receiver) = local::channel::<u32>(2);
+
+    sender.try_send(1).unwrap();
+    sender.try_send(2).unwrap();
+    assert_eq!(sender.try_send(3).unwrap_err().1, ErrorCause::WouldBlock);
+    assert_eq!(receiver.try_recv().unwrap(), 1);
+    assert_eq!(receiver.try_recv().unwrap(), 2);
+    assert_eq!(receiver.try_recv().unwrap_err().0, ErrorCause::WouldBlock);
+
+    let receiver2 = receiver.clone();
+    drop(receiver);
+    sender.try_send(1).unwrap();
+    drop(receiver2);
+    assert_eq!(sender.try_send(2).unwrap_err().1, ErrorCause::HungUp);
+}
+
+#[test]
+fn test_local_async() {
+    use futures::{executor::block_on, join};
+
+    let (sender, receiver) = local::channel::<u32>(2);
+
+    // Taking turns on a single thread, each waiting for the other.
+    let produce = async move {
+        for i in 0..100 {
+            sender.send(i).await.unwrap();
+        }
+    };
+    let consume = async {
+        let mut received = Vec::new();
+        while let Ok(value) = receiver.recv().await {
+            received.push(value);
+        }
+        received
+    };
+
+    let ((), received) = block_on(async { join!(produce, consume) });
+    assert_eq!(received, (0..100).collect::<Vec<_>>());
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn test_recv_until() {
+    let (sender, receiver) = channel::<u32>(16);
+
+    let start = Instant::now();
+    let handle = thread::spawn(move || {
+        for i in 0..10 {
+            sender.send(i).unwrap();
+            thread::sleep(Duration::from_millis(20));
+        }
+    });
+
+    // Roughly the first half arrives before the deadline.
+    let mut buf = vec![100];
+    let count = receiver.recv_until(start + Duration::from_millis(100), &mut buf);
+    assert!(start.elapsed() >= Duration::from_millis(100));
+    assert!((2..=8).contains(&count), "{count}");
+    assert_eq!(buf[1..], (0..count as u32).collect::<Vec<_>>());
+
+    // Returns as soon as the channel hangs up, with the rest.
+    let count = receiver.recv_until(Instant::now() + Duration::from_secs(10), &mut buf);
+    assert!(start.elapsed() < Duration::from_secs(5));
+    handle.join().unwrap();
+    assert_eq!(buf[1..], (0..10).collect::<Vec<_>>());
+    assert_eq!(buf.len(), 11);
+    assert!(count >= 2);
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn test_fold() {
+    let (sender, receiver) = channel::<u64>(8);
+
+    sender.send_all(1..=5).unwrap();
+    assert_eq!(receiver.fold_pending(0, |sum, v| sum + v), 15);
+    assert_eq!(receiver.fold_pending(0, |sum, v| sum + v), 0);
+
+    let handle = thread::spawn(move || sender.send_all(1..=100).unwrap());
+    assert_eq!(receiver.fold_until_hangup(0, |sum, v| sum + v), 5050);
+    handle.join().unwrap();
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn test_spin() {
+    let (sender, receiver) = channel::<u32>(1);
+
+    for spins in [0, 1, 1000] {
+        let handle = {
+            let sender = sender.clone();
+            thread::spawn(move || {
+                for i in 0..100 {
+                    sender.send_spin(i, spins).unwrap();
+                }
+            })
+        };
+
+        for i in 0..100 {
+            assert_eq!(receiver.recv_spin(spins).unwrap(), i);
+        }
+        handle.join().unwrap();
+    }
+
+    drop(sender);
+    assert_eq!(receiver.recv_spin(10).unwrap_err().0, ErrorCause::HungUp);
+}
+
+#[test]
+fn test_local_not_send() {
+    use std::rc::Rc;
+
+    let (sender, receiver) = local::channel::<Rc<RefCell<Vec<u32>>>>(2);
+    let events = Rc::new(RefCell::new(Vec::new()));
+
+    sender.try_send(events.clone()).unwrap();
+    sender.try_send(events.clone()).unwrap();
+    for i in 0..2 {
+        receiver.try_recv().unwrap().borrow_mut().push(i);
+    }
+
+    assert_eq!(*events.borrow(), [0, 1]);
+    assert_eq!(Rc::strong_count(&events), 1);
+}
+
+#[test]
+fn test_clear() {
+    let drops = Cell::new(0);
+    struct Dropper<'a>(&'a Cell<u32>, u32);
+
+    impl<'a> Drop for Dropper<'a> {
+        fn drop(&mut self) {
+            self.0.set(self.0.get() + 1);
+        }
+    }
+
+    let (sender, receiver) = channel::<Dropper>(4);
+    for i in 0..4 {
+        sender.send(Dropper(&drops, i)).unwrap();
+    }
+
+    // A reserved value is put back before clearing, and cleared with the rest.
+    drop(receiver.reserve().unwrap());
+    assert_eq!(receiver.clear(), 4);
+    assert_eq!(drops.get(), 4);
+    assert!(receiver.0.is_empty());
+
+    // The channel works as before, and nothing is dropped twice.
+    sender.send(Dropper(&drops, 4)).unwrap();
+    assert_eq!(receiver.recv().unwrap().1, 4);
+    assert_eq!(drops.get(), 5);
+
+    drop(sender);
+    drop(receiver);
+    assert_eq!(drops.get(), 5);
+}
+
+#[test]
+fn test_positions() {
+    let (sender, receiver) = channel::<u32>(2);
+
+    // The positions keep counting past the capacity.
+    for i in 0..5 {
+        assert_eq!(sender.write_position(), i);
+        assert_eq!(receiver.read_position(), i);
+
+        sender.send(i as u32).unwrap();
+        assert_eq!(sender.write_position(), i + 1);
+        assert_eq!(receiver.read_position(), i);
+
+        receiver.recv().unwrap();
+        assert_eq!(receiver.read_position(), i + 1);
+    }
+}
+
+#[test]
+fn test_traced() {
+    let (sender, receiver) = channel::<u32>(3);
+
+    for i in 0..7 {
+        let slot = sender.send_traced(i).unwrap();
+        assert_eq!(slot, i as usize % 3);
+        assert_eq!(receiver.recv_traced().unwrap(), (i, slot));
+    }
+}
+
+#[test]
+fn test_bounded_from_vec() {
+    let values = vec!["a".to_string(), "b".to_string(), "c".to_string()];
+    let (sender, receiver) = bounded_from_vec(values, 1);
+
+    assert_eq!(sender.0.len(), 3);
+    sender.send("d".to_string()).unwrap();
+    sender.try_send("e".to_string()).unwrap_err();
+
+    drop(sender);
+    assert_eq!(receiver.iter().collect::<Vec<_>>(), ["a", "b", "c", "d"]);
+
+    // An empty vector only leaves the extra capacity.
+    let (sender, receiver) = bounded_from_vec(Vec::new(), 1);
+    sender.send(1).unwrap();
+    sender.try_send(2).unwrap_err();
+    assert_eq!(receiver.recv().unwrap(), 1);
+}
+
+#[test]
+fn test_recv_ref_mut() {
+    let (sender, receiver) = channel::<Vec<u32>>(3);
+    sender.send_all([vec![1], vec![2]]).unwrap();
+
+    // Requeued values go behind the ones already buffered.
+    let mut guard = receiver.recv_ref_mut().unwrap();
+    guard.push(10);
+    guard.requeue().unwrap();
+    sender.send(vec![3]).unwrap();
+
+    assert_eq!(receiver.recv().unwrap(), [2]);
+    assert_eq!(receiver.recv().unwrap(), [1, 10]);
+
+    // Dropped guards leave the changed value at the front.
+    let mut guard = receiver.recv_ref_mut().unwrap();
+    guard.push(20);
+    drop(guard);
+    assert_eq!(receiver.recv_ref_mut().unwrap().take(), [3, 20]);
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn test_len_clamped() {
+    let (sender, receiver) = channel::<usize>(4);
+
+    let handles = (0..2)
+        .map(|_| {
+            let sender = sender.clone();
+            thread::spawn(move || {
+                for i in 0..2000 {
+                    // Given back permits move the write position back.
+                    let permit = sender.reserve().unwrap();
+                    if i % 2 == 0 {
+                        permit.send(i);
+                    }
+                }
+            })
+        })
+        .collect::<Vec<_>>();
+
+    let watcher = {
+        let receiver = receiver.clone();
+        thread::spawn(move || {
+            while !receiver.is_disconnected() || receiver.len_clamped() > 0 {
+                assert!(receiver.len_clamped() <= 4);
+            }
+        })
+    };
+    drop(sender);
+
+    for value in receiver.iter() {
+        assert!(receiver.len_clamped() <= 4);
+        // Put back the next value now and then, moving the read position back.
+        if value % 4 == 0 {
+            if let Ok(permit) = receiver.try_reserve() {
+                drop(permit);
+            }
+        }
+    }
+
+    for handle in handles {
+        handle.join().unwrap();
+    }
+    watcher.join().unwrap();
+}
+
+fn check_wait_strategy<W: WaitStrategy + 'static>() {
+    let (sender, receiver) = ChannelBuilder::new(2).wait_strategy::<W>().build::<u32>();
+
+    let err = receiver
+        .recv_timeout(Duration::from_millis(10))
+        .unwrap_err();
+    assert_eq!(err, RecvTimeoutError::Timeout);
+
+    let handle = thread::spawn(move || {
+        for i in 0..200 {
+            sender.send(i).unwrap();
+        }
+    });
+
+    assert!(receiver.iter().eq(0..200));
+    handle.join().unwrap();
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn test_block_wait() {
+    check_wait_strategy::<BlockWait>();
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn test_spin_wait() {
+    check_wait_strategy::<SpinWait>();
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn test_yield_wait() {
+    check_wait_strategy::<YieldWait>();
+}
+
+#[test]
+fn test_try_send_ref() {
+    static CLONES: AtomicUsize = AtomicUsize::new(0);
+
+    #[derive(Debug, PartialEq)]
+    struct Counted(u32);
+
+    impl Clone for Counted {
+        fn clone(&self) -> Self {
+            CLONES.fetch_add(1, Ordering::Relaxed);
+            Self(self.0)
+        }
+    }
+
+    let (sender, receiver) = channel::<Counted>(1);
+    let value = Counted(1);
+
+    sender.try_send_ref(&value).unwrap();
+    assert_eq!(CLONES.load(Ordering::Relaxed), 1);
+
+    // Nothing is cloned while the channel is full.
+    for _ in 0..3 {
+        let err = sender.try_send_ref(&value).unwrap_err();
+        assert_eq!(err.1, ErrorCause::WouldBlock);
+    }
+    assert_eq!(CLONES.load(Ordering::Relaxed), 1);
+
+    assert_eq!(receiver.recv().unwrap(), value);
+    drop(receiver);
+    sender.try_send_ref(&value).unwrap_err();
+    assert_eq!(CLONES.load(Ordering::Relaxed), 1);
+}
+
+#[test]
+fn test_priority() {
+    let (sender, receiver) = priority::channel::<u32>(4, 3);
+
+    for i in 0..4 {
+        sender.send_with_priority(i, 0).unwrap();
+        sender.send_with_priority(10 + i, 2).unwrap();
+        sender.send_with_priority(20 + i, 1).unwrap();
+    }
+    sender.try_send_with_priority(14, 2).unwrap_err();
+
+    // Levels are received from highest to lowest, each in order.
+    let received = std::iter::from_fn(|| receiver.try_recv().ok()).collect::<Vec<_>>();
+    assert_eq!(received, [10, 11, 12, 13, 20, 21, 22, 23, 0, 1, 2, 3]);
+
+    drop(sender);
+    assert_eq!(receiver.recv().unwrap_err().0, ErrorCause::HungUp);
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn test_priority_blocking() {
+    let (sender, receiver) = priority::channel::<u32>(1, 2);
+
+    let handle = thread::spawn(move || {
+        for i in 0..100 {
+            sender.send_with_priority(i, (i % 2) as usize).unwrap();
+        }
+    });
+
+    let mut received = Vec::new();
+    while let Ok(value) = receiver.recv() {
+        received.push(value);
+    }
+    handle.join().unwrap();
+
+    received.sort_unstable();
+    assert!(received.into_iter().eq(0..100));
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn test_recv_available() {
+    let (sender, receiver) = channel::<u32>(8);
+
+    let handle = thread::spawn(move || {
+        sender.send_all(0..5).unwrap();
+        sender
+    });
+    let sender = handle.join().unwrap();
+
+    let mut buf = vec![100];
+    assert_eq!(receiver.recv_available(&mut buf).unwrap(), 5);
+    assert_eq!(buf, [100, 0, 1, 2, 3, 4]);
+
+    drop(sender);
+    let err = receiver.recv_available(&mut buf).unwrap_err();
+    assert_eq!(err.0, ErrorCause::HungUp);
+}
+
+#[test]
+fn test_into_remaining() {
+    let (sender, receiver) = channel::<String>(6);
+    for word in ["a", "b", "c", "d"] {
+        sender.send(word.to_string()).unwrap();
+    }
+    receiver.recv().unwrap();
+
+    assert_eq!(receiver.into_remaining(), ["b", "c", "d"]);
+    assert!(sender.is_disconnected());
+}
+
+/// The same code, written against `std::sync::mpsc`, for each module.
+macro_rules! mpsc_code {
+    ($name:ident, $mpsc:path) => {
+        fn $name() {
+            use $mpsc as mpsc;
+
+            let (sender, receiver) = mpsc::sync_channel::<u32>(2);
+            let handle = {
+                let sender: mpsc::SyncSender<u32> = sender.clone();
+                thread::spawn(move || {
+                    for i in 0..10 {
+                        sender.send(i).unwrap();
+                    }
+                })
+            };
+
+            let received: Vec<u32> = receiver.iter().take(10).collect();
+            assert_eq!(received, (0..10).collect::<Vec<_>>());
+            handle.join().unwrap();
+
+            sender.try_send(1).unwrap();
+            sender.try_send(2).unwrap();
+            assert!(matches!(
+                sender.try_send(3),
+                Err(mpsc::TrySendError::Full(3))
+            ));
+
+            assert_eq!(receiver.try_recv(), Ok(1));
+            assert_eq!(receiver.recv_timeout(Duration::from_millis(10)), Ok(2));
+            assert_eq!(
+                receiver.recv_timeout(Duration::from_millis(10)),
+                Err(mpsc::RecvTimeoutError::Timeout)
+            );
+            assert_eq!(receiver.try_recv(), Err(mpsc::TryRecvError::Empty));
+
+            drop(sender);
+            assert_eq!(receiver.recv(), Err(mpsc::RecvError));
+            assert_eq!(receiver.try_recv(), Err(mpsc::TryRecvError::Disconnected));
+
+            drop(receiver);
+            let (sender, _) = mpsc::sync_channel::<u32>(1);
+            assert_eq!(sender.send(4), Err(mpsc::SendError(4)));
+        }
+    };
+}
+
+mpsc_code!(std_mpsc_code, std::sync::mpsc);
+mpsc_code!(crate_mpsc_code, crate::mpsc);
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn test_mpsc() {
+    std_mpsc_code();
+    crate_mpsc_code();
+}
+
+#[test]
+fn test_try_recv_n() {
+    let (sender, receiver) = channel::<u32>(10);
+    sender.send_all(0..10).unwrap();
+
+    assert_eq!(receiver.try_recv_n(4), [0, 1, 2, 3]);
+    assert_eq!(sender.0.len(), 6);
+
+    // Put back values come first, then the rest in order.
+    let first = receiver.reserve().unwrap();
+    let second = receiver.reserve().unwrap();
+    drop(first);
+    assert_eq!(receiver.try_recv_n(3), [4, 6, 7]);
+    drop(second);
+
+    // Slots given back by senders are stepped over.
+    drop(sender.reserve().unwrap());
+    let permit = sender.reserve().unwrap();
+    sender.send(10).unwrap();
+    drop(permit);
+    assert_eq!(receiver.try_recv_n(10), [5, 8, 9, 10]);
+    assert!(receiver.try_recv_n(10).is_empty());
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn test_try_recv_n_many_consumers() {
+    let (sender, receiver) = channel::<usize>(16);
+
+    let handles = (0..4)
+        .map(|_| {
+            let receiver = receiver.clone();
+            thread::spawn(move || {
+                let mut received = Vec::new();
+                loop {
+                    let values = receiver.try_recv_n(3);
+                    if values.is_empty() && receiver.is_disconnected() && receiver.0.is_empty() {
+                        return received;
+                    }
+                    received.extend(values);
+                    thread::yield_now();
+                }
+            })
+        })
+        .collect::<Vec<_>>();
+
+    sender.send_all(0..10_000).unwrap();
+    drop(sender);
+
+    let mut received = handles
+        .into_iter()
+        .flat_map(|handle| handle.join().unwrap())
+        .collect::<Vec<_>>();
+    received.sort_unstable();
+    assert!(received.into_iter().eq(0..10_000));
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn test_huge_channel() {
+    const CAPACITY: usize = 1 << 20;
+    let (sender, receiver) = channel::<u32>(CAPACITY);
+
+    // Fill the channel once, and half of it again, wrapping around.
+    sender.send_all(0..CAPACITY as u32).unwrap();
+    sender.try_send(0).unwrap_err();
+    assert_eq!(receiver.try_recv_n(CAPACITY / 2).len(), CAPACITY / 2);
+    sender.send_all(0..CAPACITY as u32 / 2).unwrap();
+
+    assert_eq!(receiver.recv().unwrap(), CAPACITY as u32 / 2);
+    assert_eq!(sender.0.len(), CAPACITY - 1);
+
+    // The values left are dropped with the channel.
+    drop(sender);
+    drop(receiver);
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn test_finish() {
+    let (sender, receiver) = channel::<u32>(2);
+
+    let handle = {
+        let receiver = receiver.clone();
+        thread::spawn(move || receiver.recv())
+    };
+    thread::sleep(Duration::from_millis(50));
+
+    // The blocked receiver is woken up, even though the sender is alive.
+    sender.finish();
+    assert_eq!(handle.join().unwrap().unwrap_err().0, ErrorCause::HungUp);
+    assert!(receiver.is_disconnected());
+    assert_eq!(sender.send(1).unwrap_err().1, ErrorCause::HungUp);
+    assert_eq!(receiver.try_recv().unwrap_err().0, ErrorCause::HungUp);
+}
+
+#[test]
+fn test_watermarks() {
+    let events = Arc::new(Mutex::new(Vec::new()));
+    let (sender, receiver) = {
+        let (high, low) = (events.clone(), events.clone());
+        ChannelBuilder::new(10)
+            .on_high_watermark(8, move |len| high.lock().push(("high", len)))
+            .on_low_watermark(2, move |len| low.lock().push(("low", len)))
+            .build::<u32>()
+    };
+
+    // Going back and forth between the watermarks doesn't fire anything.
+    sender.send_all(0..7).unwrap();
+    receiver.try_recv_n(3);
+    sender.send_all(0..3).unwrap();
+    assert!(events.lock().is_empty());
+
+    // Reaching the high watermark fires once, until the low one is reached.
+    sender.send_all(0..2).unwrap();
+    receiver.try_recv_n(2);
+    sender.send(0).unwrap();
+    assert_eq!(*events.lock(), [("high", 8)]);
+
+    receiver.try_recv_n(5);
+    assert_eq!(*events.lock(), [("high", 8)]);
+    receiver.recv().unwrap();
+    receiver.clear();
+    sender.send_all(0..7).unwrap();
+    assert_eq!(*events.lock(), [("high", 8), ("low", 2)]);
+
+    sender.send(0).unwrap();
+    assert_eq!(*events.lock(), [("high", 8), ("low", 2), ("high", 8)]);
+}
+
+#[test]
+fn test_channel_id() {
+    use std::collections::HashSet;
+
+    let (sender, receiver) = channel::<u32>(1);
+    let (other_sender, other_receiver) = channel::<u32>(1);
+
+    let ids = [
+        sender.id(),
+        sender.clone().id(),
+        receiver.id(),
+        receiver.clone().id(),
+        other_sender.id(),
+        other_receiver.clone().id(),
+    ]
+    .into_iter()
+    .collect::<HashSet<_>>();
+
+    assert_eq!(ids.len(), 2);
+    assert!(ids.contains(&receiver.id()));
+    assert!(ids.contains(&other_sender.id()));
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn test_recv_wakeup_race() {
+    let (ping, pinged) = channel::<u32>(1);
+    let (pong, ponged) = channel::<u32>(1);
+
+    // Every receive finds the channel empty at first, racing with the send
+    // which fills it. A lost wakeup shows up as a timeout.
+    let handle = thread::spawn(move || {
+        while let Ok(value) = pinged.recv_timeout(Duration::from_secs(5)) {
+            pong.send(value).unwrap();
+        }
+    });
+
+    for i in 0..5000 {
+        ping.send(i).unwrap();
+        assert_eq!(ponged.recv_timeout(Duration::from_secs(5)), Ok(i));
+    }
+
+    drop(ping);
+    handle.join().unwrap();
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn test_send_wakeup_race() {
+    let (sender, receiver) = channel::<u32>(1);
+    let (ack, acked) = channel::<()>(1);
+
+    // Every second send finds the channel full at first, racing with the
+    // receive which empties it. A lost wakeup shows up as a timeout.
+    let handle = thread::spawn(move || {
+        for i in 0..5000 {
+            assert_eq!(receiver.recv_timeout(Duration::from_secs(5)), Ok(i));
+            ack.send(()).unwrap();
+        }
+    });
+
+    sender.send(0).unwrap();
+    for i in 1..5000 {
+        sender.send(i).unwrap();
+        acked.recv_timeout(Duration::from_secs(5)).unwrap();
+    }
+
+    acked.recv().unwrap();
+    handle.join().unwrap();
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn test_recv_seq() {
+    let (sender, receiver) = channel::<u32>(4);
+
+    let handle = thread::spawn(move || sender.send_all(0..100).unwrap());
+
+    // The sequence numbers keep counting as the ring wraps around.
+    for i in 0..100 {
+        assert_eq!(receiver.recv_seq().unwrap(), (i as u64, i));
+    }
+    handle.join().unwrap();
+
+    // A value put back keeps its sequence number.
+    let (sender, receiver) = channel::<u32>(4);
+    sender.send_all([0, 1]).unwrap();
+    let first = receiver.reserve().unwrap();
+    let second = receiver.reserve().unwrap();
+    drop(first);
+    assert_eq!(receiver.recv_seq().unwrap(), (0, 0));
+    assert_eq!(second.recv(), 1);
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn test_recv_or_else() {
+    // The fallback is produced once the channel hangs up.
+    let (sender, receiver) = channel::<u32>(1);
+    let handle = thread::spawn(move || {
+        thread::sleep(Duration::from_millis(50));
+        drop(sender);
+    });
+    assert_eq!(receiver.recv_or_else(|| 42), 42);
+    handle.join().unwrap();
+
+    // A value sent before hanging up preempts the fallback.
+    let (sender, receiver) = channel::<u32>(1);
+    let handle = thread::spawn(move || {
+        thread::sleep(Duration::from_millis(50));
+        sender.send(1).unwrap();
+    });
+    assert_eq!(receiver.recv_or_else(|| panic!("no fallback needed")), 1);
+    handle.join().unwrap();
+    assert_eq!(receiver.recv_or_else(|| 42), 42);
+}
+
+static SIGNALS: AtomicUsize = AtomicUsize::new(0);
+static WAITS: AtomicUsize = AtomicUsize::new(0);
+
+/// Counts how often the channel waits and wakes up waiters.
+#[derive(Debug)]
+struct CountingWait(BlockWait);
+
+impl WaitStrategy for CountingWait {
+    fn new(signalled: bool) -> Self {
+        Self(BlockWait::new(signalled))
+    }
+
+    fn wait(&self, deadline: Option<Instant>) -> bool {
+        WAITS.fetch_add(1, Ordering::Relaxed);
+        self.0.wait(deadline)
+    }
+
+    fn signal(&self) {
+        SIGNALS.fetch_add(1, Ordering::Relaxed);
+        self.0.signal()
+    }
+
+    fn reset(&self) {
+        self.0.reset()
+    }
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn test_waiter_half_full() {
+    let (sender, receiver) = ChannelBuilder::new(4)
+        .wait_strategy::<CountingWait>()
+        .build::<u32>();
+
+    // A half full channel is both readable and writable, so nobody waits,
+    // and there is no one to wake up.
+    sender.send_all([0, 1]).unwrap();
+    for i in 2..100 {
+        sender.send(i).unwrap();
+        assert_eq!(receiver.recv().unwrap(), i - 2);
+    }
+    assert_eq!(WAITS.load(Ordering::Relaxed), 0);
+    assert_eq!(SIGNALS.load(Ordering::Relaxed), 0);
+
+    // Once it is empty, a receiver waits, and is woken up by the next send.
+    assert_eq!(receiver.try_iter().collect::<Vec<_>>(), [98, 99]);
+    let handle = thread::spawn(move || receiver.recv().unwrap());
+    while WAITS.load(Ordering::Relaxed) == 0 {
+        thread::yield_now();
+    }
+    sender.send(100).unwrap();
+    assert_eq!(handle.join().unwrap(), 100);
+    assert!(SIGNALS.load(Ordering::Relaxed) > 0);
+}
+
+#[test]
+fn test_try_send_all() {
+    let (sender, receiver) = channel::<u32>(4);
+
+    // Wrap around, so the free slots don't start at the first node.
+    sender.send(0).unwrap();
+    assert_eq!(receiver.recv().unwrap(), 0);
+    sender.send(1).unwrap();
+
+    let rest = sender.try_send_all((2..12).collect());
+    assert_eq!(rest, (5..12).collect::<Vec<_>>());
+    assert_eq!(receiver.try_iter().collect::<Vec<_>>(), [1, 2, 3, 4]);
+
+    // Nothing fits into a full channel, or one that is hung up.
+    sender.send_all([0, 1, 2, 3]).unwrap();
+    assert_eq!(sender.try_send_all(vec![4, 5]), [4, 5]);
+    drop(receiver);
+    assert_eq!(sender.try_send_all(vec![4, 5]), [4, 5]);
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn test_try_send_all_many_producers() {
+    let (sender, receiver) = channel::<(usize, usize)>(16);
+
+    let handles = (0..4)
+        .map(|producer| {
+            let sender = sender.clone();
+            thread::spawn(move || {
+                let mut values = (0..2_500).map(|i| (producer, i)).collect::<Vec<_>>();
+                while !values.is_empty() {
+                    values = sender.try_send_all(values);
+                    thread::yield_now();
+                }
+            })
+        })
+        .collect::<Vec<_>>();
+    drop(sender);
+
+    // Every producer's values arrive, in the order they were sent.
+    let mut next = [0; 4];
+    for (producer, i) in receiver.iter() {
+        assert_eq!(i, next[producer]);
+        next[producer] += 1;
+    }
+    assert_eq!(next, [2_500; 4]);
+
+    for handle in handles {
+        handle.join().unwrap();
+    }
+}
+
+#[test]
+fn test_local_recv_cancel_safety() {
+    use futures::task::{waker, ArcWake};
+    use std::{
+        future::Future,
+        pin::pin,
+        sync::atomic::AtomicBool,
+        task::{Context, Poll},
+    };
+
+    struct Flag(AtomicBool);
+
+    impl ArcWake for Flag {
+        fn wake_by_ref(arc_self: &Arc<Self>) {
+            arc_self.0.store(true, Ordering::Relaxed);
+        }
+    }
+
+    let (sender, receiver) = local::channel::<u32>(1);
+    let flag = Arc::new(Flag(AtomicBool::new(false)));
+    let waker = waker(flag.clone());
+    let mut cx = Context::from_waker(&waker);
+
+    // Woken up for a value, but dropped before getting to take it.
+    {
+        let mut recv = pin!(receiver.recv());
+        assert!(recv.as_mut().poll(&mut cx).is_pending());
+        sender.try_send(1).unwrap();
+        assert!(flag.0.load(Ordering::Relaxed));
+    }
+
+    assert_eq!(receiver.try_recv().unwrap(), 1);
+
+    // The next future still gets the values sent after.
+    let mut recv = pin!(receiver.recv());
+    assert!(recv.as_mut().poll(&mut cx).is_pending());
+    sender.try_send(2).unwrap();
+    assert_eq!(recv.as_mut().poll(&mut cx), Poll::Ready(Ok(2)));
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn test_recv_exact() {
+    let (sender, receiver) = channel::<u32>(2);
+
+    // Blocks until the whole frame is there, even if it doesn't fit.
+    let handle = thread::spawn(move || {
+        sender.send_all(0..5).unwrap();
+        thread::sleep(Duration::from_millis(50));
+        sender.send_all(5..7).unwrap();
+    });
+
+    let mut buf = Vec::new();
+    receiver.recv_exact(4, &mut buf).unwrap();
+    assert_eq!(buf, [0, 1, 2, 3]);
+
+    // Hanging up partway leaves what arrived in the buffer.
+    buf.clear();
+    let err = receiver.recv_exact(4, &mut buf).unwrap_err();
+    assert_eq!(err.0, ErrorCause::HungUp);
+    assert_eq!(buf, [4, 5, 6]);
+    handle.join().unwrap();
+}
+
+#[test]
+fn test_recv_copy_into_wraparound() {
+    let (sender, receiver) = channel::<u8>(4);
+
+    // Leave the buffered values running past the end of the buffer.
+    sender.send_all(*b"abc").unwrap();
+    assert_eq!(receiver.try_recv_n(2), b"ab");
+    sender.send_all(*b"def").unwrap();
+
+    let mut buf = [0; 8];
+    assert_eq!(receiver.recv_copy_into(&mut buf).unwrap(), 4);
+    assert_eq!(&buf[..4], b"cdef");
+    assert_eq!(receiver.recv_copy_into(&mut []).unwrap(), 0);
+
+    // Only as many as fit, and the rest stay.
+    sender.send_all(*b"ghi").unwrap();
+    assert_eq!(receiver.recv_copy_into(&mut buf[..2]).unwrap(), 2);
+    assert_eq!(&buf[..2], b"gh");
+    assert_eq!(receiver.try_recv().unwrap(), b'i');
+
+    drop(sender);
+    assert_eq!(
+        receiver.recv_copy_into(&mut buf).unwrap_err().0,
+        ErrorCause::HungUp
+    );
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn test_recv_copy_into() {
+    let data = (0..10_000).map(|i| (i * 7) as u8).collect::<Vec<_>>();
+    let (sender, receiver) = channel::<u8>(64);
+
+    let expected = data.clone();
+    let handle = thread::spawn(move || sender.send_all(data).unwrap());
+
+    let mut received = Vec::new();
+    let mut buf = [0; 100];
+    while let Ok(count) = receiver.recv_copy_into(&mut buf) {
+        assert!((1..=buf.len()).contains(&count));
+        received.extend_from_slice(&buf[..count]);
+    }
+    handle.join().unwrap();
+
+    assert_eq!(received.len(), expected.len());
+    assert_eq!(received, expected);
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn test_recv_n() {
+    let (sender, receiver) = channel::<u32>(2);
+
+    // Each producer sends its one result.
+    let handles = (0..4)
+        .map(|i| {
+            let sender = sender.clone();
+            thread::spawn(move || sender.send(i).unwrap())
+        })
+        .collect::<Vec<_>>();
+
+    let mut values = receiver.recv_n(4).unwrap();
+    values.sort();
+    assert_eq!(values, [0, 1, 2, 3]);
+    for handle in handles {
+        handle.join().unwrap();
+    }
+
+    // Hanging up partway returns what arrived in the error.
+    sender.send(4).unwrap();
+    drop(sender);
+    let err = receiver.recv_n(4).unwrap_err();
+    assert_eq!(err.1, ErrorCause::HungUp);
+    assert_eq!(err.0, [4]);
+    assert_eq!(RecvError::from(err).0, ErrorCause::HungUp);
+}
+
+#[cfg(feature = "async")]
+#[test]
+#[cfg_attr(miri, ignore)]
+fn test_poll_recv() {
+    use futures::{executor::block_on, future::poll_fn, task::noop_waker};
+    use std::task::{Context, Poll};
+
+    let (sender, receiver) = channel::<u32>(2);
+
+    // Polled by hand, without anyone to wake up.
+    let waker = noop_waker();
+    let mut cx = Context::from_waker(&waker);
+    assert!(receiver.poll_recv(&mut cx).is_pending());
+    sender.send(1).unwrap();
+    assert_eq!(receiver.poll_recv(&mut cx), Poll::Ready(Ok(1)));
+
+    // Woken up by sends from another thread, and by the hang-up.
+    let handle = thread::spawn(move || {
+        for i in 0..100 {
+            thread::sleep(Duration::from_micros(100));
+            sender.send(i).unwrap();
+        }
+    });
+
+    let received = block_on(async {
+        let mut received = Vec::new();
+        while let Ok(value) = poll_fn(|cx| receiver.poll_recv(cx)).await {
+            received.push(value);
+        }
+        received
+    });
+    assert_eq!(received, (0..100).collect::<Vec<_>>());
+    handle.join().unwrap();
+
+    let err = receiver.poll_recv(&mut cx);
+    assert_eq!(err, Poll::Ready(Err(RecvError(ErrorCause::HungUp))));
+}
+
+#[cfg(feature = "async")]
+#[test]
+#[cfg_attr(miri, ignore)]
+fn test_poll_send() {
+    use futures::{executor::block_on, future::poll_fn, task::noop_waker};
+    use std::task::{Context, Poll};
+
+    let (sender, receiver) = channel::<u32>(1);
+    let waker = noop_waker();
+    let mut cx = Context::from_waker(&waker);
+
+    // A pending poll keeps the value for the next attempt.
+    let mut value = Some(0);
+    assert_eq!(sender.poll_send(&mut value, &mut cx), Poll::Ready(Ok(())));
+    let mut value = Some(1);
+    assert!(sender.poll_send(&mut value, &mut cx).is_pending());
+    assert_eq!(value, Some(1));
+
+    // Woken up as another thread drains the channel.
+    let handle = thread::spawn(move || {
+        let mut received = Vec::new();
+        while let Ok(value) = receiver.recv() {
+            thread::sleep(Duration::from_micros(100));
+            received.push(value);
+        }
+        received
+    });
+
+    block_on(async {
+        for i in 1..100 {
+            let mut value = Some(i);
+            poll_fn(|cx| sender.poll_send(&mut value, cx))
+                .await
+                .unwrap();
+            assert_eq!(value, None);
+        }
+    });
+    drop(sender);
+    assert_eq!(handle.join().unwrap(), (0..100).collect::<Vec<_>>());
+
+    // The value comes back in the error once the channel is hung up.
+    let (sender, receiver) = channel::<u32>(1);
+    drop(receiver);
+    let mut value = Some(1);
+    let err = sender.poll_send(&mut value, &mut cx);
+    assert_eq!(err, Poll::Ready(Err(SendError(1, ErrorCause::HungUp))));
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn test_iter_idle_timeout() {
+    let (sender, receiver) = channel::<u32>(4);
+
+    // The values keep coming well within the timeout, for longer than it.
+    let handle = thread::spawn(move || {
+        for i in 0..10 {
+            sender.send(i).unwrap();
+            thread::sleep(Duration::from_millis(20));
+        }
+        thread::sleep(Duration::from_secs(1));
+        drop(sender);
+    });
+
+    let start = Instant::now();
+    let received = receiver
+        .iter_idle_timeout(Duration::from_millis(200))
+        .collect::<Vec<_>>();
+    assert_eq!(received, (0..10).collect::<Vec<_>>());
+
+    // It ended after one timeout of silence, before the hang-up.
+    assert!(start.elapsed() >= Duration::from_millis(380));
+    assert!(!receiver.is_disconnected());
+    handle.join().unwrap();
+
+    // A hang-up ends it right away.
+    let (sender, receiver) = channel::<u32>(4);
+    drop(sender);
+    let start = Instant::now();
+    assert_eq!(
+        receiver.iter_idle_timeout(Duration::from_secs(5)).next(),
+        None
+    );
+    assert!(start.elapsed() < Duration::from_secs(5));
+}
+
+#[test]
+fn test_channel_named() {
+    let (sender, receiver) = channel_named::<u32>(1, "jobs");
+
+    assert_eq!(sender.name(), Some("jobs"));
+    assert_eq!(receiver.clone().name(), Some("jobs"));
+    assert!(format!("{sender:?}").contains("\"jobs\""));
+    assert!(format!("{receiver:?}").contains("\"jobs\""));
+
+    let (sender, receiver) = channel::<u32>(1);
+    assert_eq!(sender.name(), None);
+    assert_eq!(receiver.name(), None);
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn test_waiting_receivers() {
+    let (sender, receiver) = channel::<u32>(4);
+
+    let wait_for = |count| {
+        let start = Instant::now();
+        while sender.waiting_receivers() != count {
+            assert!(start.elapsed() < Duration::from_secs(5));
+            thread::sleep(Duration::from_millis(1));
+        }
+    };
+
+    let handles = (0..3)
+        .map(|_| {
+            let receiver = receiver.clone();
+            thread::spawn(move || receiver.recv())
+        })
+        .collect::<Vec<_>>();
+    wait_for(3);
+
+    // Every receiver woken up with a value stops counting.
+    sender.send_all([0, 1, 2]).unwrap();
+    let mut received = handles
+        .into_iter()
+        .map(|handle| handle.join().unwrap().unwrap())
+        .collect::<Vec<_>>();
+    received.sort_unstable();
+    assert_eq!(received, [0, 1, 2]);
+    assert_eq!(sender.waiting_receivers(), 0);
+
+    // So does one that timed out.
+    let timed = receiver.clone();
+    let handle = thread::spawn(move || timed.recv_timeout(Duration::from_millis(50)));
+    assert_eq!(handle.join().unwrap(), Err(RecvTimeoutError::Timeout));
+    assert_eq!(sender.waiting_receivers(), 0);
+
+    // And ones woken up by the channel closing.
+    let handles = (0..2)
+        .map(|_| {
+            let receiver = receiver.clone();
+            thread::spawn(move || receiver.recv())
+        })
+        .collect::<Vec<_>>();
+    wait_for(2);
+    sender.close_with("done");
+    for handle in handles {
+        assert!(handle.join().unwrap().is_err());
+    }
+    assert_eq!(sender.waiting_receivers(), 0);
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn test_drop_and_flush() {
+    let (sender, receiver) = channel::<u32>(4);
+    let received = Arc::new(AtomicUsize::new(0));
+
+    let handle = {
+        let received = received.clone();
+        thread::spawn(move || {
+            for _ in receiver.iter() {
+                thread::sleep(Duration::from_millis(5));
+                received.fetch_add(1, Ordering::Relaxed);
+            }
+        })
+    };
+
+    // Another sender keeps sending, but its later values don't hold it up.
+    let other = sender.clone();
+    let other = thread::spawn(move || other.send_all(10..40).unwrap());
+
+    sender.send_all(0..10).unwrap();
+    sender.drop_and_flush().unwrap();
+    // The last value may still be getting processed.
+    assert!(received.load(Ordering::Relaxed) >= 9);
+
+    other.join().unwrap();
+    handle.join().unwrap();
+    assert_eq!(received.load(Ordering::Relaxed), 40);
+
+    // Gives up once the receivers hang up.
+    let (sender, receiver) = channel::<u32>(4);
+    sender.send(1).unwrap();
+    let handle = thread::spawn(move || {
+        thread::sleep(Duration::from_millis(50));
+        drop(receiver);
+    });
+    assert_eq!(sender.drop_and_flush().unwrap_err().1, ErrorCause::HungUp);
+    handle.join().unwrap();
+}
+
+#[test]
+fn test_drain_into_iter() {
+    let (sender, receiver) = channel::<u32>(4);
+    sender.send_all([0, 1, 2]).unwrap();
+
+    // Ends at the values buffered when it was created, while the sender is
+    // still around and sending.
+    let mut iter = receiver.clone().drain_into_iter();
+    assert_eq!(iter.next(), Some(0));
+    sender.send(3).unwrap();
+    assert_eq!(iter.collect::<Vec<_>>(), [1, 2]);
+
+    // The receiver was dropped along with the iterator, but not the clone.
+    assert_eq!(receiver.recv().unwrap(), 3);
+    assert_eq!(sender.0.receivers.load(Ordering::Relaxed), 1);
+
+    // An empty channel ends it right away.
+    assert_eq!(receiver.drain_into_iter().next(), None);
+    assert!(sender.is_disconnected());
+}
+
+#[test]
+fn test_capacity_remaining() {
+    let (sender, receiver) = channel::<u32>(8);
+    assert_eq!(sender.capacity_remaining(), 8);
+
+    sender.send_all([0, 1, 2]).unwrap();
+    assert_eq!(sender.capacity_remaining(), 5);
+
+    // A batch of that size fits without blocking.
+    assert!(sender.try_send_all((3..8).collect()).is_empty());
+    assert_eq!(sender.capacity_remaining(), 0);
+
+    receiver.recv().unwrap();
+    assert_eq!(sender.capacity_remaining(), 1);
+}
+
+#[test]
+fn test_try_send_counted() {
+    let (sender, receiver) = channel::<u32>(4);
+
+    let remaining = (0..4)
+        .map(|i| sender.try_send_counted(i).unwrap())
+        .collect::<Vec<_>>();
+    assert_eq!(remaining, [3, 2, 1, 0]);
+
+    let err = sender.try_send_counted(4).unwrap_err();
+    assert_eq!(err, SendError(4, ErrorCause::WouldBlock));
+
+    receiver.recv().unwrap();
+    assert_eq!(sender.try_send_counted(4).unwrap(), 0);
+
+    // It also works through the gate of a growable channel.
+    let (sender, _receiver) = ChannelBuilder::new(1).growable(true).build::<u32>();
+    assert_eq!(sender.try_send_counted(0).unwrap(), 0);
+    sender.try_grow(2).unwrap();
+    assert_eq!(sender.try_send_counted(1).unwrap(), 1);
+}
+
+#[test]
+fn test_send_coalesce() {
+    let (sender, receiver) = channel::<u32>(1);
+    let merge = |last: &mut u32, delta| *last += delta;
+
+    for delta in 1..=10 {
+        sender.send_coalesce(delta, merge).unwrap();
+    }
+    assert_eq!(receiver.try_recv().unwrap(), (1..=10).sum::<u32>());
+    receiver.try_recv().unwrap_err();
+
+    // Only the last value is merged into, once the channel is full.
+    let (sender, receiver) = channel::<u32>(2);
+    for delta in [1, 2, 3, 4] {
+        sender.send_coalesce(delta, merge).unwrap();
+    }
+    assert_eq!(receiver.try_iter().collect::<Vec<_>>(), [1, 9]);
+
+    drop(receiver);
+    let err = sender.send_coalesce(1, merge).unwrap_err();
+    assert_eq!(err, SendError(1, ErrorCause::HungUp));
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn test_send_coalesce_threaded() {
+    let (sender, receiver) = channel::<u64>(1);
+
+    // Nothing gets lost or counted twice, however the merges and receives
+    // interleave.
+    let handle = thread::spawn(move || {
+        for delta in 1..=10_000 {
+            sender
+                .send_coalesce(delta, |last, delta| *last += delta)
+                .unwrap();
+        }
+    });
+
+    let receivers = (0..2)
+        .map(|_| {
+            let receiver = receiver.clone();
+            thread::spawn(move || receiver.iter().sum::<u64>())
+        })
+        .collect::<Vec<_>>();
+    drop(receiver);
+
+    handle.join().unwrap();
+    let sum = receivers
+        .into_iter()
+        .map(|handle| handle.join().unwrap())
+        .sum::<u64>();
+    assert_eq!(sum, (1..=10_000).sum::<u64>());
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn test_send_coalesce_grow() {
+    // Nothing to merge into, so the sender parks until there is room.
+    let (sender, receiver) = ChannelBuilder::new(0).growable(true).build::<u32>();
+    let handle = {
+        let sender = sender.clone();
+        thread::spawn(move || sender.send_coalesce(1, |last, delta| *last += delta))
+    };
+
+    let start = Instant::now();
+    while sender.0.writable.sleepers() == 0 {
+        assert!(start.elapsed() < Duration::from_secs(5));
+        thread::sleep(Duration::from_millis(1));
+    }
+
+    // Growing waits for everyone to leave the gate, the parked sender too.
+    sender.try_grow(1).unwrap();
+    handle.join().unwrap().unwrap();
+    assert_eq!(receiver.try_recv().unwrap(), 1);
+}
+
+#[test]
+fn test_send_coalesce_concurrent_send() {
+    let (sender, receiver) = channel::<u32>(2);
+    sender.send_all([1, 2]).unwrap();
+
+    // The claimed first value keeps the channel full until merging starts,
+    // and by then another sender and receiver went past the merged nodes.
+    let permit = RefCell::new(Some(receiver.reserve().unwrap()));
+    sender
+        .send_coalesce(3, |last, delta| {
+            assert_eq!(permit.take().unwrap().recv(), 1);
+            sender.try_send(4).unwrap();
+            assert_eq!(receiver.try_recv().unwrap(), 4);
+            *last += delta;
+        })
+        .unwrap();
+
+    // The merged value is neither lost nor received twice, and the channel
+    // keeps working.
+    assert_eq!(receiver.try_iter().collect::<Vec<_>>(), [5]);
+    assert_eq!(sender.0.len(), 0);
+    sender.send_all([6, 7]).unwrap();
+    assert_eq!(receiver.try_iter().collect::<Vec<_>>(), [6, 7]);
+}
+
+/// Counts how many times the value with each id was dropped.
+struct Tracked<'a>(&'a [Cell<u32>], usize);
+
+impl Drop for Tracked<'_> {
+    fn drop(&mut self) {
+        let drops = &self.0[self.1];
+        drops.set(drops.get() + 1);
+    }
+}
+
+#[test]
+fn test_drop_audit_wraparound() {
+    for capacity in 1..=4 {
+        // Start filling at every position of the first few laps.
+        for offset in 0..3 * capacity {
+            for len in 0..=capacity {
+                let drops = (0..offset + len).map(|_| Cell::new(0)).collect::<Vec<_>>();
+                let (sender, receiver) = channel::<Tracked>(capacity);
+
+                for id in 0..offset {
+                    sender.send(Tracked(&drops, id)).unwrap();
+                    drop(receiver.recv().unwrap());
+                }
+                for id in offset..offset + len {
+                    sender.send(Tracked(&drops, id)).unwrap();
+                }
+                assert!(drops.iter().take(offset).all(|d| d.get() == 1));
+                assert!(drops.iter().skip(offset).all(|d| d.get() == 0));
+
+                // The last handle drops every buffered value, once.
+                drop(sender);
+                drop(receiver);
+                assert!(
+                    drops.iter().all(|d| d.get() == 1),
+                    "capacity {capacity}, offset {offset}, len {len}: {drops:?}"
+                );
+            }
+        }
+    }
+}
+
+#[test]
+fn test_drop_audit_claimed() {
+    let drops = (0..8).map(|_| Cell::new(0)).collect::<Vec<_>>();
+    let (sender, receiver) = ChannelBuilder::new(3).growable(true).build::<Tracked>();
+
+    // Wrap around.
+    for id in 0..2 {
+        sender.send(Tracked(&drops, id)).unwrap();
+        drop(receiver.recv().unwrap());
+    }
+
+    // Leave a skipped position behind a filled one.
+    let skipped = sender.reserve().unwrap();
+    sender.send(Tracked(&drops, 2)).unwrap();
+    drop(skipped);
+
+    // And a returned one behind a received one.
+    sender.send(Tracked(&drops, 3)).unwrap();
+    let returned = receiver.reserve().unwrap();
+    drop(receiver.recv().unwrap());
+    drop(returned);
+
+    // Grow across the wraparound, and fill the new room.
+    sender.try_grow(3).unwrap();
+    for id in 4..8 {
+        sender.try_send(Tracked(&drops, id)).unwrap();
+    }
+
+    drop((sender, receiver));
+    assert!(drops.iter().all(|d| d.get() == 1), "{drops:?}");
+}
+
+#[cfg(feature = "receipts")]
+#[test]
+#[cfg_attr(miri, ignore)]
+fn test_send_tracked() {
+    let (sender, receiver) = channel::<u32>(2);
+
+    let receipt = sender.send_tracked(1).unwrap();
+    assert!(!receipt.is_received());
+    assert_eq!(
+        receipt
+            .wait_timeout(Duration::from_millis(10))
+            .unwrap_err()
+            .0,
+        ErrorCause::Timeout
+    );
+
+    let handle = thread::spawn(move || {
+        thread::sleep(Duration::from_millis(10));
+        let value = receiver.recv().unwrap();
+        (receiver, value)
+    });
+    receipt.wait().unwrap();
+    assert!(receipt.is_received());
+    let (receiver, value) = handle.join().unwrap();
+    assert_eq!(value, 1);
+
+    // Laps later, the node is reused, and the receipts stay apart.
+    let receipts = (0..5)
+        .map(|value| {
+            let receipt = sender.send_tracked(value).unwrap();
+            receiver.recv().unwrap();
+            receipt
+        })
+        .collect::<Vec<_>>();
+    assert!(receipts.iter().all(Receipt::is_received));
+    let pending = sender.send_tracked(5).unwrap();
+    assert!(!pending.is_received());
+
+    // Never received, as the value is dropped along with the channel.
+    drop(receiver);
+    assert_eq!(pending.wait().unwrap_err().0, ErrorCause::HungUp);
+}
+
+#[cfg(feature = "receipts")]
+#[test]
+fn test_send_tracked_grow() {
+    let (sender, receiver) = ChannelBuilder::new(2).growable(true).build::<u32>();
+
+    // Wrap around, so the positions move to other nodes when growing.
+    sender.send_all([0, 1]).unwrap();
+    receiver.recv().unwrap();
+    let first = sender.send_tracked(2).unwrap();
+    receiver.recv().unwrap();
+    let second = sender.send_tracked(3).unwrap();
+    sender.try_grow(3).unwrap();
+
+    assert!(!first.is_received());
+    assert!(!second.is_received());
+    assert_eq!(receiver.recv().unwrap(), 2);
+    assert!(first.is_received());
+    assert!(!second.is_received());
+    assert_eq!(receiver.recv().unwrap(), 3);
+    assert!(second.is_received());
+}
+
+#[cfg(all(feature = "receipts", feature = "async"))]
+#[tokio::test]
+#[cfg_attr(miri, ignore)]
+async fn test_send_tracked_await() {
+    let (sender, receiver) = channel::<u32>(1);
+
+    let receipt = sender.send_tracked(1).unwrap();
+    let handle = thread::spawn(move || {
+        thread::sleep(Duration::from_millis(10));
+        receiver.recv().unwrap()
+    });
+
+    receipt.await.unwrap();
+    assert_eq!(handle.join().unwrap(), 1);
+}
+
+#[cfg(feature = "timing")]
+#[test]
+#[cfg_attr(miri, ignore)]
+fn test_recv_timed() {
+    let (sender, receiver) = ChannelBuilder::new(2).growable(true).build::<u32>();
+
+    sender.send(1).unwrap();
+    thread::sleep(Duration::from_millis(20));
+    sender.send(2).unwrap();
+
+    // The send time moves along with the value when the channel grows.
+    sender.try_grow(2).unwrap();
+
+    let (value, first) = receiver.recv_timed().unwrap();
+    assert_eq!(value, 1);
+    assert!(first >= Duration::from_millis(20));
+
+    let (value, second) = receiver.recv_timed().unwrap();
+    assert_eq!(value, 2);
+    assert!(second < first);
+
+    drop(sender);
+    assert_eq!(receiver.recv_timed().unwrap_err().0, ErrorCause::HungUp);
+
+    // Prefilled values are timed from when the channel was created.
+    let (_sender, receiver) = channel_from_iter([1], 1);
+    thread::sleep(Duration::from_millis(10));
+    assert!(receiver.recv_timed().unwrap().1 >= Duration::from_millis(10));
+}
+
+#[test]
+fn test_try_reserve_many() {
+    let (sender, receiver) = channel::<u32>(4);
+
+    let mut permits = sender.try_reserve_many(3).unwrap();
+    assert_eq!(permits.len(), 3);
+    assert_eq!(sender.capacity_remaining(), 1);
+    assert_eq!(
+        sender.try_reserve_many(2).unwrap_err().1,
+        ErrorCause::WouldBlock
+    );
+
+    permits.send(1).unwrap();
+    permits.send(2).unwrap();
+    assert_eq!(permits.len(), 1);
+    drop(permits);
+
+    // The unused slot was given back.
+    assert_eq!(sender.0.write.load(Ordering::Relaxed), 2);
+    assert_eq!(sender.capacity_remaining(), 2);
+    sender.send(3).unwrap();
+    assert_eq!(receiver.try_iter().collect::<Vec<_>>(), [1, 2, 3]);
+
+    // With a slot claimed after the batch, the unused ones are skipped.
+    let mut permits = sender.try_reserve_many(3).unwrap();
+    sender.send(4).unwrap();
+    permits.send(5).unwrap();
+    drop(permits);
+    assert_eq!(receiver.try_iter().collect::<Vec<_>>(), [5, 4]);
+    assert_eq!(sender.capacity_remaining(), 4);
+
+    // Using up the batch leaves nothing to give back.
+    let mut permits = sender.try_reserve_many(4).unwrap();
+    for i in 0..4 {
+        permits.send(i).unwrap();
+    }
+    assert_eq!(
+        permits.send(4).unwrap_err(),
+        SendError(4, ErrorCause::WouldBlock)
+    );
+    drop(permits);
+    assert_eq!(receiver.try_iter().collect::<Vec<_>>(), [0, 1, 2, 3]);
+
+    assert!(sender.try_reserve_many(0).unwrap().is_empty());
+    assert!(sender.try_reserve_many(5).is_err());
+    drop(receiver);
+    assert_eq!(
+        sender.try_reserve_many(1).unwrap_err().1,
+        ErrorCause::HungUp
+    );
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn test_grouped() {
+    let (sender, receiver) = grouped::channel::<u32>(4);
+    let other = receiver.group("other");
+    assert_eq!(receiver.name(), grouped::DEFAULT_GROUP);
+
+    // Every group sees the whole sequence, however many receivers it has.
+    let handles = [receiver.clone(), receiver.clone(), other.clone()]
+        .into_iter()
+        .map(|receiver| {
+            thread::spawn(move || {
+                let values = receiver.iter().collect::<Vec<_>>();
+                (receiver.name().to_owned(), values)
+            })
+        })
+        .collect::<Vec<_>>();
+    drop((receiver, other));
+
+    let producers = (0..2)
+        .map(|p| {
+            let sender = sender.clone();
+            thread::spawn(move || {
+                for i in 0..100 {
+                    sender.send(p * 100 + i).unwrap();
+                }
+            })
+        })
+        .collect::<Vec<_>>();
+    drop(sender);
+    for producer in producers {
+        producer.join().unwrap();
+    }
+
+    let mut default = Vec::new();
+    let mut other = Vec::new();
+    for handle in handles {
+        let (name, values) = handle.join().unwrap();
+        match &*name {
+            grouped::DEFAULT_GROUP => default.extend(values),
+            _ => other = values,
+        }
+    }
+
+    // Both producers' values are in order, and the sole receiver of the
+    // other group sees them interleaved in the order they were sent.
+    let first = other
+        .iter()
+        .filter(|&&v| v < 100)
+        .copied()
+        .collect::<Vec<_>>();
+    assert_eq!(first, (0..100).collect::<Vec<_>>());
+    default.sort();
+    let mut sorted = other.clone();
+    sorted.sort();
+    assert_eq!(default, sorted);
+    assert_eq!(sorted, (0..200).collect::<Vec<_>>());
+}
+
+#[test]
+fn test_grouped_hang_up() {
+    let (sender, receiver) = grouped::channel::<u32>(1);
+    let other = receiver.group("other");
+
+    // Groups without receivers are left out.
+    sender.try_send(1).unwrap();
+    assert_eq!(sender.try_send(2).unwrap_err().1, ErrorCause::WouldBlock);
+    drop(other);
+    assert_eq!(receiver.recv().unwrap(), 1);
+    sender.try_send(2).unwrap();
+    assert_eq!(receiver.recv().unwrap(), 2);
+
+    // Rejoining picks up the values left behind.
+    let other = receiver.group("other");
+    assert_eq!(other.try_recv().unwrap(), 1);
+
+    drop(receiver);
+    drop(other);
+    assert_eq!(sender.send(3).unwrap_err().1, ErrorCause::HungUp);
+
+    let receiver = {
+        let (sender, receiver) = grouped::channel::<u32>(1);
+        sender.send(1).unwrap();
+        receiver
+    };
+    assert_eq!(receiver.recv().unwrap(), 1);
+    assert_eq!(receiver.recv().unwrap_err().0, ErrorCause::HungUp);
+    assert_eq!(
+        receiver.group("late").recv().unwrap_err().0,
+        ErrorCause::HungUp
+    );
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn test_grouped_try_send_while_sending() {
+    let (sender, receiver) = grouped::channel::<u32>(1);
+    let other = receiver.group("other");
+    sender.send(1).unwrap();
+    assert_eq!(receiver.recv().unwrap(), 1);
+
+    // Parks on the other group, which is still full.
+    let handle = {
+        let sender = sender.clone();
+        thread::spawn(move || sender.send(2))
+    };
+    thread::sleep(Duration::from_millis(10));
+
+    // Doesn't wait for the parked send, so ask from another thread to tell
+    // if it hangs.
+    let (tried, result) = std::sync::mpsc::channel();
+    let trying = thread::spawn(move || tried.send(sender.try_send(3)).unwrap());
+    let err = result
+        .recv_timeout(Duration::from_secs(5))
+        .expect("try_send blocked")
+        .unwrap_err();
+    assert_eq!(err, SendError(3, ErrorCause::WouldBlock));
+    trying.join().unwrap();
+
+    assert_eq!(other.recv().unwrap(), 1);
+    handle.join().unwrap().unwrap();
+    assert_eq!(receiver.recv().unwrap(), 2);
+    assert_eq!(other.recv().unwrap(), 2);
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn test_clone_drop_accounting() {
+    use std::panic::{self, AssertUnwindSafe};
+
+    let (sender, receiver) = channel::<u32>(1);
+
+    // Handles dropped while unwinding are uncounted like any other, however
+    // many threads clone and drop them at once.
+    let handles = (0..4)
+        .map(|_| {
+            let (sender, receiver) = (sender.clone(), receiver.clone());
+            thread::spawn(move || {
+                for i in 0..1000 {
+                    let result = panic::catch_unwind(AssertUnwindSafe(|| {
+                        let clones = (sender.clone(), receiver.clone(), sender.clone());
+                        if i % 2 == 0 {
+                            // Unwinds without running the panic hook.
+                            panic::resume_unwind(Box::new(()));
+                        }
+                        drop(clones);
+                    }));
+                    assert_eq!(result.is_err(), i % 2 == 0);
+                }
+            })
+        })
+        .collect::<Vec<_>>();
+    for handle in handles {
+        handle.join().unwrap();
+    }
+
+    assert_eq!(sender.0.senders.load(Ordering::Relaxed), 1);
+    assert_eq!(sender.0.receivers.load(Ordering::Relaxed), 1);
+
+    drop(receiver);
+    assert_eq!(sender.send(1).unwrap_err().1, ErrorCause::HungUp);
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn test_bridge_from_std() {
+    let (sender, receiver) = std::sync::mpsc::channel::<u32>();
+    let receiver = mpsc::bridge_from_std(receiver, 2);
+
+    let handle = thread::spawn(move || {
+        for i in 0..100 {
+            sender.send(i).unwrap();
+        }
+    });
+
+    // Hangs up once the std side does.
+    assert_eq!(
+        receiver.iter().collect::<Vec<_>>(),
+        (0..100).collect::<Vec<_>>()
+    );
+    assert_eq!(receiver.recv().unwrap_err().0, ErrorCause::HungUp);
+    handle.join().unwrap();
+
+    // The bridge gives up once nobody receives anymore.
+    let (sender, receiver) = std::sync::mpsc::channel::<u32>();
+    drop(mpsc::bridge_from_std(receiver, 1));
+    sender.send(1).unwrap();
+    let start = Instant::now();
+    while sender.send(2).is_ok() {
+        assert!(start.elapsed() < Duration::from_secs(5));
+        thread::sleep(Duration::from_millis(1));
+    }
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn test_multi_consumer_exactly_once() {
+    const PRODUCERS: u32 = 4;
+    const VALUES: u32 = 10_000;
+
+    let (sender, receiver) = channel::<u32>(8);
+
+    let producers = (0..PRODUCERS)
+        .map(|p| {
+            let sender = sender.clone();
+            thread::spawn(move || {
+                for i in 0..VALUES {
+                    sender.send(p * VALUES + i).unwrap();
+                }
+            })
+        })
+        .collect::<Vec<_>>();
+    drop(sender);
+
+    // Mix blocking and non-blocking receives, which claim the same way.
+    let consumers = (0..4)
+        .map(|c| {
+            let receiver = receiver.clone();
+            thread::spawn(move || {
+                let mut received = Vec::new();
+                loop {
+                    let result = if c % 2 == 0 {
+                        receiver.recv()
+                    } else {
+                        receiver.try_recv()
+                    };
+                    match result {
+                        Ok(value) => received.push(value),
+                        Err(RecvError(ErrorCause::WouldBlock)) => thread::yield_now(),
+                        Err(_) => return received,
+                    }
+                }
+            })
+        })
+        .collect::<Vec<_>>();
+    drop(receiver);
+
+    for producer in producers {
+        producer.join().unwrap();
+    }
+
+    let mut seen = vec![false; (PRODUCERS * VALUES) as usize];
+    for consumer in consumers {
+        let received = consumer.join().unwrap();
+
+        // Each consumer sees every producer's values in order.
+        for p in 0..PRODUCERS {
+            let own = received
+                .iter()
+                .filter(|&&v| v / VALUES == p)
+                .collect::<Vec<_>>();
+            assert!(own.windows(2).all(|w| w[0] < w[1]));
+        }
+
+        for value in received {
+            assert!(!seen[value as usize], "{value} received twice");
+            seen[value as usize] = true;
+        }
+    }
+    assert!(seen.iter().all(|&seen| seen), "values were lost");
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn test_try_send_once_contended() {
+    let (sender, receiver) = channel::<u32>(1024);
+    let contended = Arc::new(AtomicUsize::new(0));
+    let start = Instant::now();
+
+    // Hammer the write position until some sender loses a race.
+    let handles = (0..8)
+        .map(|_| {
+            let sender = sender.clone();
+            let contended = contended.clone();
+            thread::spawn(move || {
+                let mut sent = 0;
+                while contended.load(Ordering::Relaxed) == 0
+                    && start.elapsed() < Duration::from_secs(30)
+                {
+                    match sender.try_send_once(1) {
+                        Ok(()) => sent += 1,
+                        Err(SendError(_, ErrorCause::Contended)) => {
+                            contended.fetch_add(1, Ordering::Relaxed);
+                        }
+                        Err(SendError(_, ErrorCause::WouldBlock)) => thread::yield_now(),
+                        Err(err) => panic!("{err}"),
+                    }
+                }
+                sent
+            })
+        })
+        .collect::<Vec<_>>();
+    drop(sender);
+
+    let received = receiver.iter().sum::<u32>();
+    let sent = handles.into_iter().map(|h| h.join().unwrap()).sum::<u32>();
+    assert!(contended.load(Ordering::Relaxed) > 0);
+
+    // Giving up sends nothing.
+    assert_eq!(received, sent);
+}
+
+#[test]
+fn test_select_timeout() {
+    let (_first_sender, first_receiver) = channel::<u32>(1);
+    let (_second_sender, second_receiver) = channel::<u32>(1);
+
+    let mut select = Select::new();
+    select.recv(&first_receiver);
+    select.recv(&second_receiver);
+
+    let start = Instant::now();
+    assert!(select.select_timeout(Duration::from_millis(10)).is_none());
+    assert!(start.elapsed() >= Duration::from_millis(10));
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn test_select_wakes() {
+    let (first_sender, first_receiver) = channel::<u32>(1);
+    let (second_sender, second_receiver) = channel::<u32>(1);
+
+    let mut select = Select::new();
+    let first = select.recv(&first_receiver);
+    let second = select.recv(&second_receiver);
+
+    // Sent while parked.
+    let handle = {
+        let second_sender = second_sender.clone();
+        thread::spawn(move || {
+            thread::sleep(Duration::from_millis(10));
+            second_sender.send(2).unwrap();
+        })
+    };
+    let selected = select.select_timeout(Duration::from_secs(10));
+    assert_eq!(selected, Some((second, Ok(2))));
+    handle.join().unwrap();
+
+    // Hung up while parked, which it reports, and keeps reporting.
+    let handle = thread::spawn(move || {
+        thread::sleep(Duration::from_millis(10));
+        drop(first_sender);
+    });
+    let selected = select.select_timeout(Duration::from_secs(10));
+    assert_eq!(selected, Some((first, Err(RecvError(ErrorCause::HungUp)))));
+    handle.join().unwrap();
+    assert_eq!(select.select().0, first);
+}
+
+#[test]
+fn test_select_round_robin() {
+    let (first_sender, first_receiver) = channel::<u32>(2);
+    let (second_sender, second_receiver) = channel::<u32>(2);
+    first_sender.send_all([1, 2]).unwrap();
+    second_sender.send_all([3, 4]).unwrap();
+
+    let mut select = Select::new();
+    select.recv(&first_receiver);
+    select.recv(&second_receiver);
+
+    // Both stay ready, and take turns.
+    let selected = (0..4)
+        .map(|_| select.try_select().unwrap())
+        .collect::<Vec<_>>();
+    assert_eq!(selected, [(0, Ok(1)), (1, Ok(3)), (0, Ok(2)), (1, Ok(4))]);
+    assert!(select.try_select().is_none());
+}
@@ -0,0 +1,208 @@
+//! Model checks the channel with `loom`, which runs every test under all the
+//! interleavings of its atomic operations that can tell apart, within a
+//! bound on preemptions.
+//!
+//! Run them with:
+//!
+//! ```sh
+//! LOOM_MAX_PREEMPTIONS=2 RUSTFLAGS="--cfg loom" cargo test --lib --release
+//! ```
+//!
+//! Add `--features seqcst` to check the channel with every ordering upgraded
+//! to `SeqCst` as well. With `--cfg loom`, the locks blocked threads park on
+//! are `loom`'s, so it sees them block and checks the wakeups, and so is the
+//! cell holding each value, so it checks that no access to one races with
+//! another.
+//!
+//! Threads spinning on non-blocking calls can't be told apart from ones
+//! making progress, so `loom` takes turns between them forever. The tests
+//! only ever let one thread retry.
+
+use loom::{cell::UnsafeCell, sync::Arc, thread};
+
+use super::*;
+
+/// Large enough that a torn value would show up as differing elements.
+type Value = [usize; 4];
+
+fn try_recv_all(receiver: &Receiver<Value>) -> Vec<usize> {
+    let mut received = Vec::new();
+    loop {
+        match receiver.try_recv() {
+            Ok(value) => {
+                assert!(value.iter().all(|v| *v == value[0]), "torn value");
+                received.push(value[0]);
+            }
+            Err(RecvError(ErrorCause::WouldBlock)) => thread::yield_now(),
+            Err(RecvError(ErrorCause::HungUp)) => return received,
+            Err(err) => panic!("receive failed: {err}"),
+        }
+    }
+}
+
+fn recv_all(receiver: &Receiver<Value>) -> Vec<usize> {
+    let mut received = Vec::new();
+    loop {
+        match receiver.recv() {
+            Ok(value) => {
+                assert!(value.iter().all(|v| *v == value[0]), "torn value");
+                received.push(value[0]);
+            }
+            Err(RecvError(ErrorCause::HungUp)) => return received,
+            Err(err) => panic!("receive failed: {err}"),
+        }
+    }
+}
+
+#[test]
+fn test_loom_two_producers() {
+    loom::model(|| {
+        // Room for both values, so only the consumer has to retry.
+        let (sender, receiver) = channel::<Value>(2);
+
+        let producers = (0..2)
+            .map(|producer| {
+                let sender = sender.clone();
+                thread::spawn(move || sender.try_send([producer; 4]).unwrap())
+            })
+            .collect::<Vec<_>>();
+        drop(sender);
+
+        let mut received = try_recv_all(&receiver);
+        for producer in producers {
+            producer.join().unwrap();
+        }
+
+        // Nothing lost, and nothing received twice.
+        received.sort_unstable();
+        assert_eq!(received, [0, 1]);
+    });
+}
+
+#[test]
+fn test_loom_two_consumers() {
+    loom::model(|| {
+        // Everything is sent up front, so the consumers never have to wait
+        // for a value, only race each other for them.
+        let (sender, receiver) = channel::<Value>(2);
+        for value in 0..2 {
+            sender.try_send([value; 4]).unwrap();
+        }
+        drop(sender);
+
+        let consumer = {
+            let receiver = receiver.clone();
+            thread::spawn(move || try_recv_all(&receiver))
+        };
+        let mut received = try_recv_all(&receiver);
+        received.extend(consumer.join().unwrap());
+
+        received.sort_unstable();
+        assert_eq!(received, [0, 1]);
+    });
+}
+
+#[test]
+fn test_loom_wraparound() {
+    loom::model(|| {
+        // More values than nodes, so the nodes are reused while contended.
+        let (sender, receiver) = channel::<Value>(1);
+
+        let producer = thread::spawn(move || {
+            for value in 0..2 {
+                sender.send([value; 4]).unwrap();
+            }
+        });
+        let received = recv_all(&receiver);
+        producer.join().unwrap();
+
+        assert_eq!(received, [0, 1]);
+    });
+}
+
+#[test]
+fn test_loom_blocking_two_consumers() {
+    loom::model(|| {
+        let (sender, receiver) = channel::<Value>(1);
+
+        // Both consumers may park before anything is sent, and each value
+        // must wake one of them.
+        let consumer = {
+            let receiver = receiver.clone();
+            thread::spawn(move || recv_all(&receiver))
+        };
+        let producer = thread::spawn(move || {
+            for value in 0..2 {
+                sender.send([value; 4]).unwrap();
+            }
+        });
+        let mut received = recv_all(&receiver);
+
+        producer.join().unwrap();
+        received.extend(consumer.join().unwrap());
+
+        received.sort_unstable();
+        assert_eq!(received, [0, 1]);
+    });
+}
+
+#[test]
+fn test_loom_blocking_send_hang_up() {
+    loom::model(|| {
+        let (sender, receiver) = channel::<Value>(1);
+        sender.send([0; 4]).unwrap();
+
+        // The sender may park on the full channel before the hang up, which
+        // must then wake it.
+        let producer = thread::spawn(move || sender.send([1; 4]));
+        drop(receiver);
+
+        let err = producer.join().unwrap().unwrap_err();
+        assert_eq!(err, SendError([1; 4], ErrorCause::HungUp));
+    });
+}
+
+#[test]
+fn test_loom_happens_before() {
+    loom::model(|| {
+        let (sender, receiver) = channel::<()>(1);
+        let data = Arc::new(UnsafeCell::new(0));
+
+        // The send orders the write before the receive, like a lock would,
+        // so `loom` sees no race on the data.
+        let producer = {
+            let data = data.clone();
+            thread::spawn(move || {
+                data.with_mut(|data| unsafe { *data = 1 });
+                sender.send(()).unwrap();
+            })
+        };
+        receiver.recv().unwrap();
+        assert_eq!(data.with(|data| unsafe { *data }), 1);
+
+        producer.join().unwrap();
+    });
+}
+
+/// Each thread sends on its own channel and then looks at the other. Setting
+/// the waiter after a send fences with `SeqCst`, so whichever thread sends
+/// last sees the other value, with the default orderings or with `seqcst`.
+#[test]
+fn test_loom_total_order() {
+    loom::model(|| {
+        let (first_sender, first_receiver) = channel::<()>(1);
+        let (second_sender, second_receiver) = channel::<()>(1);
+
+        // The handles are given back, so no one hangs up in between.
+        let other = thread::spawn(move || {
+            second_sender.try_send(()).unwrap();
+            let seen = first_receiver.try_recv().is_ok();
+            (seen, second_sender, first_receiver)
+        });
+        first_sender.try_send(()).unwrap();
+        let seen = second_receiver.try_recv().is_ok();
+
+        let (other_seen, ..) = other.join().unwrap();
+        assert!(seen || other_seen, "both sends were missed");
+    });
+}
@@ -0,0 +1,71 @@
+//! Smoke tests for the build without the `std` feature, where the channel
+//! runs on spin locks and spinning waiters.
+
+use std::thread;
+
+use super::*;
+
+#[test]
+fn test_no_std_read_and_write() {
+    let (sender, receiver) = channel::<Vec<u32>>(2);
+
+    sender.send(vec![1, 2, 3]).unwrap();
+    sender.try_send(vec![4, 5, 6]).unwrap();
+    assert_eq!(
+        sender.try_send(vec![7]).unwrap_err().1,
+        ErrorCause::WouldBlock
+    );
+
+    assert_eq!(receiver.recv().unwrap(), vec![1, 2, 3]);
+    assert_eq!(receiver.try_recv().unwrap(), vec![4, 5, 6]);
+    assert_eq!(receiver.try_recv().unwrap_err().0, ErrorCause::WouldBlock);
+
+    drop(sender);
+    assert_eq!(receiver.recv().unwrap_err().0, ErrorCause::HungUp);
+}
+
+#[test]
+fn test_no_std_close_with() {
+    let (sender, receiver) = channel::<u32>(1);
+
+    sender.close_with("done");
+    receiver.close_with("ignored");
+
+    let closed = ErrorCause::Closed("done".into());
+    assert_eq!(sender.send(1).unwrap_err().1, closed);
+    assert_eq!(receiver.recv().unwrap_err().0, closed);
+}
+
+#[test]
+fn test_no_std_grow() {
+    let (sender, receiver) = ChannelBuilder::new(1).growable(true).build::<u32>();
+
+    sender.send(0).unwrap();
+    sender.try_grow(1).unwrap();
+    sender.send(1).unwrap();
+
+    assert_eq!(receiver.try_iter().collect::<Vec<_>>(), [0, 1]);
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn test_no_std_blocking() {
+    let (sender, receiver) = ChannelBuilder::new(1).fair(true).build::<u32>();
+
+    let handles = (0..2)
+        .map(|_| {
+            let sender = sender.clone();
+            thread::spawn(move || {
+                for i in 0..100 {
+                    sender.send(i).unwrap();
+                }
+            })
+        })
+        .collect::<Vec<_>>();
+    drop(sender);
+
+    assert_eq!(receiver.iter().sum::<u32>(), 2 * (0..100).sum::<u32>());
+    for handle in handles {
+        handle.join().unwrap();
+    }
+}
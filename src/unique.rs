@@ -0,0 +1,175 @@
+#[cfg(feature = "std")]
+use std::time::Duration;
+
+#[cfg(feature = "std")]
+use crate::RecvTimeoutError;
+use crate::{Iter, Receiver, RecvError, SendError, Sender, TryIter};
+
+/// The sending half of a single-producer channel, created by
+/// [`channel_single`](crate::channel_single).
+///
+/// Unlike [`Sender`], it can't be cloned:
+/// ```compile_fail
+/// use atomic_mpmc::channel_single;
+///
+/// let (sender, receiver) = channel_single::<i32>(1);
+/// let other = sender.clone();
+/// ```
+#[derive(Debug)]
+pub struct UniqueSender<T>(Sender<T>);
+
+impl<T> UniqueSender<T> {
+    pub(crate) fn new(sender: Sender<T>) -> Self {
+        Self(sender)
+    }
+
+    /// Send a value to the channel. This function will block the current thread
+    /// if the channel is full.
+    ///
+    /// # Examples
+    /// ```
+    /// use atomic_mpmc::channel_single;
+    ///
+    /// let (mut sender, mut receiver) = channel_single::<i32>(1);
+    ///
+    /// sender.send(1).unwrap();
+    /// ```
+    pub fn send(&mut self, value: T) -> Result<(), SendError<T>> {
+        self.0.send(value)
+    }
+
+    /// Attempt to send a value to the channel. This function will return
+    /// `Err(SendError(value, ErrorCause::WouldBlock))` if the channel is full.
+    ///
+    /// # Examples
+    /// ```
+    /// use atomic_mpmc::channel_single;
+    ///
+    /// let (mut sender, mut receiver) = channel_single::<i32>(1);
+    ///
+    /// sender.send(1).unwrap();
+    /// sender.try_send(2).unwrap_err();
+    /// ```
+    pub fn try_send(&mut self, value: T) -> Result<(), SendError<T>> {
+        self.0.try_send(value)
+    }
+
+    /// Send every value of an iterator to the channel, blocking as needed.
+    /// For more information, see [`Sender::send_all`].
+    ///
+    /// # Examples
+    /// ```
+    /// use atomic_mpmc::channel_single;
+    ///
+    /// let (mut sender, mut receiver) = channel_single::<i32>(3);
+    ///
+    /// assert_eq!(sender.send_all([1, 2, 3]).unwrap(), 3);
+    /// ```
+    pub fn send_all<I: IntoIterator<Item = T>>(
+        &mut self,
+        iter: I,
+    ) -> Result<usize, (usize, SendError<T>)> {
+        self.0.send_all(iter)
+    }
+
+    /// Returns `true` if the receiver has hung up.
+    /// For more information, see [`Sender::is_disconnected`].
+    pub fn is_disconnected(&self) -> bool {
+        self.0.is_disconnected()
+    }
+}
+
+/// The receiving half of a single-consumer channel, created by
+/// [`channel_single`](crate::channel_single).
+///
+/// Unlike [`Receiver`], it can't be cloned:
+/// ```compile_fail
+/// use atomic_mpmc::channel_single;
+///
+/// let (sender, receiver) = channel_single::<i32>(1);
+/// let other = receiver.clone();
+/// ```
+#[derive(Debug)]
+pub struct UniqueReceiver<T>(Receiver<T>);
+
+impl<T> UniqueReceiver<T> {
+    pub(crate) fn new(receiver: Receiver<T>) -> Self {
+        Self(receiver)
+    }
+
+    /// Receive a value from the channel. This function will block the current
+    /// thread if the channel is empty.
+    ///
+    /// # Examples
+    /// ```
+    /// use atomic_mpmc::channel_single;
+    ///
+    /// let (mut sender, mut receiver) = channel_single::<i32>(1);
+    ///
+    /// sender.send(1).unwrap();
+    /// assert_eq!(receiver.recv().unwrap(), 1);
+    /// ```
+    pub fn recv(&mut self) -> Result<T, RecvError> {
+        self.0.recv()
+    }
+
+    /// Attempt to receive a value from the channel. This function will return
+    /// `Err(RecvError(ErrorCause::WouldBlock))` if the channel is empty.
+    ///
+    /// # Examples
+    /// ```
+    /// use atomic_mpmc::channel_single;
+    ///
+    /// let (mut sender, mut receiver) = channel_single::<i32>(1);
+    ///
+    /// receiver.try_recv().unwrap_err();
+    /// ```
+    pub fn try_recv(&mut self) -> Result<T, RecvError> {
+        self.0.try_recv()
+    }
+
+    /// Receive a value from the channel, blocking for at most `timeout`.
+    /// For more information, see [`Receiver::recv_timeout`].
+    ///
+    /// # Examples
+    /// ```
+    /// use atomic_mpmc::{channel_single, RecvTimeoutError};
+    /// use std::time::Duration;
+    ///
+    /// let (mut sender, mut receiver) = channel_single::<i32>(1);
+    ///
+    /// let err = receiver.recv_timeout(Duration::from_millis(10)).unwrap_err();
+    /// assert_eq!(err, RecvTimeoutError::Timeout);
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn recv_timeout(&mut self, timeout: Duration) -> Result<T, RecvTimeoutError> {
+        self.0.recv_timeout(timeout)
+    }
+
+    /// Returns `true` if the sender has hung up.
+    /// For more information, see [`Receiver::is_disconnected`].
+    pub fn is_disconnected(&self) -> bool {
+        self.0.is_disconnected()
+    }
+
+    /// Returns an iterator over the values of the channel, blocking between
+    /// them. For more information, see [`Receiver::iter`].
+    pub fn iter(&mut self) -> Iter<&Receiver<T>> {
+        self.0.iter()
+    }
+
+    /// Returns an iterator over the pending values of the channel.
+    /// For more information, see [`Receiver::try_iter`].
+    pub fn try_iter(&mut self) -> TryIter<&Receiver<T>> {
+        self.0.try_iter()
+    }
+}
+
+impl<T> IntoIterator for UniqueReceiver<T> {
+    type Item = T;
+    type IntoIter = Iter<Receiver<T>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.into_iter()
+    }
+}
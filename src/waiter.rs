@@ -1,32 +1,399 @@
-use parking_lot::{Condvar, Mutex};
+use alloc::boxed::Box;
+#[cfg(feature = "std")]
+use alloc::vec::Vec;
+#[cfg(feature = "std")]
+use core::task::Waker;
+use core::{fmt, hint};
+#[cfg(feature = "std")]
+use std::{thread, time::Instant};
 
+use crate::sync::{
+    atomic::{fence, AtomicBool, AtomicUsize},
+    Ordering,
+};
+#[cfg(feature = "std")]
+use crate::sync::{Condvar, Mutex};
+
+/// The point in time a wait gives up at. This is [`std::time::Instant`] with
+/// the `std` feature. Without it there is no clock, so this type has no
+/// values, and waits never time out.
+#[cfg(feature = "std")]
+pub type Deadline = Instant;
+
+/// The point in time a wait gives up at. This is [`std::time::Instant`] with
+/// the `std` feature. Without it there is no clock, so this type has no
+/// values, and waits never time out.
+#[cfg(not(feature = "std"))]
+pub type Deadline = core::convert::Infallible;
+
+/// Whether the deadline, if any, has passed.
+#[inline(always)]
+pub(crate) fn passed(deadline: Option<Deadline>) -> bool {
+    #[cfg(feature = "std")]
+    return deadline.is_some_and(|deadline| deadline <= Instant::now());
+
+    #[cfg(not(feature = "std"))]
+    match deadline {
+        Some(never) => match never {},
+        None => false,
+    }
+}
+
+/// How threads wait for a channel to become readable or writable, set with
+/// [`ChannelBuilder::wait_strategy`](crate::ChannelBuilder::wait_strategy).
+///
+/// A strategy is a flag which blocked threads wait on. Each channel creates
+/// two of them, one for each direction.
+///
+/// Implementations must order memory like a lock would: whatever a thread
+/// did before [`WaitStrategy::signal`] must be visible to a thread returning
+/// from [`WaitStrategy::wait`], and a thread calling [`WaitStrategy::reset`]
+/// must see everything done before a signal it overwrites.
+pub trait WaitStrategy: fmt::Debug + Send + Sync {
+    /// Creates the flag, signalled or not.
+    fn new(signalled: bool) -> Self
+    where
+        Self: Sized;
+
+    /// Blocks until the flag is signalled, or the deadline, if any, passes.
+    /// Returns whether the flag was signalled. It is fine to return early,
+    /// as callers check again what they were waiting for.
+    fn wait(&self, deadline: Option<Deadline>) -> bool;
+
+    /// Signals the flag, waking up everyone waiting on it.
+    fn signal(&self);
+
+    /// Clears the flag, so the next wait blocks until it is signalled again.
+    fn reset(&self);
+}
+
+/// Creates the strategy channels use unless told otherwise, see
+/// [`ChannelBuilder::wait_strategy`](crate::ChannelBuilder::wait_strategy).
+pub(crate) fn default_strategy(signalled: bool) -> Box<dyn WaitStrategy> {
+    #[cfg(feature = "std")]
+    return Box::new(BlockWait::new(signalled));
+
+    #[cfg(not(feature = "std"))]
+    Box::new(SpinWait::new(signalled))
+}
+
+/// Parks blocked threads until they are woken up. This is the default, and
+/// the best choice unless latency matters more than CPU time. Only available
+/// with the `std` feature.
+#[cfg(feature = "std")]
 #[derive(Debug)]
-pub(crate) struct Waiter {
+pub struct BlockWait {
     mutex: Mutex<bool>,
     condvar: Condvar,
 }
 
-impl Waiter {
-    pub fn new(value: bool) -> Self {
+#[cfg(feature = "std")]
+impl WaitStrategy for BlockWait {
+    fn new(signalled: bool) -> Self {
         Self {
-            mutex: Mutex::new(value),
+            mutex: Mutex::new(signalled),
             condvar: Condvar::new(),
         }
     }
 
-    pub fn wait(&self) {
+    fn wait(&self, deadline: Option<Deadline>) -> bool {
         let mut lock = self.mutex.lock();
         while !*lock {
-            self.condvar.wait(&mut lock);
+            match deadline {
+                Some(deadline) => {
+                    if self.condvar.wait_until(&mut lock, deadline).timed_out() {
+                        return *lock;
+                    }
+                }
+                None => self.condvar.wait(&mut lock),
+            }
         }
+        true
+    }
+
+    /// Wakes up everyone, as they may be waiting for different things.
+    fn signal(&self) {
+        *self.mutex.lock() = true;
+        self.condvar.notify_all();
     }
 
-    pub fn reset(&self) {
+    fn reset(&self) {
         *self.mutex.lock() = false;
     }
+}
+
+/// Busy-waits on blocked threads, for the lowest latency. This takes up a
+/// core for every blocked thread, so there should be enough to go around.
+/// Without the `std` feature, there is no way to park threads, so this is
+/// the default.
+#[derive(Debug)]
+pub struct SpinWait(AtomicBool);
+
+impl WaitStrategy for SpinWait {
+    fn new(signalled: bool) -> Self {
+        Self(AtomicBool::new(signalled))
+    }
+
+    fn wait(&self, deadline: Option<Deadline>) -> bool {
+        poll(&self.0, deadline, hint::spin_loop)
+    }
+
+    fn signal(&self) {
+        signal(&self.0)
+    }
+
+    fn reset(&self) {
+        reset(&self.0)
+    }
+}
+
+/// Yields to other threads while blocked, for lower latency than parking
+/// without holding up the threads which could unblock us. Only available
+/// with the `std` feature.
+#[cfg(feature = "std")]
+#[derive(Debug)]
+pub struct YieldWait(AtomicBool);
+
+#[cfg(feature = "std")]
+impl WaitStrategy for YieldWait {
+    fn new(signalled: bool) -> Self {
+        Self(AtomicBool::new(signalled))
+    }
+
+    fn wait(&self, deadline: Option<Deadline>) -> bool {
+        poll(&self.0, deadline, thread::yield_now)
+    }
+
+    fn signal(&self) {
+        signal(&self.0)
+    }
+
+    fn reset(&self) {
+        reset(&self.0)
+    }
+}
+
+/// Polls a flag until it is signalled, or the deadline passes.
+fn poll(flag: &AtomicBool, deadline: Option<Deadline>, relax: fn()) -> bool {
+    while !flag.load(Ordering::Acquire) {
+        if passed(deadline) {
+            return false;
+        }
+        relax();
+    }
+    true
+}
+
+fn signal(flag: &AtomicBool) {
+    // Pairs with the fence in `reset`, so either the reset lands first and is
+    // overwritten here, or the reset thread sees what was done before this.
+    fence(Ordering::SeqCst);
+    flag.store(true, Ordering::Release);
+}
+
+fn reset(flag: &AtomicBool) {
+    flag.store(false, Ordering::Relaxed);
+    fence(Ordering::SeqCst);
+}
+
+/// A [`WaitStrategy`] along with the number of threads waiting on it, so
+/// setting it costs next to nothing while nobody waits.
+///
+/// Waiting goes: [`Waiter::prepare`], check again what to wait for, and only
+/// then [`Sleep::wait`]. Whoever changes what is waited for must set the
+/// waiter afterwards.
+#[derive(Debug)]
+pub(crate) struct Waiter {
+    strategy: Box<dyn WaitStrategy>,
+
+    /// Number of threads between preparing to wait and being done waiting.
+    sleepers: AtomicUsize,
+
+    /// Tasks, or selecting threads, to wake up along with the blocked
+    /// threads.
+    #[cfg(feature = "std")]
+    wakers: Mutex<Vec<Waker>>,
+
+    /// Number of wakers, so setting the waiter skips the lock while there
+    /// are none.
+    #[cfg(feature = "std")]
+    registered: AtomicUsize,
+}
+
+impl Waiter {
+    pub fn new(strategy: Box<dyn WaitStrategy>) -> Self {
+        Self {
+            strategy,
+            sleepers: AtomicUsize::new(0),
+
+            #[cfg(feature = "std")]
+            wakers: Mutex::new(Vec::new()),
+            #[cfg(feature = "std")]
+            registered: AtomicUsize::new(0),
+        }
+    }
+
+    /// Registers a task to be woken up the next time the waiter is set. The
+    /// caller must then check again what it is waiting for, like after
+    /// [`Waiter::prepare`].
+    #[cfg(feature = "std")]
+    pub fn register(&self, waker: &Waker) {
+        {
+            let mut wakers = self.wakers.lock();
+            if !wakers.iter().any(|w| w.will_wake(waker)) {
+                wakers.push(waker.clone());
+            }
+            self.registered.store(wakers.len(), Ordering::Relaxed);
+        }
+
+        // Pairs with the fence in `set`, so either the check sees the
+        // change, or the setter sees the waker.
+        fence(Ordering::SeqCst);
+    }
+
+    #[cfg(feature = "std")]
+    fn wake(&self) {
+        if self.registered.load(Ordering::Relaxed) == 0 {
+            return;
+        }
+
+        let wakers = {
+            let mut wakers = self.wakers.lock();
+            self.registered.store(0, Ordering::Relaxed);
+            core::mem::take(&mut *wakers)
+        };
+        for waker in wakers {
+            waker.wake();
+        }
+    }
+
+    /// Counts the caller as waiting and resets the waiter. The caller must
+    /// then check again what it is waiting for, so a change in between is
+    /// either seen by the check or sets the waiter.
+    pub fn prepare(&self) -> Sleep<'_> {
+        self.sleepers.fetch_add(1, Ordering::Relaxed);
+        self.strategy.reset();
+
+        // Pairs with the fence in `set`, so either the check sees the
+        // change, or the setter sees us waiting.
+        fence(Ordering::SeqCst);
+
+        Sleep {
+            waiter: self,
+            waited: false,
+        }
+    }
+
+    /// Blocks until `ready` returns `true`. Whoever makes it return `true`
+    /// must set the waiter afterwards.
+    pub fn wait_for(&self, ready: impl Fn() -> bool) {
+        while !ready() {
+            let sleep = self.prepare();
+            if ready() {
+                return;
+            }
+
+            sleep.wait();
+        }
+    }
+
+    /// Number of threads waiting, or about to.
+    pub fn sleepers(&self) -> usize {
+        self.sleepers.load(Ordering::Relaxed)
+    }
 
     pub fn set(&self) {
-        *self.mutex.lock() = true;
-        self.condvar.notify_one();
+        fence(Ordering::SeqCst);
+        if self.sleepers.load(Ordering::Relaxed) > 0 {
+            self.strategy.signal();
+        }
+
+        #[cfg(feature = "std")]
+        self.wake();
+    }
+}
+
+/// A thread about to wait on a [`Waiter`], created by [`Waiter::prepare`].
+pub(crate) struct Sleep<'a> {
+    waiter: &'a Waiter,
+    waited: bool,
+}
+
+impl Sleep<'_> {
+    pub fn wait(mut self) {
+        self.waited = true;
+        self.waiter.strategy.wait(None);
+    }
+
+    /// Returns `false` if the deadline passed before the waiter was set.
+    #[cfg(feature = "std")]
+    pub fn wait_until(mut self, deadline: Deadline) -> bool {
+        self.waited = true;
+        self.waiter.strategy.wait(Some(deadline))
+    }
+}
+
+impl Drop for Sleep<'_> {
+    fn drop(&mut self) {
+        let others = self.waiter.sleepers.fetch_sub(1, Ordering::Relaxed) - 1;
+
+        // Preparing reset the waiter, maybe right after it was set for
+        // someone else who has yet to notice.
+        if !self.waited && others > 0 {
+            self.waiter.strategy.signal();
+        }
+    }
+}
+
+/// A ticket lock, letting threads take turns in the order they arrived.
+#[derive(Debug, Default)]
+pub(crate) struct Turnstile {
+    next: AtomicUsize,
+    serving: AtomicUsize,
+    #[cfg(feature = "std")]
+    mutex: Mutex<()>,
+    #[cfg(feature = "std")]
+    condvar: Condvar,
+}
+
+impl Turnstile {
+    /// Blocks until it is the caller's turn. The turn ends when the returned
+    /// guard is dropped.
+    pub fn enter(&self) -> Turn<'_> {
+        let ticket = self.next.fetch_add(1, Ordering::Relaxed);
+
+        #[cfg(feature = "std")]
+        if self.serving.load(Ordering::Acquire) != ticket {
+            let mut lock = self.mutex.lock();
+            while self.serving.load(Ordering::Acquire) != ticket {
+                self.condvar.wait(&mut lock);
+            }
+        }
+
+        #[cfg(not(feature = "std"))]
+        while self.serving.load(Ordering::Acquire) != ticket {
+            hint::spin_loop();
+        }
+
+        Turn(self)
+    }
+
+    /// Whether any thread holds or is waiting for a turn.
+    pub fn is_busy(&self) -> bool {
+        self.next.load(Ordering::Relaxed) != self.serving.load(Ordering::Relaxed)
+    }
+}
+
+pub(crate) struct Turn<'a>(&'a Turnstile);
+
+impl Drop for Turn<'_> {
+    fn drop(&mut self) {
+        self.0.serving.fetch_add(1, Ordering::Release);
+
+        #[cfg(feature = "std")]
+        {
+            let _lock = self.0.mutex.lock();
+            self.0.condvar.notify_all();
+        }
     }
 }
@@ -0,0 +1,84 @@
+use alloc::sync::Arc;
+use core::fmt;
+
+use crate::sync::{atomic::AtomicBool, Ordering};
+
+/// Called with the channel's length when it crosses a watermark.
+pub(crate) type Callback = Arc<dyn Fn(usize) + Send + Sync>;
+
+/// Callbacks for when a channel fills up past a high watermark, and then
+/// drains down to a low one, set with
+/// [`ChannelBuilder::on_high_watermark`](crate::ChannelBuilder::on_high_watermark)
+/// and [`ChannelBuilder::on_low_watermark`](crate::ChannelBuilder::on_low_watermark).
+#[derive(Clone, Default)]
+pub(crate) struct Watermarks {
+    pub(crate) high: Option<(usize, Callback)>,
+    pub(crate) low: Option<(usize, Callback)>,
+}
+
+impl Watermarks {
+    fn is_high(&self, len: usize) -> bool {
+        match (&self.high, &self.low) {
+            (Some((high, _)), _) => len >= *high,
+            (None, Some((low, _))) => len > *low,
+            (None, None) => false,
+        }
+    }
+
+    fn is_low(&self, len: usize) -> bool {
+        match (&self.low, &self.high) {
+            (Some((low, _)), _) => len <= *low,
+            (None, Some((high, _))) => len < *high,
+            (None, None) => false,
+        }
+    }
+}
+
+impl fmt::Debug for Watermarks {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Watermarks")
+            .field("high", &self.high.as_ref().map(|(level, _)| level))
+            .field("low", &self.low.as_ref().map(|(level, _)| level))
+            .finish()
+    }
+}
+
+/// The watermarks of a channel, along with which one it crossed last.
+#[derive(Debug)]
+pub(crate) struct WatermarkState {
+    watermarks: Watermarks,
+    high: AtomicBool,
+}
+
+impl WatermarkState {
+    pub fn new(watermarks: Watermarks) -> Option<Self> {
+        if watermarks.high.is_none() && watermarks.low.is_none() {
+            return None;
+        }
+
+        Some(Self {
+            watermarks,
+            high: AtomicBool::new(false),
+        })
+    }
+
+    /// Checks the length after a write, calling the high watermark's
+    /// callback if it was just reached.
+    pub fn written(&self, len: usize) {
+        if self.watermarks.is_high(len) && !self.high.swap(true, Ordering::AcqRel) {
+            if let Some((_, callback)) = &self.watermarks.high {
+                callback(len);
+            }
+        }
+    }
+
+    /// Checks the length after a read, calling the low watermark's callback
+    /// if it was just reached since the high one was.
+    pub fn read(&self, len: usize) {
+        if self.watermarks.is_low(len) && self.high.swap(false, Ordering::AcqRel) {
+            if let Some((_, callback)) = &self.watermarks.low {
+                callback(len);
+            }
+        }
+    }
+}